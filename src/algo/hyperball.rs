@@ -0,0 +1,693 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! HyperBall: approximate neighborhood function and distance-based
+//! centralities.
+//!
+//! An implementation of the _HyperBall_ algorithm described by Paolo Boldi
+//! and Sebastiano Vigna in “In-core computation of geometric centralities
+//! with HyperBall: A hundred billion nodes and beyond”, _Proc. of 2013 IEEE
+//! 13th International Conference on Data Mining Workshops (ICDMW 2013)_,
+//! IEEE, 2013, which in turn is based on _HyperANF_ (Paolo Boldi, Marco Rosa,
+//! and Sebastiano Vigna, “HyperANF: Approximating the neighbourhood function
+//! of very large graphs on a budget”, _Proceedings of the 20th international
+//! conference on World Wide Web_, pages 625–634, ACM, 2011).
+//!
+//! Each node is associated with a [HyperLogLog counter](HyperLogLogCounterArray)
+//! that, after _t_ iterations, approximates the set of nodes at distance at
+//! most _t_ from the node. At each iteration, the counter of a node is
+//! replaced by the union of its counter with the counters of its successors.
+//! The estimated sizes of the balls make it possible to compute the
+//! neighborhood function of the graph, and, for each node, the sum of the
+//! distances and the sum of the inverse distances to the nodes it can
+//! reach.
+//!
+//! Note that since balls contain the nodes reachable _from_ a node, the
+//! centralities computed by [`HyperBall`] are the classical ones (which are
+//! based on the distances _to_ a node) for the transpose graph. To compute
+//! the centralities of a graph, run [`HyperBall`] on its transpose (and
+//! possibly pass the graph as the transpose of the transpose).
+//!
+//! # Memory requirements
+//!
+//! HyperBall requires two arrays of counters, each using 2<sup>_b_</sup> bytes
+//! per node, where _b_ is the base-2 logarithm of the number of registers per
+//! counter, two booleans per node, and, if centralities are required, two
+//! additional `f64` per node. If a transpose is provided, an additional
+//! boolean per node is necessary.
+
+use crate::traits::*;
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use sux::traits::Succ;
+
+/// An array of HyperLogLog counters.
+///
+/// Each counter contains 2<sup>`log2m`</sup> byte-sized registers. The
+/// relative standard deviation of the estimate of a counter is approximately
+/// 1.04/√2<sup>`log2m`</sup>; [`log2m_for_rsd`](Self::log2m_for_rsd) computes
+/// the number of registers necessary for a given relative standard
+/// deviation.
+///
+/// Elements are `u64` values, which are hashed using a seeded 64-bit mixer, so
+/// counters with different seeds provide independent estimates.
+pub struct HyperLogLogCounterArray {
+    log2m: usize,
+    num_counters: usize,
+    seed: u64,
+    registers: Box<[UnsafeCell<u8>]>,
+}
+
+// Concurrent writes are possible only through the unsafe
+// [`HyperLogLogCounterArray::set_unchecked`] method.
+unsafe impl Send for HyperLogLogCounterArray {}
+unsafe impl Sync for HyperLogLogCounterArray {}
+
+impl HyperLogLogCounterArray {
+    /// Create a new array containing `num_counters` empty counters with
+    /// 2<sup>`log2m`</sup> registers each.
+    ///
+    /// # Panics
+    ///
+    /// If `log2m` is smaller than 4 or larger than 16.
+    pub fn new(num_counters: usize, log2m: usize, seed: u64) -> Self {
+        assert!(
+            (4..=16).contains(&log2m),
+            "The base-2 logarithm of the number of registers must be between 4 and 16 (got {})",
+            log2m
+        );
+        let len = num_counters << log2m;
+        let mut registers = Vec::with_capacity(len);
+        registers.extend((0..len).map(|_| UnsafeCell::new(0)));
+        Self {
+            log2m,
+            num_counters,
+            seed,
+            registers: registers.into_boxed_slice(),
+        }
+    }
+
+    /// Return the base-2 logarithm of the number of registers per counter
+    /// necessary to obtain the given relative standard deviation.
+    pub fn log2m_for_rsd(rsd: f64) -> usize {
+        ((1.04 / rsd).powi(2).log2().ceil() as usize).clamp(4, 16)
+    }
+
+    /// Return the relative standard deviation of the counters of an array
+    /// with 2<sup>`log2m`</sup> registers per counter.
+    pub fn rsd(log2m: usize) -> f64 {
+        1.04 / ((1_usize << log2m) as f64).sqrt()
+    }
+
+    /// Return the number of counters.
+    pub fn num_counters(&self) -> usize {
+        self.num_counters
+    }
+
+    /// Return the base-2 logarithm of the number of registers per counter.
+    pub fn log2m(&self) -> usize {
+        self.log2m
+    }
+
+    /// Clear all counters.
+    pub fn clear(&mut self) {
+        self.registers
+            .par_iter_mut()
+            .with_min_len(1 << 16)
+            .for_each(|r| *r.get_mut() = 0);
+    }
+
+    #[inline(always)]
+    fn all_registers(&self) -> &[u8] {
+        // SAFETY: UnsafeCell<u8> has the same layout as u8
+        unsafe {
+            std::slice::from_raw_parts(self.registers.as_ptr() as *const u8, self.registers.len())
+        }
+    }
+
+    #[inline(always)]
+    fn all_registers_mut(&mut self) -> &mut [u8] {
+        // SAFETY: UnsafeCell<u8> has the same layout as u8
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                self.registers.as_mut_ptr() as *mut u8,
+                self.registers.len(),
+            )
+        }
+    }
+
+    /// Return the registers of a counter.
+    #[inline(always)]
+    pub fn counter(&self, index: usize) -> &[u8] {
+        let m = 1 << self.log2m;
+        &self.all_registers()[index * m..(index + 1) * m]
+    }
+
+    /// Set the registers of a counter.
+    #[inline(always)]
+    pub fn set(&mut self, index: usize, registers: &[u8]) {
+        let m = 1 << self.log2m;
+        self.all_registers_mut()[index * m..(index + 1) * m].copy_from_slice(registers);
+    }
+
+    /// Set the registers of a counter through a shared reference.
+    ///
+    /// # Safety
+    ///
+    /// No other thread must access the same counter during the execution of
+    /// this method.
+    #[inline(always)]
+    unsafe fn set_unchecked(&self, index: usize, registers: &[u8]) {
+        let m = 1 << self.log2m;
+        debug_assert_eq!(registers.len(), m);
+        std::ptr::copy_nonoverlapping(registers.as_ptr(), self.registers[index * m].get(), m);
+    }
+
+    /// Add an element to a counter.
+    pub fn add(&mut self, index: usize, element: u64) {
+        let log2m = self.log2m;
+        let m = 1 << log2m;
+        // The seed is mixed with the element before hashing, so that no small
+        // element (e.g., the one equal to the seed) is mapped to zero
+        let hash = mix64(
+            element
+                .wrapping_add(self.seed)
+                .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+                ^ 0xD6E8_FEB8_6659_FD93,
+        );
+        let register = (hash & (m as u64 - 1)) as usize;
+        // The remaining bits, with a sentinel to bound the number of
+        // trailing zeroes
+        let rest = (hash >> log2m) | (1 << (64 - log2m));
+        let value = rest.trailing_zeros() as u8 + 1;
+        let r = &mut self.all_registers_mut()[index * m + register];
+        *r = (*r).max(value);
+    }
+
+    /// Return the estimate of the number of distinct elements added to a
+    /// counter.
+    pub fn estimate(&self, index: usize) -> f64 {
+        estimate(self.counter(index))
+    }
+}
+
+/// Mix the bits of a 64-bit value (the finalizer of MurmurHash3).
+#[inline(always)]
+fn mix64(mut x: u64) -> u64 {
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    x ^= x >> 33;
+    x
+}
+
+/// Return the HyperLogLog estimate associated with a list of registers.
+fn estimate(registers: &[u8]) -> f64 {
+    let m = registers.len() as f64;
+    let alpha_m_m = m
+        * m
+        * match registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+    let mut sum = 0.0;
+    let mut zeroes = 0;
+    for &r in registers {
+        sum += 1.0 / (1_u64 << r) as f64;
+        zeroes += (r == 0) as usize;
+    }
+    let estimate = alpha_m_m / sum;
+    if zeroes != 0 && estimate < 2.5 * m {
+        // Small-range correction (linear counting)
+        m * (m / zeroes as f64).ln()
+    } else {
+        estimate
+    }
+}
+
+/// Merge `src` into `dst` (i.e., compute the register-wise maximum),
+/// returning whether `dst` changed.
+#[inline(always)]
+fn merge(dst: &mut [u8], src: &[u8]) -> bool {
+    let mut changed = false;
+    for (d, &s) in dst.iter_mut().zip(src) {
+        changed |= s > *d;
+        *d = (*d).max(s);
+    }
+    changed
+}
+
+#[inline(always)]
+fn load_f64(x: &AtomicU64) -> f64 {
+    f64::from_bits(x.load(Ordering::Relaxed))
+}
+
+/// Add to an `f64` stored in an [`AtomicU64`]; this is not an atomic
+/// operation, so there must be a single writer.
+#[inline(always)]
+fn add_f64(x: &AtomicU64, y: f64) {
+    x.store((load_f64(x) + y).to_bits(), Ordering::Relaxed);
+}
+
+/// A builder for [`HyperBall`].
+pub struct HyperBallBuilder<'a, G, T, D> {
+    graph: &'a G,
+    transpose: Option<&'a T>,
+    deg_cumul: &'a D,
+    log2m: usize,
+    seed: u64,
+    granularity: Option<usize>,
+    centralities: bool,
+}
+
+impl<'a, G: RandomAccessGraph + Sync, D: Succ<Input = usize, Output = usize> + Send + Sync>
+    HyperBallBuilder<'a, G, G, D>
+{
+    /// Create a new builder for the given graph and its degree cumulative
+    /// function (see [`par_apply`](SequentialLabeling::par_apply)).
+    ///
+    /// By default, counters have 2<sup>7</sup> registers, the seed is zero,
+    /// centralities are not computed, and no transpose is used.
+    pub fn new(graph: &'a G, deg_cumul: &'a D) -> Self {
+        Self {
+            graph,
+            transpose: None,
+            deg_cumul,
+            log2m: 7,
+            seed: 0,
+            granularity: None,
+            centralities: false,
+        }
+    }
+}
+
+impl<
+        'a,
+        G: RandomAccessGraph + Sync,
+        T: RandomAccessGraph + Sync,
+        D: Succ<Input = usize, Output = usize> + Send + Sync,
+    > HyperBallBuilder<'a, G, T, D>
+{
+    /// Set the transpose of the graph, which will be used to perform
+    /// _systolic_ iterations, in which only the predecessors of nodes whose
+    /// counters changed in the previous iteration are considered.
+    pub fn transpose<U: RandomAccessGraph + Sync>(
+        self,
+        transpose: &'a U,
+    ) -> HyperBallBuilder<'a, G, U, D> {
+        HyperBallBuilder {
+            graph: self.graph,
+            transpose: Some(transpose),
+            deg_cumul: self.deg_cumul,
+            log2m: self.log2m,
+            seed: self.seed,
+            granularity: self.granularity,
+            centralities: self.centralities,
+        }
+    }
+
+    /// Set the base-2 logarithm of the number of registers per counter.
+    pub fn log2m(mut self, log2m: usize) -> Self {
+        self.log2m = log2m;
+        self
+    }
+
+    /// Set the seed used to hash nodes.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the tentative number of arcs processed by each parallel task.
+    ///
+    /// This is an advanced option: see
+    /// [`par_apply`](SequentialLabeling::par_apply).
+    pub fn granularity(mut self, granularity: usize) -> Self {
+        self.granularity = Some(granularity);
+        self
+    }
+
+    /// Set whether to compute, for each node, the sum of distances and the sum
+    /// of inverse distances, from which centralities are derived.
+    pub fn centralities(mut self, centralities: bool) -> Self {
+        self.centralities = centralities;
+        self
+    }
+
+    /// Build a [`HyperBall`] instance.
+    pub fn build(self) -> HyperBall<'a, G, T, D> {
+        let num_nodes = self.graph.num_nodes();
+        let atomic_bools = |value| (0..num_nodes).map(|_| AtomicBool::new(value)).collect();
+        let atomic_f64s = || {
+            (0..num_nodes)
+                .map(|_| AtomicU64::new(0.0_f64.to_bits()))
+                .collect()
+        };
+        HyperBall {
+            graph: self.graph,
+            transpose: self.transpose,
+            deg_cumul: self.deg_cumul,
+            granularity: self
+                .granularity
+                .unwrap_or(((self.graph.num_arcs() >> 9) as usize).max(1024)),
+            curr: HyperLogLogCounterArray::new(num_nodes, self.log2m, self.seed),
+            next: HyperLogLogCounterArray::new(num_nodes, self.log2m, self.seed),
+            modified: atomic_bools(false),
+            next_modified: atomic_bools(false),
+            must_check: if self.transpose.is_some() {
+                atomic_bools(false)
+            } else {
+                Box::default()
+            },
+            sum_of_distances: self.centralities.then(atomic_f64s),
+            sum_of_inverse_distances: self.centralities.then(atomic_f64s),
+            neighborhood_function: vec![],
+            num_modified: 0,
+        }
+    }
+}
+
+/// The HyperBall algorithm.
+///
+/// Instances are created using a [`HyperBallBuilder`]. After calling
+/// [`run`](HyperBall::run) or [`run_until_done`](HyperBall::run_until_done),
+/// the neighborhood function and, if requested, centralities are available.
+pub struct HyperBall<'a, G, T, D> {
+    graph: &'a G,
+    transpose: Option<&'a T>,
+    deg_cumul: &'a D,
+    granularity: usize,
+    /// The counters at the current iteration.
+    curr: HyperLogLogCounterArray,
+    /// The counters at the next iteration.
+    next: HyperLogLogCounterArray,
+    /// Whether a counter changed during the last iteration.
+    modified: Box<[AtomicBool]>,
+    /// Whether a counter changed during the current iteration.
+    next_modified: Box<[AtomicBool]>,
+    /// In systolic iterations, whether a node has a successor whose counter
+    /// changed during the last iteration.
+    must_check: Box<[AtomicBool]>,
+    sum_of_distances: Option<Box<[AtomicU64]>>,
+    sum_of_inverse_distances: Option<Box<[AtomicU64]>>,
+    neighborhood_function: Vec<f64>,
+    num_modified: usize,
+}
+
+impl<
+        'a,
+        G: RandomAccessGraph + Sync,
+        T: RandomAccessGraph + Sync,
+        D: Succ<Input = usize, Output = usize> + Send + Sync,
+    > HyperBall<'a, G, T, D>
+{
+    /// Run HyperBall until no counter changes.
+    pub fn run_until_done(
+        &mut self,
+        thread_pool: &rayon::ThreadPool,
+        pl: Option<&mut ProgressLogger>,
+    ) -> Result<()> {
+        self.run(usize::MAX, None, thread_pool, pl)
+    }
+
+    /// Run HyperBall.
+    ///
+    /// # Arguments
+    ///
+    /// * `upper_bound` - An upper bound on the number of iterations.
+    /// * `threshold` - If not `None`, the computation stops when the relative
+    ///   increment of the neighborhood function is below this value.
+    /// * `thread_pool` - The thread pool to use.
+    /// * `pl` - An optional mutable reference to a progress logger, which
+    ///   will log iterations.
+    pub fn run(
+        &mut self,
+        upper_bound: usize,
+        threshold: Option<f64>,
+        thread_pool: &rayon::ThreadPool,
+        pl: Option<&mut ProgressLogger>,
+    ) -> Result<()> {
+        if let Some(transpose) = self.transpose {
+            ensure!(
+                transpose.num_nodes() == self.graph.num_nodes(),
+                "The transpose has {} nodes, but the graph has {} nodes",
+                transpose.num_nodes(),
+                self.graph.num_nodes()
+            );
+        }
+
+        let mut pl = pl;
+        if let Some(pl) = pl.as_mut() {
+            pl.item_name("iteration");
+            pl.expected_updates(None);
+            pl.start("Running HyperBall...");
+        }
+
+        self.init(thread_pool);
+
+        for iteration in 1..=upper_bound {
+            if self.num_modified == 0 {
+                break;
+            }
+            let prev = *self.neighborhood_function.last().unwrap();
+            self.iterate(iteration, thread_pool);
+            let curr = *self.neighborhood_function.last().unwrap();
+
+            log::info!(
+                "Iteration {}: neighborhood function {}, modified counters {}",
+                iteration,
+                curr,
+                self.num_modified
+            );
+            if let Some(pl) = pl.as_mut() {
+                pl.update_and_display();
+            }
+
+            if let Some(threshold) = threshold {
+                if curr / prev - 1.0 < threshold {
+                    log::info!(
+                        "Relative increment {} below threshold {}",
+                        curr / prev - 1.0,
+                        threshold
+                    );
+                    break;
+                }
+            }
+        }
+
+        if let Some(pl) = pl.as_mut() {
+            pl.done();
+        }
+        Ok(())
+    }
+
+    /// Initialize counters, so that each node is in its own ball.
+    fn init(&mut self, thread_pool: &rayon::ThreadPool) {
+        let num_nodes = self.graph.num_nodes();
+        thread_pool.install(|| {
+            self.curr.clear();
+            self.next.clear();
+        });
+        for node in 0..num_nodes {
+            self.curr.add(node, node as u64);
+        }
+        let init_f64 = |v: &Option<Box<[AtomicU64]>>| {
+            if let Some(v) = v {
+                v.par_iter()
+                    .with_min_len(1024)
+                    .for_each(|x| x.store(0.0_f64.to_bits(), Ordering::Relaxed));
+            }
+        };
+        let curr = &self.curr;
+        let modified = &self.modified;
+        let nf = thread_pool.install(|| {
+            init_f64(&self.sum_of_distances);
+            init_f64(&self.sum_of_inverse_distances);
+            modified
+                .par_iter()
+                .with_min_len(1024)
+                .for_each(|m| m.store(true, Ordering::Relaxed));
+            (0..num_nodes)
+                .into_par_iter()
+                .with_min_len(1024)
+                .map(|node| curr.estimate(node))
+                .sum::<f64>()
+        });
+        self.neighborhood_function = vec![nf];
+        self.num_modified = num_nodes;
+    }
+
+    /// Perform an iteration.
+    fn iterate(&mut self, iteration: usize, thread_pool: &rayon::ThreadPool) {
+        let num_nodes = self.graph.num_nodes();
+        // Systolic iterations are useful only if few nodes have been modified
+        let systolic = self.transpose.is_some() && self.num_modified < num_nodes / 4;
+
+        if systolic {
+            let transpose = self.transpose.unwrap();
+            let modified = &self.modified;
+            let must_check = &self.must_check;
+            thread_pool.install(|| {
+                (0..num_nodes)
+                    .into_par_iter()
+                    .with_min_len(1024)
+                    .filter(|&node| modified[node].load(Ordering::Relaxed))
+                    .for_each(|node| {
+                        for pred in transpose.successors(node) {
+                            must_check[pred].store(true, Ordering::Relaxed);
+                        }
+                    })
+            });
+        }
+
+        let graph = self.graph;
+        let curr = &self.curr;
+        let next = &self.next;
+        let modified = &self.modified;
+        let next_modified = &self.next_modified;
+        let must_check = &self.must_check;
+        let sum_of_distances = self.sum_of_distances.as_deref();
+        let sum_of_inverse_distances = self.sum_of_inverse_distances.as_deref();
+        let m = 1 << curr.log2m();
+
+        let (delta, num_modified) = graph.par_apply(
+            |range| {
+                let mut buffer = vec![0; m];
+                let mut delta = 0.0;
+                let mut num_modified = 0;
+                for node in range {
+                    let counter = curr.counter(node);
+                    buffer.copy_from_slice(counter);
+                    let mut changed = false;
+                    if !systolic || must_check[node].swap(false, Ordering::Relaxed) {
+                        for succ in graph.successors(node) {
+                            // If the counter of a successor did not change, it
+                            // is already included in the counter of the node
+                            if modified[succ].load(Ordering::Relaxed) {
+                                changed |= merge(&mut buffer, curr.counter(succ));
+                            }
+                        }
+                    }
+                    // SAFETY: each node is processed by exactly one thread
+                    unsafe { next.set_unchecked(node, &buffer) };
+                    next_modified[node].store(changed, Ordering::Relaxed);
+
+                    if changed {
+                        num_modified += 1;
+                        let d = estimate(&buffer) - estimate(counter);
+                        delta += d;
+                        if let Some(sum_of_distances) = sum_of_distances {
+                            add_f64(&sum_of_distances[node], d * iteration as f64);
+                        }
+                        if let Some(sum_of_inverse_distances) = sum_of_inverse_distances {
+                            add_f64(&sum_of_inverse_distances[node], d / iteration as f64);
+                        }
+                    }
+                }
+                (delta, num_modified)
+            },
+            |(d0, m0): (f64, usize), (d1, m1)| (d0 + d1, m0 + m1),
+            self.granularity,
+            self.deg_cumul,
+            thread_pool,
+            None,
+        );
+
+        std::mem::swap(&mut self.curr, &mut self.next);
+        std::mem::swap(&mut self.modified, &mut self.next_modified);
+        self.num_modified = num_modified;
+        let last = *self.neighborhood_function.last().unwrap();
+        self.neighborhood_function.push(last + delta);
+    }
+
+    /// Return the neighborhood function computed so far.
+    ///
+    /// The element of index _t_ is the (estimated) number of pairs of nodes
+    /// (_x_, _y_) such that _y_ is reachable from _x_ in at most _t_ steps.
+    pub fn neighborhood_function(&self) -> &[f64] {
+        &self.neighborhood_function
+    }
+
+    /// Return, for each node, the (estimated) number of nodes reachable from it
+    /// within the number of iterations performed so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `thread_pool` - The thread pool to use.
+    pub fn reachable_nodes(&self, thread_pool: &rayon::ThreadPool) -> Box<[f64]> {
+        thread_pool.install(|| {
+            (0..self.graph.num_nodes())
+                .into_par_iter()
+                .with_min_len(1024)
+                .map(|node| self.curr.estimate(node))
+                .collect::<Vec<_>>()
+                .into_boxed_slice()
+        })
+    }
+
+    /// Return, for each node, the (estimated) sum of the distances to the nodes
+    /// reachable from it, if centralities have been requested.
+    pub fn sum_of_distances(&self) -> Option<Box<[f64]>> {
+        self.sum_of_distances
+            .as_ref()
+            .map(|v| v.iter().map(load_f64).collect())
+    }
+
+    /// Return, for each node, the (estimated) harmonic centrality, that is, the
+    /// sum of the inverse distances to the nodes reachable from it, if
+    /// centralities have been requested.
+    pub fn harmonic_centrality(&self) -> Option<Box<[f64]>> {
+        self.sum_of_inverse_distances
+            .as_ref()
+            .map(|v| v.iter().map(load_f64).collect())
+    }
+
+    /// Return, for each node, the (estimated) closeness centrality, that is,
+    /// the inverse of the sum of the distances to the nodes reachable from
+    /// it (zero if no node is reachable), if centralities have been requested.
+    pub fn closeness_centrality(&self) -> Option<Box<[f64]>> {
+        self.sum_of_distances.as_ref().map(|v| {
+            v.iter()
+                .map(|x| {
+                    let s = load_f64(x);
+                    if s == 0.0 {
+                        0.0
+                    } else {
+                        1.0 / s
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Return, for each node, the (estimated) Lin centrality, that is, the
+    /// square of the number of nodes reachable from it divided by the sum of
+    /// the distances to such nodes (one if no other node is reachable), if
+    /// centralities have been requested.
+    pub fn lin_centrality(&self) -> Option<Box<[f64]>> {
+        self.sum_of_distances.as_ref().map(|v| {
+            v.iter()
+                .enumerate()
+                .map(|(node, x)| {
+                    let s = load_f64(x);
+                    if s == 0.0 {
+                        1.0
+                    } else {
+                        let r = self.curr.estimate(node);
+                        r * r / s
+                    }
+                })
+                .collect()
+        })
+    }
+}
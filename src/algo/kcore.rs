@@ -0,0 +1,200 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::llp::invert_permutation;
+use crate::traits::*;
+use dsi_progress_logger::prelude::*;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The k-core decomposition of a symmetric graph.
+///
+/// The _k_-core of a graph is its largest induced subgraph in which all nodes
+/// have degree at least _k_; the core number of a node is the largest _k_
+/// such that the node belongs to the _k_-core, and the degeneracy of the graph
+/// is the maximum core number.
+///
+/// Besides core numbers, this structure contains a _degeneracy ordering_ of
+/// the nodes, that is, an ordering in which each node has at most
+/// [degeneracy](KCores::degeneracy) neighbors following it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KCores {
+    degeneracy: usize,
+    cores: Box<[usize]>,
+    order: Box<[usize]>,
+}
+
+impl KCores {
+    /// Create a new instance from the core number of each node and a
+    /// degeneracy ordering.
+    pub fn new(cores: Box<[usize]>, order: Box<[usize]>) -> Self {
+        assert_eq!(cores.len(), order.len());
+        KCores {
+            degeneracy: cores.iter().copied().max().unwrap_or(0),
+            cores,
+            order,
+        }
+    }
+
+    /// Return the degeneracy of the graph, that is, the maximum core number.
+    pub fn degeneracy(&self) -> usize {
+        self.degeneracy
+    }
+
+    /// Return a slice containing, for each node, its core number.
+    pub fn cores(&self) -> &[usize] {
+        &self.cores
+    }
+
+    /// Consume self and return the core number of each node.
+    pub fn into_cores(self) -> Box<[usize]> {
+        self.cores
+    }
+
+    /// Return the nodes in degeneracy order.
+    ///
+    /// Core numbers are nondecreasing along the order.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Compute and return the permutation associated with the degeneracy
+    /// order, that is, the permutation mapping each node to its position in
+    /// the order.
+    ///
+    /// The result can be passed to [`permute`](crate::transform::permute).
+    pub fn perm(&self) -> Box<[usize]> {
+        let mut perm = vec![0; self.order.len()];
+        invert_permutation(&self.order, &mut perm);
+        perm.into_boxed_slice()
+    }
+}
+
+/// Compute in parallel the k-core decomposition of a symmetric graph.
+///
+/// This function implements a level-synchronous parallel version of the
+/// peeling algorithm of Batagelj and Zaveršnik: for each core number _k_,
+/// nodes of degree at most _k_ are removed in parallel, in rounds, updating
+/// atomically the degrees of their neighbors, until all remaining nodes have
+/// degree larger than _k_. The nodes removed in the same round are placed
+/// in the [degeneracy order](KCores::order) in increasing order.
+///
+/// Loops are ignored, and successor lists must not contain duplicates.
+/// Besides the graph, this function needs four `usize` per node in the worst
+/// case.
+///
+/// The graph is assumed to be symmetric, but no check is performed: on a
+/// non-symmetric graph, the results are meaningless.
+pub fn kcore<G: RandomAccessGraph + Sync>(
+    graph: &G,
+    mut threads: impl AsMut<rayon::ThreadPool>,
+    pl: Option<&mut ProgressLogger>,
+) -> KCores {
+    let num_nodes = graph.num_nodes();
+    let thread_pool = threads.as_mut();
+
+    let mut pl = pl;
+    if let Some(pl) = pl.as_mut() {
+        pl.item_name("node");
+        pl.expected_updates(Some(num_nodes));
+        pl.start("Computing k-core decomposition...");
+    }
+
+    // The number of remaining neighbors of each node
+    let degrees = thread_pool.install(|| {
+        (0..num_nodes)
+            .into_par_iter()
+            .with_min_len(1024)
+            .map(|node| {
+                AtomicUsize::new(
+                    graph
+                        .successors(node)
+                        .into_iter()
+                        .filter(|&succ| succ != node)
+                        .count(),
+                )
+            })
+            .collect::<Box<[_]>>()
+    });
+    let cores = (0..num_nodes)
+        .map(|_| AtomicUsize::new(usize::MAX))
+        .collect::<Box<[_]>>();
+
+    let mut order = Vec::with_capacity(num_nodes);
+    let mut remaining = (0..num_nodes).collect::<Vec<_>>();
+    let next = Mutex::new(vec![]);
+
+    while !remaining.is_empty() {
+        // All remaining nodes have degree larger than the previous core number
+        let k = thread_pool.install(|| {
+            remaining
+                .par_iter()
+                .map(|&node| degrees[node].load(Ordering::Relaxed))
+                .min()
+                .unwrap()
+        });
+        let mut frontier = thread_pool.install(|| {
+            remaining
+                .par_iter()
+                .copied()
+                .filter(|&node| degrees[node].load(Ordering::Relaxed) <= k)
+                .collect::<Vec<_>>()
+        });
+        frontier
+            .iter()
+            .for_each(|&node| cores[node].store(k, Ordering::Relaxed));
+
+        while !frontier.is_empty() {
+            thread_pool.install(|| {
+                frontier.par_sort_unstable();
+                frontier.par_chunks(1024).for_each(|chunk| {
+                    let mut local = vec![];
+                    for &node in chunk {
+                        for succ in graph.successors(node) {
+                            if succ == node || degrees[succ].load(Ordering::Relaxed) <= k {
+                                continue;
+                            }
+                            // Exactly one removed neighbor brings the degree to k
+                            if degrees[succ].fetch_sub(1, Ordering::Relaxed) == k + 1 {
+                                cores[succ].store(k, Ordering::Relaxed);
+                                local.push(succ);
+                            }
+                        }
+                    }
+                    next.lock().unwrap().extend(local);
+                });
+            });
+            if let Some(pl) = pl.as_mut() {
+                pl.update_with_count(frontier.len());
+            }
+            order.extend_from_slice(&frontier);
+            frontier = std::mem::take(&mut *next.lock().unwrap());
+        }
+
+        remaining = thread_pool.install(|| {
+            remaining
+                .par_iter()
+                .copied()
+                .filter(|&node| cores[node].load(Ordering::Relaxed) == usize::MAX)
+                .collect()
+        });
+    }
+
+    if let Some(pl) = pl.as_mut() {
+        pl.done();
+    }
+
+    KCores::new(
+        cores
+            .into_vec()
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect(),
+        order.into_boxed_slice(),
+    )
+}
@@ -20,7 +20,8 @@
 //!
 //! Note that the graph provided should be _symmetric_ and _loopless_. If this
 //! is not the case, please use [crate::transform::simplify] to generate a
-//! suitable graph.
+//! suitable graph. Symmetry can be checked beforehand using
+//! [`check_symmetry`].
 //!
 //! # Memory requirements
 //!
@@ -54,11 +55,35 @@ pub(crate) mod label_store;
 mod mix64;
 pub mod preds;
 
+/// Checks that a graph is symmetric, as required by
+/// [`layered_label_propagation`], returning an error reporting a
+/// non-reciprocated arc if it is not.
+///
+/// The check uses [`reciprocity`](crate::algo::reciprocity), which computes
+/// the transpose of the graph.
+pub fn check_symmetry<G: SequentialGraph>(
+    graph: &G,
+    pl: Option<&mut ProgressLogger>,
+) -> Result<()> {
+    let reciprocity =
+        crate::algo::reciprocity(graph, crate::algo::Reciprocity::DEFAULT_BATCH_SIZE, 1, pl)?;
+    if let Some((x, y)) = reciprocity.non_reciprocal_arcs().first() {
+        anyhow::bail!(
+            "The graph is not symmetric: the arc {} -> {} is not reciprocated ({:.3}% of the arcs are reciprocal)",
+            x,
+            y,
+            100.0 * reciprocity.reciprocity()
+        );
+    }
+    Ok(())
+}
+
 /// Runs layered label propagation on the provided symmetric graph and returns
 /// the resulting labels.
 ///
 /// Note that no symmetry check is performed, but in that case the algorithm
-/// usually will not give satisfactory results.
+/// usually will not give satisfactory results: use [`check_symmetry`] to
+/// check the graph beforehand.
 ///
 /// # Arguments
 ///
@@ -415,12 +440,12 @@ fn combine(result: &mut [usize], labels: &[usize], temp_perm: &mut [usize]) -> R
 use std::cell::UnsafeCell;
 
 #[derive(Copy, Clone)]
-struct UnsafeSlice<'a, T>(&'a [UnsafeCell<T>]);
+pub(crate) struct UnsafeSlice<'a, T>(&'a [UnsafeCell<T>]);
 unsafe impl<'a, T: Send + Sync> Send for UnsafeSlice<'a, T> {}
 unsafe impl<'a, T: Send + Sync> Sync for UnsafeSlice<'a, T> {}
 
 impl<'a, T> UnsafeSlice<'a, T> {
-    fn new(slice: &'a mut [T]) -> Self {
+    pub(crate) fn new(slice: &'a mut [T]) -> Self {
         #![allow(trivial_casts)]
         Self(unsafe { &*(slice as *mut [T] as *const [UnsafeCell<T>]) })
     }
@@ -434,7 +459,7 @@ impl<'a, T> UnsafeSlice<'a, T> {
     ///
     /// It is UB if two threads write to the same index without
     /// synchronization.
-    unsafe fn write(&self, i: usize, value: T) {
+    pub(crate) unsafe fn write(&self, i: usize, value: T) {
         let ptr = self.0[i].get();
         *ptr = value;
     }
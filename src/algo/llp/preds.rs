@@ -26,6 +26,7 @@
 //! # }
 //! ```
 
+use crate::algo::preds::{Iteration, MaxIters};
 use anyhow::ensure;
 use predicates::{reflection::PredicateReflection, Predicate};
 use std::fmt::Display;
@@ -43,46 +44,11 @@ pub struct PredParams {
 }
 
 /// Stop after at most the provided number of updates for a given ɣ.
-#[derive(Debug, Clone)]
-pub struct MaxUpdates {
-    max_updates: usize,
-}
-
-impl MaxUpdates {
-    pub const DEFAULT_MAX_UPDATES: usize = usize::MAX;
-}
-
-impl From<Option<usize>> for MaxUpdates {
-    fn from(max_updates: Option<usize>) -> Self {
-        match max_updates {
-            Some(max_updates) => MaxUpdates { max_updates },
-            None => Self::default(),
-        }
-    }
-}
-
-impl From<usize> for MaxUpdates {
-    fn from(max_updates: usize) -> Self {
-        Some(max_updates).into()
-    }
-}
-
-impl Default for MaxUpdates {
-    fn default() -> Self {
-        Self::from(Self::DEFAULT_MAX_UPDATES)
-    }
-}
+pub type MaxUpdates = MaxIters;
 
-impl Display for MaxUpdates {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("(max updates: {})", self.max_updates))
-    }
-}
-
-impl PredicateReflection for MaxUpdates {}
-impl Predicate<PredParams> for MaxUpdates {
-    fn eval(&self, pred_params: &PredParams) -> bool {
-        pred_params.update + 1 >= self.max_updates
+impl Iteration for PredParams {
+    fn iteration(&self) -> usize {
+        self.update
     }
 }
 
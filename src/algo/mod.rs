@@ -10,5 +10,33 @@
 mod bfs_order;
 pub use bfs_order::BfsOrder;
 
+pub mod hyperball;
+pub use hyperball::{HyperBall, HyperBallBuilder, HyperLogLogCounterArray};
+
+mod kcore;
+pub use kcore::{kcore, KCores};
+
 pub mod llp;
 pub use llp::*;
+
+pub mod pagerank;
+pub use pagerank::{Dangling, PageRank};
+
+pub mod preds;
+
+mod reciprocity;
+pub use reciprocity::{reciprocity, Reciprocity};
+
+mod scc;
+pub use scc::{scc, Sccs};
+
+pub mod sumsweep;
+pub use sumsweep::SumSweep;
+
+mod triangles;
+pub use triangles::{triangles, Triangles};
+
+pub mod visits;
+
+mod wcc;
+pub use wcc::{wcc, Wccs};
@@ -0,0 +1,457 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! PageRank.
+//!
+//! A parallel implementation of PageRank based on the power method, as
+//! described by Sergey Brin and Lawrence Page in “The anatomy of a large-scale
+//! hypertextual Web search engine”, _Computer Networks and ISDN Systems_,
+//! 30(1-7):107−117, 1998.
+//!
+//! The rank vector is computed using a [`PageRank`] instance, which makes it
+//! possible to set the damping factor, a preference (personalization) vector,
+//! and the [strategy](Dangling) used to distribute the rank of dangling nodes
+//! (i.e., nodes without successors). Iterations are stopped when a
+//! [predicate](preds) is satisfied.
+//!
+//! There are two ways of computing PageRank:
+//!
+//! - [`run_transpose`](PageRank::run_transpose) iterates on the _transpose_ of
+//!   the graph, which must provide random access (e.g., a
+//!   [`BVGraph`](crate::graphs::bvgraph::BVGraph)), pulling the rank of each
+//!   node from its predecessors; the work is balanced among threads using the
+//!   degree cumulative function of the transpose, as in
+//!   [par_apply](crate::traits::SequentialLabeling::par_apply);
+//! - [`run_seq`](PageRank::run_seq) iterates on the graph using a
+//!   [split](crate::traits::SplitLabeling) sequential scan (e.g., on a
+//!   [`BVGraphSeq`](crate::graphs::bvgraph::BVGraphSeq)), pushing the rank
+//!   of each node to its successors.
+//!
+//! # Memory requirements
+//!
+//! Besides the memory that is necessary to load the graph (or its transpose):
+//!
+//! - [`run_transpose`](PageRank::run_transpose) requires two `f64` (the
+//!   current and the next rank vector) and a `usize` (the outdegree) per
+//!   node;
+//! - [`run_seq`](PageRank::run_seq) requires, in addition, an [`AtomicU64`]
+//!   per node, in which the ranks pushed by the threads to each node are
+//!   accumulated, that is, two `f64`, a `usize`, and an `AtomicU64` per node.
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> anyhow::Result<()> {
+//! use predicates::prelude::*;
+//! use webgraph::algo::pagerank::{preds::L1Norm, PageRank};
+//! use webgraph::graphs::vec_graph::VecGraph;
+//! use webgraph::labels::proj::Left;
+//! use webgraph::prelude::*;
+//!
+//! let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0), (2, 1)]));
+//! let rank = PageRank::new()
+//!     .alpha(0.85)
+//!     .run_seq(&graph, L1Norm::try_from(1E-9)?, Threads::Num(2), None)?;
+//! assert!((rank.iter().sum::<f64>() - 1.0).abs() < 1E-9);
+//! #     Ok(())
+//! # }
+//! ```
+
+use super::llp::UnsafeSlice;
+use crate::traits::*;
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use log::info;
+use predicates::Predicate;
+use preds::PredParams;
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use sux::traits::Succ;
+
+pub mod preds;
+
+/// The strategy used to distribute the rank of dangling nodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Dangling<'a> {
+    /// The rank of dangling nodes is distributed uniformly among all nodes
+    /// (the _weakly preferential_ variant).
+    #[default]
+    Uniform,
+    /// The rank of dangling nodes is distributed following the preference
+    /// vector (the _strongly preferential_ variant).
+    Preference,
+    /// The rank of dangling nodes is distributed following the given
+    /// distribution.
+    Distribution(&'a [f64]),
+}
+
+/// Computes PageRank.
+///
+/// By default, the damping factor is [`DEFAULT_ALPHA`](Self::DEFAULT_ALPHA),
+/// the preference vector is uniform, and the rank of dangling nodes is
+/// distributed uniformly.
+#[derive(Debug, Clone, Default)]
+pub struct PageRank<'a> {
+    alpha: Option<f64>,
+    preference: Option<&'a [f64]>,
+    dangling: Dangling<'a>,
+    granularity: Option<usize>,
+}
+
+impl<'a> PageRank<'a> {
+    pub const DEFAULT_ALPHA: f64 = 0.85;
+
+    /// Create a new instance with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the damping factor, which must be in [0..1).
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// Set the preference (personalization) vector, which must be a
+    /// distribution over the nodes of the graph.
+    pub fn preference(mut self, preference: &'a [f64]) -> Self {
+        self.preference = Some(preference);
+        self
+    }
+
+    /// Set the strategy used to distribute the rank of dangling nodes.
+    pub fn dangling(mut self, dangling: Dangling<'a>) -> Self {
+        self.dangling = dangling;
+        self
+    }
+
+    /// Set the tentative number of arcs processed by each parallel task in
+    /// [`run_transpose`](Self::run_transpose).
+    ///
+    /// This is an advanced option: see
+    /// [par_apply](crate::traits::SequentialLabeling::par_apply).
+    pub fn granularity(mut self, granularity: usize) -> Self {
+        self.granularity = Some(granularity);
+        self
+    }
+
+    /// Check the parameters against the number of nodes of the graph.
+    fn check(&self, num_nodes: usize) -> Result<f64> {
+        let alpha = self.alpha.unwrap_or(Self::DEFAULT_ALPHA);
+        ensure!(
+            (0.0..1.0).contains(&alpha),
+            "The damping factor must be in [0..1) (got {})",
+            alpha
+        );
+        if let Some(preference) = self.preference {
+            check_distribution(preference, num_nodes, "preference vector")?;
+        }
+        if let Dangling::Distribution(distribution) = self.dangling {
+            check_distribution(distribution, num_nodes, "dangling-node distribution")?;
+        }
+        Ok(alpha)
+    }
+
+    /// Return the distribution used for dangling nodes, or `None` if
+    /// it is uniform.
+    fn dangling_distribution(&self) -> Option<&'a [f64]> {
+        match self.dangling {
+            Dangling::Uniform => None,
+            Dangling::Preference => self.preference,
+            Dangling::Distribution(distribution) => Some(distribution),
+        }
+    }
+
+    /// Compute PageRank iterating on the transpose of a graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `transpose` - The transpose of the graph.
+    /// * `deg_cumul` - The degree cumulative function of the transpose, as in
+    ///   [par_apply](crate::traits::SequentialLabeling::par_apply).
+    /// * `predicate` - The stopping condition (see [`preds`]).
+    /// * `threads` - The threads to use.
+    /// * `pl` - An optional mutable reference to a progress logger, which
+    ///   will log iterations.
+    pub fn run_transpose<G: RandomAccessGraph + Sync>(
+        &self,
+        transpose: &G,
+        deg_cumul: &(impl Succ<Input = usize, Output = usize> + Send + Sync),
+        predicate: impl Predicate<PredParams>,
+        mut threads: impl AsMut<rayon::ThreadPool>,
+        pl: Option<&mut ProgressLogger>,
+    ) -> Result<Box<[f64]>> {
+        let num_nodes = transpose.num_nodes();
+        let alpha = self.check(num_nodes)?;
+        let thread_pool = threads.as_mut();
+        let granularity = self
+            .granularity
+            .unwrap_or(((transpose.num_arcs() >> 9) as usize).max(1024));
+
+        // The outdegrees of the graph are the indegrees of the transpose
+        let outdegree = (0..num_nodes)
+            .map(|_| AtomicUsize::new(0))
+            .collect::<Box<[_]>>();
+        transpose.par_apply(
+            |range| {
+                for node in range {
+                    for pred in transpose.successors(node) {
+                        outdegree[pred].fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            },
+            |_, _| (),
+            granularity,
+            deg_cumul,
+            thread_pool,
+            None,
+        );
+        let outdegree = outdegree
+            .into_vec()
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect::<Box<[_]>>();
+
+        self.iterate(
+            &outdegree,
+            transpose.num_arcs(),
+            alpha,
+            predicate,
+            thread_pool,
+            pl,
+            |rank, next, thread_pool| {
+                let next = UnsafeSlice::new(next);
+                transpose.par_apply(
+                    |range| {
+                        for node in range {
+                            let mut sum = 0.0;
+                            for pred in transpose.successors(node) {
+                                sum += rank[pred] / outdegree[pred] as f64;
+                            }
+                            // SAFETY: each node is processed by exactly one thread
+                            unsafe { next.write(node, sum) };
+                        }
+                    },
+                    |_, _| (),
+                    granularity,
+                    deg_cumul,
+                    thread_pool,
+                    None,
+                );
+            },
+        )
+    }
+
+    /// Compute PageRank iterating on a graph using a split sequential scan.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph.
+    /// * `predicate` - The stopping condition (see [`preds`]).
+    /// * `threads` - The threads to use.
+    /// * `pl` - An optional mutable reference to a progress logger, which
+    ///   will log iterations.
+    pub fn run_seq<G: SequentialGraph + SplitLabeling>(
+        &self,
+        graph: &G,
+        predicate: impl Predicate<PredParams>,
+        mut threads: impl AsMut<rayon::ThreadPool>,
+        pl: Option<&mut ProgressLogger>,
+    ) -> Result<Box<[f64]>> {
+        let num_nodes = graph.num_nodes();
+        let alpha = self.check(num_nodes)?;
+        let thread_pool = threads.as_mut();
+        let num_threads = thread_pool.current_num_threads();
+
+        let mut outdegree = vec![0; num_nodes];
+        let outdegree_slice = UnsafeSlice::new(&mut outdegree);
+        let num_arcs = AtomicU64::new(0);
+        thread_pool.in_place_scope(|scope| {
+            for iter in graph.split_iter(num_threads) {
+                let num_arcs = &num_arcs;
+                scope.spawn(move |_| {
+                    let mut arcs = 0;
+                    for_!( (node, succ) in iter {
+                        let d = succ.into_iter().count();
+                        // SAFETY: each node is returned by exactly one lender
+                        unsafe { outdegree_slice.write(node, d) };
+                        arcs += d as u64;
+                    });
+                    num_arcs.fetch_add(arcs, Ordering::Relaxed);
+                });
+            }
+        });
+
+        let sum = (0..num_nodes)
+            .map(|_| AtomicU64::new(0.0_f64.to_bits()))
+            .collect::<Box<[_]>>();
+
+        self.iterate(
+            &outdegree,
+            num_arcs.into_inner(),
+            alpha,
+            predicate,
+            thread_pool,
+            pl,
+            |rank, next, thread_pool| {
+                let num_threads = thread_pool.current_num_threads();
+                let sum = &sum;
+                let outdegree = &outdegree;
+                thread_pool.in_place_scope(|scope| {
+                    for iter in graph.split_iter(num_threads) {
+                        scope.spawn(move |_| {
+                            for_!( (node, succ) in iter {
+                                if outdegree[node] == 0 {
+                                    continue;
+                                }
+                                let contrib = rank[node] / outdegree[node] as f64;
+                                for succ in succ {
+                                    add_f64(&sum[succ], contrib);
+                                }
+                            });
+                        });
+                    }
+                });
+                thread_pool.install(|| {
+                    next.par_iter_mut()
+                        .zip(sum.par_iter())
+                        .with_min_len(1024)
+                        .for_each(|(n, s)| {
+                            *n = f64::from_bits(s.swap(0.0_f64.to_bits(), Ordering::Relaxed))
+                        })
+                });
+            },
+        )
+    }
+
+    /// The power method.
+    ///
+    /// At each iteration, `step` must store in its second argument, for each
+    /// node, the sum over all predecessors of their rank divided by their
+    /// outdegree; this method takes care of dangling nodes, of the preference
+    /// vector, and of the stopping condition.
+    #[allow(clippy::too_many_arguments)]
+    fn iterate(
+        &self,
+        outdegree: &[usize],
+        num_arcs: u64,
+        alpha: f64,
+        predicate: impl Predicate<PredParams>,
+        thread_pool: &rayon::ThreadPool,
+        pl: Option<&mut ProgressLogger>,
+        step: impl Fn(&[f64], &mut [f64], &rayon::ThreadPool),
+    ) -> Result<Box<[f64]>> {
+        let num_nodes = outdegree.len();
+        if num_nodes == 0 {
+            return Ok(Box::default());
+        }
+        let uniform = 1.0 / num_nodes as f64;
+        let preference = self.preference;
+        let dangling_distribution = self.dangling_distribution();
+
+        let mut pl = pl;
+        if let Some(pl) = pl.as_mut() {
+            pl.item_name("iteration");
+            pl.expected_updates(None);
+            pl.start(format!("Computing PageRank (alpha={})...", alpha));
+        }
+        info!("Stopping criterion: {predicate}");
+
+        let mut rank = vec![uniform; num_nodes];
+        let mut next = vec![0.0; num_nodes];
+
+        for iteration in 0.. {
+            let dangling_rank = thread_pool.install(|| {
+                (0..num_nodes)
+                    .into_par_iter()
+                    .with_min_len(1024)
+                    .filter(|&node| outdegree[node] == 0)
+                    .map(|node| rank[node])
+                    .sum::<f64>()
+            });
+
+            step(&rank, &mut next, thread_pool);
+
+            let norm_delta = thread_pool.install(|| {
+                next.par_iter_mut()
+                    .zip(rank.par_iter())
+                    .enumerate()
+                    .with_min_len(1024)
+                    .map(|(node, (n, &r))| {
+                        let p = preference.map_or(uniform, |p| p[node]);
+                        let d = dangling_distribution.map_or(uniform, |d| d[node]);
+                        *n = alpha * (*n + dangling_rank * d) + (1.0 - alpha) * p;
+                        (*n - r).abs()
+                    })
+                    .sum::<f64>()
+            });
+
+            std::mem::swap(&mut rank, &mut next);
+
+            info!(
+                "Iteration {}: ℓ₁ norm of the delta {}",
+                iteration, norm_delta
+            );
+            if let Some(pl) = pl.as_mut() {
+                pl.update_and_display();
+            }
+
+            if predicate.eval(&PredParams {
+                num_nodes,
+                num_arcs,
+                iteration,
+                norm_delta,
+            }) {
+                break;
+            }
+        }
+
+        if let Some(pl) = pl.as_mut() {
+            pl.done();
+        }
+
+        Ok(rank.into_boxed_slice())
+    }
+}
+
+/// Check that a vector is a distribution over the nodes of a graph.
+fn check_distribution(v: &[f64], num_nodes: usize, name: &str) -> Result<()> {
+    ensure!(
+        v.len() == num_nodes,
+        "The {} has length {}, but the graph has {} nodes",
+        name,
+        v.len(),
+        num_nodes
+    );
+    ensure!(
+        v.iter().all(|&x| x >= 0.0),
+        "The {} has negative entries",
+        name
+    );
+    let sum = v.iter().sum::<f64>();
+    ensure!(
+        (sum - 1.0).abs() < 1E-6,
+        "The {} sums to {}, not to one",
+        name,
+        sum
+    );
+    Ok(())
+}
+
+/// Atomically add to an `f64` stored in an [`AtomicU64`].
+#[inline(always)]
+fn add_f64(x: &AtomicU64, y: f64) {
+    let mut current = x.load(Ordering::Relaxed);
+    loop {
+        let new = (f64::from_bits(current) + y).to_bits();
+        match x.compare_exchange_weak(current, new, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => return,
+            Err(value) => current = value,
+        }
+    }
+}
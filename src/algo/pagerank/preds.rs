@@ -0,0 +1,104 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Predicates implementing stopping conditions.
+//!
+//! The computation of [PageRank](super::PageRank) requires a
+//! [predicate](Predicate) to stop the iterations. This module provides the
+//! predicates specific to PageRank: they evaluate to true if the iterations should be stopped.
+//!
+//! You can combine the predicates using the `and` and `or` methods provided by
+//! the [`Predicate`] trait, also with the predicates shared by iterative
+//! algorithms, such as [`MaxIters`](crate::algo::preds::MaxIters).
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use predicates::prelude::*;
+//! use webgraph::algo::pagerank::preds::L1Norm;
+//! use webgraph::algo::preds::MaxIters;
+//!
+//! let mut predicate = L1Norm::try_from(1E-9)?.boxed();
+//! predicate = predicate.or(MaxIters::from(100)).boxed();
+//! #     Ok(())
+//! # }
+//! ```
+
+use crate::algo::preds::Iteration;
+use anyhow::ensure;
+use predicates::{reflection::PredicateReflection, Predicate};
+use std::fmt::Display;
+
+#[doc(hidden)]
+/// This structure is passed to predicates to provide the
+/// information that is needed to evaluate them.
+pub struct PredParams {
+    pub num_nodes: usize,
+    pub num_arcs: u64,
+    pub iteration: usize,
+    pub norm_delta: f64,
+}
+
+impl Iteration for PredParams {
+    fn iteration(&self) -> usize {
+        self.iteration
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Stop if the ℓ₁ norm of the difference between the rank vectors of two
+/// consecutive iterations is below the given threshold.
+///
+/// The [default threshold](Self::DEFAULT_THRESHOLD) is the same as that
+/// of the Java implementation.
+pub struct L1Norm {
+    threshold: f64,
+}
+
+impl L1Norm {
+    pub const DEFAULT_THRESHOLD: f64 = 1E-6;
+}
+
+impl TryFrom<Option<f64>> for L1Norm {
+    type Error = anyhow::Error;
+    fn try_from(threshold: Option<f64>) -> anyhow::Result<Self> {
+        Ok(match threshold {
+            Some(threshold) => {
+                ensure!(!threshold.is_nan());
+                ensure!(threshold >= 0.0, "The threshold must be nonnegative");
+                L1Norm { threshold }
+            }
+            None => Self::default(),
+        })
+    }
+}
+
+impl TryFrom<f64> for L1Norm {
+    type Error = anyhow::Error;
+    fn try_from(threshold: f64) -> anyhow::Result<Self> {
+        Some(threshold).try_into()
+    }
+}
+
+impl Default for L1Norm {
+    fn default() -> Self {
+        Self::try_from(Self::DEFAULT_THRESHOLD).unwrap()
+    }
+}
+
+impl Display for L1Norm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("(ℓ₁ norm of the delta: {})", self.threshold))
+    }
+}
+
+impl PredicateReflection for L1Norm {}
+impl Predicate<PredParams> for L1Norm {
+    fn eval(&self, pred_params: &PredParams) -> bool {
+        pred_params.norm_delta <= self.threshold
+    }
+}
@@ -0,0 +1,91 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Predicates implementing stopping conditions shared by iterative algorithms.
+//!
+//! The predicates in this module can be used with every algorithm whose
+//! predicate parameters implement [`Iteration`], such as
+//! [layered label propagation](super::llp) and [PageRank](super::PageRank).
+//! They can be combined with algorithm-specific predicates using the `and` and
+//! `or` methods provided by the [`Predicate`] trait.
+//!
+//! For backward compatibility, the predicates specific to layered label
+//! propagation, which are defined in [`llp::preds`](super::llp::preds), are
+//! re-exported by this module.
+//!
+//! # Examples
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use predicates::prelude::*;
+//! use webgraph::algo::pagerank::preds::L1Norm;
+//! use webgraph::algo::preds::MaxIters;
+//!
+//! let mut predicate = L1Norm::try_from(1E-9)?.boxed();
+//! predicate = predicate.or(MaxIters::from(100)).boxed();
+//! #     Ok(())
+//! # }
+//! ```
+
+use predicates::{reflection::PredicateReflection, Predicate};
+use std::fmt::Display;
+
+pub use super::llp::preds::*;
+
+/// Predicate parameters of an iterative algorithm exposing the current
+/// iteration.
+pub trait Iteration {
+    /// Returns the current iteration, starting from zero.
+    fn iteration(&self) -> usize;
+}
+
+/// Stop after at most the provided number of iterations.
+#[derive(Debug, Clone)]
+pub struct MaxIters {
+    max_iters: usize,
+}
+
+impl MaxIters {
+    pub const DEFAULT_MAX_ITERS: usize = usize::MAX;
+    /// The name of [`DEFAULT_MAX_ITERS`](Self::DEFAULT_MAX_ITERS) when this
+    /// predicate was specific to layered label propagation.
+    #[deprecated(note = "use `DEFAULT_MAX_ITERS` instead")]
+    pub const DEFAULT_MAX_UPDATES: usize = Self::DEFAULT_MAX_ITERS;
+}
+
+impl From<Option<usize>> for MaxIters {
+    fn from(max_iters: Option<usize>) -> Self {
+        match max_iters {
+            Some(max_iters) => MaxIters { max_iters },
+            None => Self::default(),
+        }
+    }
+}
+
+impl From<usize> for MaxIters {
+    fn from(max_iters: usize) -> Self {
+        Some(max_iters).into()
+    }
+}
+
+impl Default for MaxIters {
+    fn default() -> Self {
+        Self::from(Self::DEFAULT_MAX_ITERS)
+    }
+}
+
+impl Display for MaxIters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("(max iterations: {})", self.max_iters))
+    }
+}
+
+impl PredicateReflection for MaxIters {}
+impl<P: Iteration> Predicate<P> for MaxIters {
+    fn eval(&self, pred_params: &P) -> bool {
+        pred_params.iteration() + 1 >= self.max_iters
+    }
+}
@@ -0,0 +1,141 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::Result;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+
+/// The reciprocity of the arcs of a graph.
+///
+/// An arc _x_ → _y_ is _reciprocal_ if the graph contains also the arc
+/// _y_ → _x_ (loops are always reciprocal); a graph is symmetric if and only
+/// if all its arcs are reciprocal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reciprocity {
+    num_arcs: u64,
+    num_reciprocal: u64,
+    non_reciprocal: Vec<(usize, usize)>,
+}
+
+impl Reciprocity {
+    /// The default batch size used to compute the transpose.
+    pub const DEFAULT_BATCH_SIZE: usize = 10_000_000;
+
+    /// Return whether the graph is symmetric.
+    pub fn is_symmetric(&self) -> bool {
+        self.num_reciprocal == self.num_arcs
+    }
+
+    /// Return the number of arcs of the graph.
+    pub fn num_arcs(&self) -> u64 {
+        self.num_arcs
+    }
+
+    /// Return the number of reciprocal arcs of the graph.
+    pub fn num_reciprocal(&self) -> u64 {
+        self.num_reciprocal
+    }
+
+    /// Return the fraction of reciprocal arcs (one for a graph without arcs).
+    pub fn reciprocity(&self) -> f64 {
+        if self.num_arcs == 0 {
+            1.0
+        } else {
+            self.num_reciprocal as f64 / self.num_arcs as f64
+        }
+    }
+
+    /// Return the first non-reciprocal arcs of the graph, in lexicographical
+    /// order.
+    ///
+    /// At most as many arcs as specified when calling [`reciprocity`] are
+    /// returned.
+    pub fn non_reciprocal_arcs(&self) -> &[(usize, usize)] {
+        &self.non_reciprocal
+    }
+}
+
+/// Compute the reciprocity of the arcs of a graph, and thus whether it is
+/// symmetric.
+///
+/// The graph is scanned once to compute its transpose using
+/// [`transpose`](crate::transform::transpose), and then scanned again together
+/// with its transpose, merging the successors of each node in the
+/// graph with its successors in the transpose (i.e., its predecessors in the
+/// graph). Successor lists need not be sorted, but in that case they will be
+/// sorted before merging.
+///
+/// # Arguments
+///
+/// * `graph` - The graph.
+/// * `batch_size` - The batch size used to compute the transpose; see
+///   [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+/// * `max_non_reciprocal` - The maximum number of non-reciprocal arcs to
+///   record.
+/// * `pl` - An optional mutable reference to a progress logger.
+pub fn reciprocity<G: SequentialGraph>(
+    graph: &G,
+    batch_size: usize,
+    max_non_reciprocal: usize,
+    pl: Option<&mut ProgressLogger>,
+) -> Result<Reciprocity> {
+    let transpose = crate::transform::transpose(graph, batch_size)?;
+
+    let mut pl = pl;
+    if let Some(pl) = pl.as_mut() {
+        pl.item_name("node");
+        pl.expected_updates(Some(graph.num_nodes()));
+        pl.start("Comparing the graph with its transpose...");
+    }
+
+    let mut num_arcs = 0;
+    let mut num_reciprocal = 0;
+    let mut non_reciprocal = vec![];
+    let mut succ_g = vec![];
+    let mut succ_t = vec![];
+
+    let mut iter = graph.iter();
+    let mut iter_t = transpose.iter();
+    while let Some((node, succ)) = iter.next() {
+        let (node_t, pred) = iter_t.next().unwrap();
+        debug_assert_eq!(node, node_t);
+        succ_g.clear();
+        succ_g.extend(succ);
+        succ_g.sort_unstable();
+        succ_t.clear();
+        succ_t.extend(pred);
+
+        let mut pos = 0;
+        for &s in &succ_g {
+            num_arcs += 1;
+            while pos < succ_t.len() && succ_t[pos] < s {
+                pos += 1;
+            }
+            if pos < succ_t.len() && succ_t[pos] == s {
+                num_reciprocal += 1;
+                pos += 1;
+            } else if non_reciprocal.len() < max_non_reciprocal {
+                non_reciprocal.push((node, s));
+            }
+        }
+
+        if let Some(pl) = pl.as_mut() {
+            pl.light_update();
+        }
+    }
+
+    if let Some(pl) = pl.as_mut() {
+        pl.done();
+    }
+
+    Ok(Reciprocity {
+        num_arcs,
+        num_reciprocal,
+        non_reciprocal,
+    })
+}
@@ -0,0 +1,198 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::traits::RandomAccessGraph;
+use dsi_progress_logger::prelude::*;
+use sux::prelude::BitVec;
+
+/// The strongly connected components of a graph.
+///
+/// Components are numbered from zero; the component of each node
+/// is available through [`components`](Sccs::components).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sccs {
+    num_components: usize,
+    components: Box<[usize]>,
+}
+
+impl Sccs {
+    /// Create a new instance from the number of components and the
+    /// component of each node.
+    pub fn new(num_components: usize, components: Box<[usize]>) -> Self {
+        Sccs {
+            num_components,
+            components,
+        }
+    }
+
+    /// Return the number of strongly connected components.
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Return a slice containing, for each node, its component.
+    pub fn components(&self) -> &[usize] {
+        &self.components
+    }
+
+    /// Consume self and return the component of each node.
+    pub fn into_components(self) -> Box<[usize]> {
+        self.components
+    }
+
+    /// Compute and return the size of each component.
+    pub fn compute_sizes(&self) -> Box<[usize]> {
+        compute_sizes(&self.components, self.num_components)
+    }
+
+    /// Renumber components by non-increasing size, so that component zero is
+    /// the largest one, and return the sizes of the renumbered components.
+    ///
+    /// Components of the same size keep their relative order.
+    pub fn sort_by_size(&mut self) -> Box<[usize]> {
+        sort_by_size(&mut self.components, self.num_components)
+    }
+}
+
+/// Compute the size of each component given the component of each node.
+pub(super) fn compute_sizes(components: &[usize], num_components: usize) -> Box<[usize]> {
+    let mut sizes = vec![0; num_components];
+    for &c in components.iter() {
+        sizes[c] += 1;
+    }
+    sizes.into_boxed_slice()
+}
+
+/// Renumber components by non-increasing size, keeping the relative order of
+/// components of the same size, and return the sizes of the renumbered
+/// components.
+pub(super) fn sort_by_size(components: &mut [usize], num_components: usize) -> Box<[usize]> {
+    let sizes = compute_sizes(components, num_components);
+    let mut order = (0..num_components).collect::<Vec<_>>();
+    order.sort_by(|&x, &y| sizes[y].cmp(&sizes[x]));
+    let mut rank = vec![0; num_components];
+    for (r, &c) in order.iter().enumerate() {
+        rank[c] = r;
+    }
+    for c in components.iter_mut() {
+        *c = rank[*c];
+    }
+    order.iter().map(|&c| sizes[c]).collect()
+}
+
+/// Compute the strongly connected components of a graph using Tarjan's
+/// algorithm.
+///
+/// The visit is iterative, so it does not depend on the size of the thread
+/// stack, and it uses the space-efficient variant described by David J.
+/// Pearce in “A space-efficient algorithm for finding strongly connected
+/// components”, _Information Processing Letters_, 116(1):47−52, 2016: besides
+/// the visit stacks, it needs just a `usize` and a bit per node.
+///
+/// Components are numbered in reverse topological order: if there is an arc
+/// from a node in component _x_ to a node in component _y_ ≠ _x_, then
+/// _x_ > _y_. In particular, component zero is a sink.
+pub fn scc<G: RandomAccessGraph>(graph: &G, pl: Option<&mut ProgressLogger>) -> Sccs {
+    let num_nodes = graph.num_nodes();
+    let mut pl = pl;
+    if let Some(pl) = pl.as_mut() {
+        pl.item_name("node");
+        pl.expected_updates(Some(num_nodes));
+        pl.start("Computing strongly connected components...");
+    }
+
+    // Zero means unvisited; nodes on the visit stacks have indices in
+    // [1..index), whereas nodes whose component has been assigned have
+    // indices in (component..num_nodes]. Since the number of nodes on the
+    // stacks plus the number of assigned components never exceeds the
+    // number of nodes, the two ranges never overlap.
+    let mut rindex = vec![0; num_nodes];
+    // Whether a node might be the root of a component
+    let mut root = BitVec::new(num_nodes);
+    let mut index = 1;
+    let mut component = num_nodes;
+    // Nodes whose component is still to be assigned
+    let mut stack = vec![];
+    // The visit stack, containing each visited node and the iterator on
+    // its remaining successors
+    let mut visit = vec![];
+
+    for start in 0..num_nodes {
+        if rindex[start] != 0 {
+            continue;
+        }
+
+        rindex[start] = index;
+        index += 1;
+        root.set(start, true);
+        visit.push((start, graph.successors(start).into_iter()));
+
+        while let Some((node, succ)) = visit.last_mut() {
+            let node = *node;
+            let mut child = None;
+            for s in succ {
+                if rindex[s] == 0 {
+                    child = Some(s);
+                    break;
+                }
+                if rindex[s] < rindex[node] {
+                    rindex[node] = rindex[s];
+                    root.set(node, false);
+                }
+            }
+
+            if let Some(child) = child {
+                rindex[child] = index;
+                index += 1;
+                root.set(child, true);
+                visit.push((child, graph.successors(child).into_iter()));
+                continue;
+            }
+
+            // All successors have been visited
+            visit.pop();
+            if let Some(pl) = pl.as_mut() {
+                pl.light_update();
+            }
+
+            if root[node] {
+                index -= 1;
+                while let Some(&last) = stack.last() {
+                    if rindex[node] > rindex[last] {
+                        break;
+                    }
+                    stack.pop();
+                    rindex[last] = component;
+                    index -= 1;
+                }
+                rindex[node] = component;
+                component -= 1;
+            } else {
+                stack.push(node);
+            }
+
+            // Update the parent, if any
+            if let Some(&(parent, _)) = visit.last() {
+                if rindex[node] < rindex[parent] {
+                    rindex[parent] = rindex[node];
+                    root.set(parent, false);
+                }
+            }
+        }
+    }
+
+    if let Some(pl) = pl.as_mut() {
+        pl.done();
+    }
+
+    // Renumber components starting from zero
+    for r in rindex.iter_mut() {
+        *r = num_nodes - *r;
+    }
+
+    Sccs::new(num_nodes - component, rindex.into_boxed_slice())
+}
@@ -0,0 +1,488 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Exact computation of the radius, the diameter, and the eccentricities of a
+//! strongly connected graph.
+//!
+//! An implementation of the _ExactSumSweep_ algorithm described by Michele
+//! Borassi, Pierluigi Crescenzi, Michel Habib, Walter A. Kosters, Andrea
+//! Marino, and Frank W. Takes in “Fast diameter and radius BFS-based
+//! computation in (weakly connected) real-world graphs: With an application to
+//! the six degrees of separation games”, _Theoretical Computer Science_,
+//! 586:59−80, 2015, which generalizes the _iFub_ algorithm.
+//!
+//! The algorithm performs a sequence of forward and backward breadth-first
+//! visits: each visit computes exactly the (forward or backward) eccentricity
+//! of its source, and refines lower and upper bounds on the eccentricities of
+//! all other nodes using the triangle inequality. The computation stops when
+//! the bounds certify the quantities required by the chosen [`Level`].
+//! Usually, a very small number of visits is sufficient to compute radius and
+//! diameter of large real-world graphs.
+//!
+//! Since bounds are available after each visit, [`SumSweep::step`] can be used
+//! to obtain approximate values, by stopping the computation early.
+//!
+//! The graph must be strongly connected: this condition is checked during the
+//! visits, and an error is returned if it does not hold. The transpose of the
+//! graph is necessary to perform backward visits; for symmetric graphs, the
+//! graph itself can be passed as transpose.
+//!
+//! # Memory requirements
+//!
+//! The algorithm requires seven `usize` and two bits per node, plus the memory
+//! that is necessary to load the graph and its transpose.
+
+use super::visits::breadth_first::{self, Event};
+use crate::traits::RandomAccessGraph;
+use anyhow::{ensure, Result};
+use dsi_progress_logger::prelude::*;
+use std::convert::Infallible;
+use std::ops::ControlFlow;
+
+/// The quantities computed by [`SumSweep`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// The radius, that is, the minimum forward eccentricity.
+    Radius,
+    /// The diameter, that is, the maximum forward eccentricity.
+    Diameter,
+    /// Both radius and diameter.
+    RadiusDiameter,
+    /// All forward eccentricities (and thus radius and diameter).
+    AllForward,
+    /// All forward and backward eccentricities.
+    All,
+}
+
+/// The direction of a visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dir {
+    Forward,
+    Backward,
+}
+
+/// The heuristics used to choose the source of the next visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    /// A forward visit from the node with the largest upper bound on its
+    /// forward eccentricity (improves the diameter upper bound).
+    ForwardMaxHigh,
+    /// A backward visit from the node with the largest upper bound on its
+    /// backward eccentricity (improves the diameter upper bound).
+    BackwardMaxHigh,
+    /// A forward visit from the node with the smallest lower bound on its
+    /// forward eccentricity (improves the radius lower bound).
+    ForwardMinLow,
+    /// A backward visit from the node with the smallest upper bound on its
+    /// forward eccentricity (improves the upper bounds on forward
+    /// eccentricities).
+    BackwardMinForwardHigh,
+}
+
+/// The number of initial visits using the _SumSweep_ heuristic.
+const NUM_SWEEPS: usize = 6;
+
+/// The ExactSumSweep algorithm.
+///
+/// After creating an instance with [`new`](SumSweep::new), call
+/// [`run`](SumSweep::run) to perform the computation, or
+/// [`step`](SumSweep::step) repeatedly to perform one visit at a time and
+/// inspect the bounds.
+pub struct SumSweep<'a, G: RandomAccessGraph, T: RandomAccessGraph> {
+    graph: &'a G,
+    level: Level,
+    num_nodes: usize,
+    /// Lower bounds on forward eccentricities.
+    forward_low: Box<[usize]>,
+    /// Upper bounds on forward eccentricities.
+    forward_high: Box<[usize]>,
+    /// Lower bounds on backward eccentricities.
+    backward_low: Box<[usize]>,
+    /// Upper bounds on backward eccentricities.
+    backward_high: Box<[usize]>,
+    /// For each node, the sum of the distances to the sources of backward
+    /// visits.
+    forward_tot: Box<[usize]>,
+    /// For each node, the sum of the distances from the sources of forward
+    /// visits.
+    backward_tot: Box<[usize]>,
+    dist: Box<[usize]>,
+    forward_visit: breadth_first::Seq<'a, G>,
+    backward_visit: breadth_first::Seq<'a, T>,
+    num_visits: usize,
+}
+
+impl<'a, G: RandomAccessGraph, T: RandomAccessGraph> SumSweep<'a, G, T> {
+    /// Create a new instance computing the quantities specified by `level`
+    /// for a graph, given its transpose.
+    pub fn new(graph: &'a G, transpose: &'a T, level: Level) -> Self {
+        let num_nodes = graph.num_nodes();
+        Self {
+            graph,
+            level,
+            num_nodes,
+            forward_low: vec![0; num_nodes].into_boxed_slice(),
+            forward_high: vec![usize::MAX; num_nodes].into_boxed_slice(),
+            backward_low: vec![0; num_nodes].into_boxed_slice(),
+            backward_high: vec![usize::MAX; num_nodes].into_boxed_slice(),
+            forward_tot: vec![0; num_nodes].into_boxed_slice(),
+            backward_tot: vec![0; num_nodes].into_boxed_slice(),
+            dist: vec![usize::MAX; num_nodes].into_boxed_slice(),
+            forward_visit: breadth_first::Seq::new(graph),
+            backward_visit: breadth_first::Seq::new(transpose),
+            num_visits: 0,
+        }
+    }
+
+    /// Run the algorithm until the quantities specified by the level have
+    /// been computed.
+    pub fn run(&mut self, pl: Option<&mut ProgressLogger>) -> Result<()> {
+        let mut pl = pl;
+        if let Some(pl) = pl.as_mut() {
+            pl.item_name("visit");
+            pl.expected_updates(None);
+            pl.start("Running ExactSumSweep...");
+        }
+        while !self.step()? {
+            if let Some(pl) = pl.as_mut() {
+                pl.update_and_display();
+            }
+        }
+        if let Some(pl) = pl.as_mut() {
+            pl.done();
+        }
+        Ok(())
+    }
+
+    /// Perform a visit, unless the computation is already complete, and
+    /// return whether the computation is complete.
+    pub fn step(&mut self) -> Result<bool> {
+        if self.is_done() {
+            return Ok(true);
+        }
+
+        let (node, dir) = if self.num_visits == 0 {
+            // The node of maximum outdegree
+            let node = (0..self.num_nodes)
+                .max_by_key(|&node| (self.graph.outdegree(node), usize::MAX - node))
+                .unwrap();
+            (node, Dir::Forward)
+        } else {
+            // SumSweep: alternate backward and forward visits from the nodes
+            // that are farthest from the previous sources
+            let (node, dir) = match self.num_visits {
+                n if n >= NUM_SWEEPS => (usize::MAX, Dir::Forward),
+                n if n % 2 == 1 => (
+                    self.arg_max(&self.backward_tot, &self.backward_tot, Dir::Backward),
+                    Dir::Backward,
+                ),
+                _ => (
+                    self.arg_max(&self.forward_tot, &self.forward_tot, Dir::Forward),
+                    Dir::Forward,
+                ),
+            };
+            if node == usize::MAX {
+                self.choose()
+            } else {
+                (node, dir)
+            }
+        };
+
+        self.visit(node, dir)?;
+        Ok(self.is_done())
+    }
+
+    /// Choose the source and the direction of the next visit, rotating among
+    /// the heuristics appropriate for the level.
+    fn choose(&self) -> (usize, Dir) {
+        let choices: &[Choice] = match self.level {
+            Level::Radius => &[Choice::ForwardMinLow, Choice::BackwardMinForwardHigh],
+            Level::Diameter => &[Choice::ForwardMaxHigh, Choice::BackwardMaxHigh],
+            Level::RadiusDiameter => &[
+                Choice::ForwardMaxHigh,
+                Choice::BackwardMaxHigh,
+                Choice::ForwardMinLow,
+                Choice::BackwardMinForwardHigh,
+            ],
+            Level::AllForward => &[
+                Choice::ForwardMaxHigh,
+                Choice::BackwardMinForwardHigh,
+                Choice::ForwardMinLow,
+            ],
+            Level::All => &[
+                Choice::ForwardMaxHigh,
+                Choice::BackwardMaxHigh,
+                Choice::BackwardMinForwardHigh,
+                Choice::ForwardMinLow,
+            ],
+        };
+
+        // Try the heuristics in order, starting from the one associated
+        // with the current visit, until one has a candidate
+        let start = self.num_visits.saturating_sub(NUM_SWEEPS);
+        for i in 0..choices.len() {
+            let choice = choices[(start + i) % choices.len()];
+            let (node, dir) = match choice {
+                Choice::ForwardMaxHigh => (
+                    self.arg_max(&self.forward_high, &self.forward_tot, Dir::Forward),
+                    Dir::Forward,
+                ),
+                Choice::BackwardMaxHigh => (
+                    self.arg_max(&self.backward_high, &self.backward_tot, Dir::Backward),
+                    Dir::Backward,
+                ),
+                Choice::ForwardMinLow => (
+                    self.arg_min(&self.forward_low, &self.forward_tot, Dir::Forward),
+                    Dir::Forward,
+                ),
+                Choice::BackwardMinForwardHigh => (
+                    self.arg_min(&self.forward_high, &self.forward_tot, Dir::Backward),
+                    Dir::Backward,
+                ),
+            };
+            if node != usize::MAX {
+                return (node, dir);
+            }
+        }
+        // If all eccentricities in the directions used by the heuristics are
+        // known, the computation is complete
+        unreachable!("No candidate for the next visit, but the computation is not complete")
+    }
+
+    /// Return whether the eccentricity of a node in the given direction is
+    /// known.
+    fn is_exact(&self, node: usize, dir: Dir) -> bool {
+        match dir {
+            Dir::Forward => self.forward_low[node] == self.forward_high[node],
+            Dir::Backward => self.backward_low[node] == self.backward_high[node],
+        }
+    }
+
+    /// Return the node maximizing `key` (breaking ties by `tie`) among those
+    /// whose eccentricity in the given direction is not known, or `usize::MAX`
+    /// if there is no such node.
+    fn arg_max(&self, key: &[usize], tie: &[usize], dir: Dir) -> usize {
+        (0..self.num_nodes)
+            .filter(|&node| !self.is_exact(node, dir))
+            .max_by_key(|&node| (key[node], tie[node], usize::MAX - node))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Return the node minimizing `key` (breaking ties by `tie`) among those
+    /// whose eccentricity in the given direction is not known, or `usize::MAX`
+    /// if there is no such node.
+    fn arg_min(&self, key: &[usize], tie: &[usize], dir: Dir) -> usize {
+        (0..self.num_nodes)
+            .filter(|&node| !self.is_exact(node, dir))
+            .min_by_key(|&node| (key[node], tie[node], node))
+            .unwrap_or(usize::MAX)
+    }
+
+    /// Perform a visit and update the bounds.
+    fn visit(&mut self, start: usize, dir: Dir) -> Result<()> {
+        let (ecc, reached) = match dir {
+            Dir::Forward => bfs(&mut self.forward_visit, start, &mut self.dist),
+            Dir::Backward => bfs(&mut self.backward_visit, start, &mut self.dist),
+        };
+        ensure!(
+            reached == self.num_nodes,
+            "The graph is not strongly connected: a {} visit from node {} reached {} nodes out of {}",
+            match dir {
+                Dir::Forward => "forward",
+                Dir::Backward => "backward",
+            },
+            start,
+            reached,
+            self.num_nodes
+        );
+        self.num_visits += 1;
+
+        // A forward visit from v gives the forward eccentricity of v and the
+        // distances d(v, w), which bound the backward eccentricities, as
+        // d(v, w) ≤ ecc_b(w) ≤ d(v, w) + ecc_b(v); symmetrically for backward
+        // visits
+        let (ecc_low, ecc_high, low, high, tot) = match dir {
+            Dir::Forward => (
+                &mut self.forward_low,
+                &mut self.forward_high,
+                &mut self.backward_low,
+                &mut self.backward_high,
+                &mut self.backward_tot,
+            ),
+            Dir::Backward => (
+                &mut self.backward_low,
+                &mut self.backward_high,
+                &mut self.forward_low,
+                &mut self.forward_high,
+                &mut self.forward_tot,
+            ),
+        };
+        ecc_low[start] = ecc;
+        ecc_high[start] = ecc;
+        let start_high = high[start];
+        for (node, &d) in self.dist.iter().enumerate() {
+            low[node] = low[node].max(d);
+            high[node] = high[node].min(d.saturating_add(start_high));
+            tot[node] += d;
+        }
+
+        log::debug!(
+            "Visit {} ({:?} from {}): radius in [{}..{}], diameter in [{}..{}]",
+            self.num_visits,
+            dir,
+            start,
+            self.radius_lower_bound(),
+            DisplayBound(self.radius_upper_bound()),
+            self.diameter_lower_bound(),
+            DisplayBound(self.diameter_upper_bound()),
+        );
+        Ok(())
+    }
+
+    /// Return whether the quantities specified by the level have been
+    /// computed.
+    pub fn is_done(&self) -> bool {
+        if self.num_nodes == 0 {
+            return true;
+        }
+        let radius_done = || self.radius_lower_bound() == self.radius_upper_bound();
+        let diameter_done = || self.diameter_lower_bound() == self.diameter_upper_bound();
+        let all_done = |dir| (0..self.num_nodes).all(|node| self.is_exact(node, dir));
+        match self.level {
+            Level::Radius => radius_done(),
+            Level::Diameter => diameter_done(),
+            Level::RadiusDiameter => radius_done() && diameter_done(),
+            Level::AllForward => all_done(Dir::Forward),
+            Level::All => all_done(Dir::Forward) && all_done(Dir::Backward),
+        }
+    }
+
+    /// Return the number of visits performed so far.
+    pub fn num_visits(&self) -> usize {
+        self.num_visits
+    }
+
+    /// Return a lower bound on the radius.
+    pub fn radius_lower_bound(&self) -> usize {
+        self.forward_low.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Return an upper bound on the radius, or `usize::MAX` if no upper bound
+    /// is known yet.
+    pub fn radius_upper_bound(&self) -> usize {
+        self.forward_high.iter().copied().min().unwrap_or(0)
+    }
+
+    /// Return the radius, if it has been computed.
+    pub fn radius(&self) -> Option<usize> {
+        let radius = self.radius_upper_bound();
+        (self.radius_lower_bound() == radius).then_some(radius)
+    }
+
+    /// Return a node whose forward eccentricity is at most the
+    /// [upper bound on the radius](Self::radius_upper_bound), and thus equal
+    /// to the radius when the latter is known.
+    pub fn radial_vertex(&self) -> usize {
+        (0..self.num_nodes)
+            .min_by_key(|&node| self.forward_high[node])
+            .unwrap_or(0)
+    }
+
+    /// Return a lower bound on the diameter.
+    pub fn diameter_lower_bound(&self) -> usize {
+        let forward = self.forward_low.iter().copied().max().unwrap_or(0);
+        let backward = self.backward_low.iter().copied().max().unwrap_or(0);
+        forward.max(backward)
+    }
+
+    /// Return an upper bound on the diameter, or `usize::MAX` if no upper
+    /// bound is known yet.
+    pub fn diameter_upper_bound(&self) -> usize {
+        let forward = self.forward_high.iter().copied().max().unwrap_or(0);
+        let backward = self.backward_high.iter().copied().max().unwrap_or(0);
+        forward.min(backward)
+    }
+
+    /// Return the diameter, if it has been computed.
+    pub fn diameter(&self) -> Option<usize> {
+        let diameter = self.diameter_lower_bound();
+        (self.diameter_upper_bound() == diameter).then_some(diameter)
+    }
+
+    /// Return a node whose forward or backward eccentricity is equal to the
+    /// [lower bound on the diameter](Self::diameter_lower_bound), and thus to
+    /// the diameter when the latter is known.
+    pub fn diametral_vertex(&self) -> usize {
+        (0..self.num_nodes)
+            .max_by_key(|&node| self.forward_low[node].max(self.backward_low[node]))
+            .unwrap_or(0)
+    }
+
+    /// Return the forward eccentricity of a node, if it is known.
+    pub fn forward_eccentricity(&self, node: usize) -> Option<usize> {
+        self.is_exact(node, Dir::Forward)
+            .then_some(self.forward_low[node])
+    }
+
+    /// Return the backward eccentricity of a node, if it is known.
+    pub fn backward_eccentricity(&self, node: usize) -> Option<usize> {
+        self.is_exact(node, Dir::Backward)
+            .then_some(self.backward_low[node])
+    }
+
+    /// Return lower and upper bounds on the forward eccentricity of a node.
+    ///
+    /// The upper bound is `usize::MAX` if no upper bound is known yet.
+    pub fn forward_eccentricity_bounds(&self, node: usize) -> (usize, usize) {
+        (self.forward_low[node], self.forward_high[node])
+    }
+
+    /// Return lower and upper bounds on the backward eccentricity of a node.
+    ///
+    /// The upper bound is `usize::MAX` if no upper bound is known yet.
+    pub fn backward_eccentricity_bounds(&self, node: usize) -> (usize, usize) {
+        (self.backward_low[node], self.backward_high[node])
+    }
+}
+
+/// A wrapper displaying a bound returned by [`SumSweep`], using ∞ for
+/// `usize::MAX` (i.e., for upper bounds that are not yet known).
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayBound(pub usize);
+
+impl std::fmt::Display for DisplayBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0 == usize::MAX {
+            f.write_str("∞")
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+/// Perform a breadth-first visit from `start`, storing in `dist` the distance
+/// of each node from `start` (`usize::MAX` if unreachable), and return the
+/// eccentricity of `start` and the number of reached nodes.
+fn bfs<G: RandomAccessGraph>(
+    visit: &mut breadth_first::Seq<G>,
+    start: usize,
+    dist: &mut [usize],
+) -> (usize, usize) {
+    dist.fill(usize::MAX);
+    visit.reset();
+    let mut ecc = 0;
+    let mut reached = 0;
+    let _ = visit.visit(start, |event| {
+        if let Event::Unknown { node, distance, .. } = event {
+            dist[node] = distance;
+            ecc = distance;
+            reached += 1;
+        }
+        ControlFlow::<Infallible>::Continue(())
+    });
+    (ecc, reached)
+}
@@ -0,0 +1,288 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::llp::UnsafeSlice;
+use crate::traits::*;
+use dsi_progress_logger::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use sux::traits::Succ;
+
+/// The triangles of a symmetric graph.
+///
+/// Besides the number of triangles each node belongs to, this structure
+/// records the degree of each node (loops excluded), so that it can compute
+/// [local](Triangles::local_clustering_coefficients),
+/// [average](Triangles::average_clustering_coefficient) and
+/// [global](Triangles::global_clustering_coefficient) clustering
+/// coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triangles {
+    triangles: Box<[usize]>,
+    degrees: Box<[usize]>,
+}
+
+impl Triangles {
+    /// Create a new instance from the number of triangles of each node and
+    /// the degree of each node.
+    pub fn new(triangles: Box<[usize]>, degrees: Box<[usize]>) -> Self {
+        assert_eq!(triangles.len(), degrees.len());
+        Triangles { triangles, degrees }
+    }
+
+    /// Return a slice containing, for each node, the number of triangles it
+    /// belongs to.
+    pub fn triangles(&self) -> &[usize] {
+        &self.triangles
+    }
+
+    /// Consume self and return the number of triangles of each node.
+    pub fn into_triangles(self) -> Box<[usize]> {
+        self.triangles
+    }
+
+    /// Return a slice containing, for each node, its degree, loops excluded.
+    pub fn degrees(&self) -> &[usize] {
+        &self.degrees
+    }
+
+    /// Return the number of triangles of the graph.
+    pub fn num_triangles(&self) -> u64 {
+        self.triangles.iter().map(|&t| t as u64).sum::<u64>() / 3
+    }
+
+    /// Return the number of wedges (paths of length two, or connected
+    /// triples) of the graph.
+    pub fn num_wedges(&self) -> u64 {
+        self.degrees
+            .iter()
+            .map(|&d| d as u64 * (d as u64).saturating_sub(1) / 2)
+            .sum()
+    }
+
+    /// Return the local clustering coefficient of each node, that is, the
+    /// fraction of pairs of neighbors of the node that are adjacent.
+    ///
+    /// Nodes with less than two neighbors have coefficient zero.
+    pub fn local_clustering_coefficients(&self) -> Box<[f64]> {
+        self.triangles
+            .iter()
+            .zip(self.degrees.iter())
+            .map(|(&t, &d)| {
+                if d < 2 {
+                    0.0
+                } else {
+                    2.0 * t as f64 / (d as f64 * (d - 1) as f64)
+                }
+            })
+            .collect()
+    }
+
+    /// Return the average of the local clustering coefficients (i.e., the
+    /// Watts–Strogatz clustering coefficient).
+    ///
+    /// Nodes with less than two neighbors contribute zero to the average.
+    pub fn average_clustering_coefficient(&self) -> f64 {
+        if self.triangles.is_empty() {
+            return 0.0;
+        }
+        self.local_clustering_coefficients().iter().sum::<f64>() / self.triangles.len() as f64
+    }
+
+    /// Return the global clustering coefficient (a.k.a. transitivity), that
+    /// is, three times the number of triangles divided by the number of
+    /// wedges.
+    ///
+    /// If the graph has no wedges, the coefficient is zero.
+    pub fn global_clustering_coefficient(&self) -> f64 {
+        let num_wedges = self.num_wedges();
+        if num_wedges == 0 {
+            0.0
+        } else {
+            3.0 * self.num_triangles() as f64 / num_wedges as f64
+        }
+    }
+}
+
+/// Count in parallel the triangles of a symmetric graph.
+///
+/// This function implements the _forward_ algorithm: edges are oriented from
+/// the endpoint of smaller degree to the endpoint of larger degree (ties are
+/// broken by node identifier), and each triangle is enumerated once, from its
+/// lowest endpoint _u_, by intersecting with a merge the oriented successors
+/// of _u_ and of each oriented successor _v_ of _u_. Since every node has at
+/// most _O_(√_m_) oriented successors, where _m_ is the number of edges, the
+/// running time is _O_(_m_<sup>3/2</sup>).
+///
+/// Successor lists must be [sorted](SortedIterator) and must not contain
+/// duplicates; loops are ignored.
+///
+/// The oriented successor lists are built once, in parallel, using the
+/// degree cumulative function of the graph to balance the work among threads,
+/// as in [par_apply](crate::traits::SequentialLabeling::par_apply), and
+/// stored in compressed-sparse-row format. Besides the graph, this function
+/// thus needs three `usize` per node and a `usize` per edge (i.e., a `usize`
+/// every two arcs of the symmetric graph).
+///
+/// The graph is assumed to be symmetric, but no check is performed: on a
+/// non-symmetric graph, the results are meaningless.
+pub fn triangles<G>(
+    graph: &G,
+    deg_cumul: &(impl Succ<Input = usize, Output = usize> + Send + Sync),
+    mut threads: impl AsMut<rayon::ThreadPool>,
+    pl: Option<&mut ProgressLogger>,
+) -> Triangles
+where
+    G: RandomAccessGraph + Sync,
+    for<'a> <<G as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter: SortedIterator,
+{
+    let num_nodes = graph.num_nodes();
+    let granularity = ((graph.num_arcs() >> 9) as usize).max(1024);
+    let threads = threads.as_mut();
+
+    let mut pl = pl;
+    if let Some(pl) = pl.as_mut() {
+        pl.info(format_args!("Computing degrees..."));
+    }
+
+    // Degrees, loops excluded
+    let mut degrees = vec![0; num_nodes];
+    {
+        let degrees = UnsafeSlice::new(&mut degrees);
+        graph.par_apply(
+            |range| {
+                for u in range {
+                    let degree = graph.successors(u).into_iter().filter(|&v| v != u).count();
+                    // SAFETY: each node is processed by exactly one thread
+                    unsafe { degrees.write(u, degree) };
+                }
+            },
+            |(), ()| (),
+            granularity,
+            deg_cumul,
+            threads,
+            None,
+        );
+    }
+
+    // Whether the edge between u and v is oriented from u to v
+    let forward = |u: usize, v: usize| (degrees[u], u) < (degrees[v], v);
+
+    if let Some(pl) = pl.as_mut() {
+        pl.info(format_args!("Building oriented successor lists..."));
+    }
+
+    // Offsets of the oriented successor lists: we first store the number of
+    // oriented successors of node u at position u + 1, and then compute
+    // prefix sums
+    let mut offsets = vec![0; num_nodes + 1];
+    {
+        let offsets = UnsafeSlice::new(&mut offsets);
+        graph.par_apply(
+            |range| {
+                for u in range {
+                    let forward_degree = graph
+                        .successors(u)
+                        .into_iter()
+                        .filter(|&v| forward(u, v))
+                        .count();
+                    // SAFETY: each node is processed by exactly one thread
+                    unsafe { offsets.write(u + 1, forward_degree) };
+                }
+            },
+            |(), ()| (),
+            granularity,
+            deg_cumul,
+            threads,
+            None,
+        );
+    }
+    for u in 0..num_nodes {
+        offsets[u + 1] += offsets[u];
+    }
+
+    // Oriented successor lists, sorted as the original successor lists
+    let mut succ = vec![0; offsets[num_nodes]];
+    {
+        let succ = UnsafeSlice::new(&mut succ);
+        let offsets = &offsets;
+        graph.par_apply(
+            |range| {
+                for u in range {
+                    let oriented = graph.successors(u).into_iter().filter(|&v| forward(u, v));
+                    for (i, v) in (offsets[u]..).zip(oriented) {
+                        // SAFETY: each node writes only in its own range
+                        unsafe { succ.write(i, v) };
+                    }
+                }
+            },
+            |(), ()| (),
+            granularity,
+            deg_cumul,
+            threads,
+            None,
+        );
+    }
+    let succ = |u: usize| &succ[offsets[u]..offsets[u + 1]];
+
+    if let Some(pl) = pl.as_mut() {
+        pl.item_name("node");
+        pl.expected_updates(Some(num_nodes));
+        pl.start("Counting triangles...");
+    }
+
+    let triangles = (0..num_nodes)
+        .map(|_| AtomicUsize::new(0))
+        .collect::<Box<[_]>>();
+
+    // Oriented successor lists have different lengths than successor lists,
+    // so we balance the work by nodes, rather than by arcs
+    graph.par_node_apply(
+        |range| {
+            for u in range {
+                let succ_u = succ(u);
+                let mut count_u = 0;
+                for &v in succ_u {
+                    let succ_v = succ(v);
+                    let (mut i, mut j) = (0, 0);
+                    while i < succ_u.len() && j < succ_v.len() {
+                        match succ_u[i].cmp(&succ_v[j]) {
+                            std::cmp::Ordering::Less => i += 1,
+                            std::cmp::Ordering::Greater => j += 1,
+                            std::cmp::Ordering::Equal => {
+                                count_u += 1;
+                                triangles[v].fetch_add(1, Ordering::Relaxed);
+                                triangles[succ_u[i]].fetch_add(1, Ordering::Relaxed);
+                                i += 1;
+                                j += 1;
+                            }
+                        }
+                    }
+                }
+                if count_u != 0 {
+                    triangles[u].fetch_add(count_u, Ordering::Relaxed);
+                }
+            }
+        },
+        |(), ()| (),
+        1024,
+        threads,
+        pl.as_deref_mut(),
+    );
+
+    if let Some(pl) = pl.as_mut() {
+        pl.done();
+    }
+
+    Triangles::new(
+        triangles
+            .into_vec()
+            .into_iter()
+            .map(AtomicUsize::into_inner)
+            .collect(),
+        degrees.into_boxed_slice(),
+    )
+}
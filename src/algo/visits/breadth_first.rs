@@ -0,0 +1,276 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Breadth-first visits.
+//!
+//! [`Seq`] is a sequential visit, whereas [`Par`] is a parallel visit that
+//! processes each frontier (i.e., the set of nodes at the same distance from
+//! the root) in parallel.
+
+use crate::traits::RandomAccessGraph;
+use rayon::prelude::*;
+use std::ops::ControlFlow::{self, Continue};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use sux::prelude::BitVec;
+
+/// The events generated by a breadth-first visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The visit from `root` is starting.
+    Init { root: usize },
+    /// The node has been discovered, traversing an arc from `pred` (the root
+    /// is discovered with itself as `pred`), and it is at the given distance
+    /// from the root.
+    Unknown {
+        node: usize,
+        pred: usize,
+        root: usize,
+        distance: usize,
+    },
+    /// An arc from `pred` to a node that was already discovered has been
+    /// traversed.
+    Known {
+        node: usize,
+        pred: usize,
+        root: usize,
+    },
+    /// The frontier at the given distance from the root, which contains
+    /// `size` nodes, is going to be processed.
+    FrontierSize { distance: usize, size: usize },
+    /// The visit from `root` is complete.
+    Done { root: usize },
+}
+
+/// A sequential breadth-first visit.
+///
+/// Nodes are discovered in the same order of a classical queue-based visit.
+pub struct Seq<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    visited: BitVec,
+    curr: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl<'a, G: RandomAccessGraph> Seq<'a, G> {
+    /// Create a new sequential breadth-first visit on a graph.
+    pub fn new(graph: &'a G) -> Self {
+        Self {
+            graph,
+            visited: BitVec::new(graph.num_nodes()),
+            curr: vec![],
+            next: vec![],
+        }
+    }
+
+    /// Visit the nodes reachable from `root` that have not been visited yet.
+    ///
+    /// If `root` has already been visited, no event is generated.
+    pub fn visit<E>(
+        &mut self,
+        root: usize,
+        mut callback: impl FnMut(Event) -> ControlFlow<E, ()>,
+    ) -> ControlFlow<E, ()> {
+        if self.visited[root] {
+            return Continue(());
+        }
+        let Self {
+            graph,
+            visited,
+            curr,
+            next,
+        } = self;
+
+        callback(Event::Init { root })?;
+        visited.set(root, true);
+        callback(Event::Unknown {
+            node: root,
+            pred: root,
+            root,
+            distance: 0,
+        })?;
+
+        curr.clear();
+        curr.push(root);
+        let mut distance = 0;
+        while !curr.is_empty() {
+            callback(Event::FrontierSize {
+                distance,
+                size: curr.len(),
+            })?;
+            distance += 1;
+            for &node in curr.iter() {
+                for succ in graph.successors(node) {
+                    if visited[succ] {
+                        callback(Event::Known {
+                            node: succ,
+                            pred: node,
+                            root,
+                        })?;
+                    } else {
+                        visited.set(succ, true);
+                        callback(Event::Unknown {
+                            node: succ,
+                            pred: node,
+                            root,
+                            distance,
+                        })?;
+                        next.push(succ);
+                    }
+                }
+            }
+            std::mem::swap(curr, next);
+            next.clear();
+        }
+
+        callback(Event::Done { root })
+    }
+
+    /// Visit all nodes of the graph, using as roots, in increasing order, the
+    /// nodes that have not been visited yet.
+    pub fn visit_all<E>(
+        &mut self,
+        mut callback: impl FnMut(Event) -> ControlFlow<E, ()>,
+    ) -> ControlFlow<E, ()> {
+        for root in 0..self.graph.num_nodes() {
+            self.visit(root, &mut callback)?;
+        }
+        Continue(())
+    }
+
+    /// Return whether a node has been visited.
+    pub fn is_visited(&self, node: usize) -> bool {
+        self.visited[node]
+    }
+
+    /// Reset the visit, marking all nodes as not visited.
+    pub fn reset(&mut self) {
+        self.visited.fill(false);
+    }
+}
+
+/// A parallel breadth-first visit.
+///
+/// Each frontier is split in chunks of `granularity` nodes, which are
+/// processed in parallel; the callback must thus be [`Sync`]. The
+/// distance of each node from the root is the same as in a sequential visit,
+/// but nodes with the same distance are discovered in an unspecified order,
+/// and the `pred` of each discovered node is one of its predecessors in the
+/// previous frontier.
+pub struct Par<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    visited: Box<[AtomicBool]>,
+    granularity: usize,
+}
+
+impl<'a, G: RandomAccessGraph + Sync> Par<'a, G> {
+    /// Create a new parallel breadth-first visit on a graph, processing
+    /// chunks of `granularity` nodes of each frontier in parallel.
+    pub fn new(graph: &'a G, granularity: usize) -> Self {
+        Self {
+            graph,
+            visited: (0..graph.num_nodes())
+                .map(|_| AtomicBool::new(false))
+                .collect(),
+            granularity: granularity.max(1),
+        }
+    }
+
+    /// Visit the nodes reachable from `root` that have not been visited yet
+    /// using the given thread pool.
+    ///
+    /// If `root` has already been visited, no event is generated.
+    pub fn visit<E: Send>(
+        &mut self,
+        root: usize,
+        callback: impl Fn(Event) -> ControlFlow<E, ()> + Sync,
+        thread_pool: &rayon::ThreadPool,
+    ) -> ControlFlow<E, ()> {
+        if self.visited[root].swap(true, Ordering::Relaxed) {
+            return Continue(());
+        }
+        let graph = self.graph;
+        let visited = &self.visited;
+
+        callback(Event::Init { root })?;
+        callback(Event::Unknown {
+            node: root,
+            pred: root,
+            root,
+            distance: 0,
+        })?;
+
+        let mut curr = vec![root];
+        let next = Mutex::new(vec![]);
+        let mut distance = 0;
+        while !curr.is_empty() {
+            callback(Event::FrontierSize {
+                distance,
+                size: curr.len(),
+            })?;
+            distance += 1;
+            thread_pool.install(|| {
+                curr.par_chunks(self.granularity).try_for_each(|chunk| {
+                    let mut local = vec![];
+                    for &node in chunk {
+                        for succ in graph.successors(node) {
+                            // We check before swapping to avoid writing to
+                            // the cache line of nodes already visited
+                            if visited[succ].load(Ordering::Relaxed)
+                                || visited[succ].swap(true, Ordering::Relaxed)
+                            {
+                                callback(Event::Known {
+                                    node: succ,
+                                    pred: node,
+                                    root,
+                                })?;
+                            } else {
+                                callback(Event::Unknown {
+                                    node: succ,
+                                    pred: node,
+                                    root,
+                                    distance,
+                                })?;
+                                local.push(succ);
+                            }
+                        }
+                    }
+                    next.lock().unwrap().extend(local);
+                    Continue(())
+                })
+            })?;
+            curr = std::mem::take(&mut *next.lock().unwrap());
+        }
+
+        callback(Event::Done { root })
+    }
+
+    /// Visit all nodes of the graph, using as roots, in increasing order, the
+    /// nodes that have not been visited yet.
+    pub fn visit_all<E: Send>(
+        &mut self,
+        callback: impl Fn(Event) -> ControlFlow<E, ()> + Sync,
+        thread_pool: &rayon::ThreadPool,
+    ) -> ControlFlow<E, ()> {
+        for root in 0..self.graph.num_nodes() {
+            self.visit(root, &callback, thread_pool)?;
+        }
+        Continue(())
+    }
+
+    /// Return whether a node has been visited.
+    pub fn is_visited(&self, node: usize) -> bool {
+        self.visited[node].load(Ordering::Relaxed)
+    }
+
+    /// Reset the visit, marking all nodes as not visited.
+    pub fn reset(&mut self) {
+        self.visited
+            .iter()
+            .for_each(|v| v.store(false, Ordering::Relaxed));
+    }
+}
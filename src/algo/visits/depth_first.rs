@@ -0,0 +1,158 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Depth-first visits.
+//!
+//! [`Seq`] is an iterative (i.e., non-recursive) visit, so it does not depend
+//! on the size of the thread stack.
+
+use crate::traits::RandomAccessGraph;
+use std::ops::ControlFlow::{self, Continue};
+use sux::prelude::BitVec;
+
+/// The events generated by a depth-first visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The visit from `root` is starting.
+    Init { root: usize },
+    /// The node has been discovered, traversing an arc from `parent` (the root
+    /// is discovered with itself as `parent`), and it is at the given depth in
+    /// the visit tree.
+    Previsit {
+        node: usize,
+        parent: usize,
+        root: usize,
+        depth: usize,
+    },
+    /// An arc from `parent` to a node that was already discovered has been
+    /// traversed; `on_stack` is true if the node is an ancestor of `parent`
+    /// in the visit tree (or `parent` itself), that is, if the arc closes a
+    /// cycle.
+    Revisit {
+        node: usize,
+        parent: usize,
+        root: usize,
+        on_stack: bool,
+    },
+    /// All successors of the node have been visited.
+    Postvisit {
+        node: usize,
+        parent: usize,
+        root: usize,
+        depth: usize,
+    },
+    /// The visit from `root` is complete.
+    Done { root: usize },
+}
+
+/// A sequential depth-first visit.
+pub struct Seq<'a, G: RandomAccessGraph> {
+    graph: &'a G,
+    visited: BitVec,
+    on_stack: BitVec,
+}
+
+impl<'a, G: RandomAccessGraph> Seq<'a, G> {
+    /// Create a new depth-first visit on a graph.
+    pub fn new(graph: &'a G) -> Self {
+        let num_nodes = graph.num_nodes();
+        Self {
+            graph,
+            visited: BitVec::new(num_nodes),
+            on_stack: BitVec::new(num_nodes),
+        }
+    }
+
+    /// Visit the nodes reachable from `root` that have not been visited yet.
+    ///
+    /// If `root` has already been visited, no event is generated.
+    pub fn visit<E>(
+        &mut self,
+        root: usize,
+        mut callback: impl FnMut(Event) -> ControlFlow<E, ()>,
+    ) -> ControlFlow<E, ()> {
+        if self.visited[root] {
+            return Continue(());
+        }
+        let graph = self.graph;
+
+        callback(Event::Init { root })?;
+        self.visited.set(root, true);
+        self.on_stack.set(root, true);
+        callback(Event::Previsit {
+            node: root,
+            parent: root,
+            root,
+            depth: 0,
+        })?;
+
+        // Each element contains a node, its parent, and an iterator on its
+        // remaining successors
+        let mut stack = vec![(root, root, graph.successors(root).into_iter())];
+
+        while !stack.is_empty() {
+            let depth = stack.len() - 1;
+            let (node, _, succ) = &mut stack[depth];
+            let node = *node;
+
+            if let Some(succ) = succ.next() {
+                if self.visited[succ] {
+                    callback(Event::Revisit {
+                        node: succ,
+                        parent: node,
+                        root,
+                        on_stack: self.on_stack[succ],
+                    })?;
+                } else {
+                    self.visited.set(succ, true);
+                    self.on_stack.set(succ, true);
+                    callback(Event::Previsit {
+                        node: succ,
+                        parent: node,
+                        root,
+                        depth: depth + 1,
+                    })?;
+                    stack.push((succ, node, graph.successors(succ).into_iter()));
+                }
+            } else {
+                let (node, parent, _) = stack.pop().unwrap();
+                self.on_stack.set(node, false);
+                callback(Event::Postvisit {
+                    node,
+                    parent,
+                    root,
+                    depth,
+                })?;
+            }
+        }
+
+        callback(Event::Done { root })
+    }
+
+    /// Visit all nodes of the graph, using as roots, in increasing order, the
+    /// nodes that have not been visited yet.
+    pub fn visit_all<E>(
+        &mut self,
+        mut callback: impl FnMut(Event) -> ControlFlow<E, ()>,
+    ) -> ControlFlow<E, ()> {
+        for root in 0..self.graph.num_nodes() {
+            self.visit(root, &mut callback)?;
+        }
+        Continue(())
+    }
+
+    /// Return whether a node has been visited.
+    pub fn is_visited(&self, node: usize) -> bool {
+        self.visited[node]
+    }
+
+    /// Reset the visit, marking all nodes as not visited.
+    pub fn reset(&mut self) {
+        self.visited.fill(false);
+        self.on_stack.fill(false);
+    }
+}
@@ -0,0 +1,57 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Visits on graphs.
+//!
+//! This module provides [breadth-first](breadth_first) visits, both sequential
+//! and parallel, and [depth-first](depth_first) visits on a
+//! [`RandomAccessGraph`](crate::traits::RandomAccessGraph).
+//!
+//! Visits are driven by a callback that is invoked on a number of events (the
+//! discovery of a node, the traversal of an arc, etc.; see the `Event` type of
+//! each module), with the information available about the event (e.g., the
+//! parent of the node, or its distance from the root). The callback returns a
+//! [`ControlFlow`](std::ops::ControlFlow): returning
+//! [`Break`](std::ops::ControlFlow::Break) interrupts the visit, and the value
+//! associated with the break is returned by the visit method. Callbacks that
+//! never interrupt the visit can use [`Infallible`](std::convert::Infallible)
+//! as break type.
+//!
+//! Visit structures keep track of visited nodes across calls to their `visit`
+//! method, so that, for example, calling `visit` on all nodes will visit each
+//! node exactly once (`visit_all` is a convenience method doing exactly
+//! this). To start afresh, call `reset`. If a visit is interrupted, the state
+//! of the structure is undefined until `reset` is called.
+//!
+//! # Examples
+//!
+//! Computing the distances from node 0 and stopping as soon as node 3 is
+//! discovered:
+//!
+//! ```
+//! use std::ops::ControlFlow::{Break, Continue};
+//! use webgraph::algo::visits::breadth_first::{Event, Seq};
+//! use webgraph::graphs::vec_graph::VecGraph;
+//! use webgraph::labels::proj::Left;
+//!
+//! let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 3), (0, 4), (4, 3)]));
+//! let mut dist = vec![usize::MAX; 5];
+//! let mut visit = Seq::new(&graph);
+//! let result = visit.visit(0, |event| {
+//!     if let Event::Unknown { node, distance, .. } = event {
+//!         dist[node] = distance;
+//!         if node == 3 {
+//!             return Break(distance);
+//!         }
+//!     }
+//!     Continue(())
+//! });
+//! assert_eq!(result, Break(2));
+//! ```
+
+pub mod breadth_first;
+pub mod depth_first;
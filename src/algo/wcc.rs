@@ -0,0 +1,199 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::scc::{compute_sizes, sort_by_size};
+use crate::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The weakly connected components of a graph.
+///
+/// Components are numbered from zero; the component of each node
+/// is available through [`components`](Wccs::components).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wccs {
+    num_components: usize,
+    components: Box<[usize]>,
+}
+
+impl Wccs {
+    /// Create a new instance from the number of components and the
+    /// component of each node.
+    pub fn new(num_components: usize, components: Box<[usize]>) -> Self {
+        Wccs {
+            num_components,
+            components,
+        }
+    }
+
+    /// Return the number of weakly connected components.
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Return a slice containing, for each node, its component.
+    pub fn components(&self) -> &[usize] {
+        &self.components
+    }
+
+    /// Consume self and return the component of each node.
+    pub fn into_components(self) -> Box<[usize]> {
+        self.components
+    }
+
+    /// Compute and return the size of each component.
+    pub fn compute_sizes(&self) -> Box<[usize]> {
+        compute_sizes(&self.components, self.num_components)
+    }
+
+    /// Renumber components by non-increasing size, so that component zero is
+    /// the largest (giant) one, and return the sizes of the renumbered
+    /// components.
+    ///
+    /// Components of the same size keep their relative order.
+    pub fn sort_by_size(&mut self) -> Box<[usize]> {
+        sort_by_size(&mut self.components, self.num_components)
+    }
+}
+
+/// A lock-free union-find structure.
+///
+/// Roots are always the smallest node of their set: when merging two sets,
+/// the root with the larger index is linked to the root with the smaller
+/// index, so parents are always smaller than their children, and concurrent
+/// unions cannot create cycles. Paths are halved during finds.
+struct UnionFind {
+    parent: Box<[AtomicUsize]>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).map(AtomicUsize::new).collect(),
+        }
+    }
+
+    fn find(&self, mut x: usize) -> usize {
+        loop {
+            let p = self.parent[x].load(Ordering::Relaxed);
+            if p == x {
+                return x;
+            }
+            let g = self.parent[p].load(Ordering::Relaxed);
+            // Path halving: g is an ancestor of x in any case, so failure
+            // is harmless
+            let _ =
+                self.parent[x].compare_exchange_weak(p, g, Ordering::Relaxed, Ordering::Relaxed);
+            x = g;
+        }
+    }
+
+    fn union(&self, x: usize, y: usize) {
+        let (mut x, mut y) = (x, y);
+        loop {
+            x = self.find(x);
+            y = self.find(y);
+            if x == y {
+                return;
+            }
+            let (small, large) = if x < y { (x, y) } else { (y, x) };
+            if self.parent[large]
+                .compare_exchange(large, small, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+}
+
+/// Compute the weakly connected components of a graph in parallel.
+///
+/// The graph is scanned once using [`SplitLabeling`], and each arc is fed to a
+/// lock-free union-find structure, so there is no need for the transpose, and
+/// the graph can be accessed sequentially (e.g., as a
+/// [`BVGraphSeq`](crate::graphs::bvgraph::BVGraphSeq)). Besides the
+/// graph, this function needs just a `usize` per node, as the array of the
+/// union-find structure is reused to store the components.
+///
+/// Components are numbered in the order of their smallest node: in particular,
+/// node zero is always in component zero.
+pub fn wcc<G>(
+    graph: &G,
+    mut threads: impl AsMut<rayon::ThreadPool>,
+    pl: Option<&mut ProgressLogger>,
+) -> Wccs
+where
+    G: SequentialGraph + SplitLabeling,
+{
+    let num_nodes = graph.num_nodes();
+    let mut pl = pl;
+    if let Some(pl) = pl.as_mut() {
+        pl.item_name("node");
+        pl.expected_updates(Some(num_nodes));
+        pl.start("Computing weakly connected components...");
+    }
+    let pl_lock = pl.map(Mutex::new);
+
+    let union_find = UnionFind::new(num_nodes);
+    let pool = threads.as_mut();
+    let num_threads = pool.current_num_threads();
+
+    pool.in_place_scope(|scope| {
+        for iter in graph.split_iter(num_threads) {
+            let union_find = &union_find;
+            let pl_lock = &pl_lock;
+            scope.spawn(move |_| {
+                let mut count = 0;
+                for_!( (node, succ) in iter {
+                    for s in succ {
+                        union_find.union(node, s);
+                    }
+                    count += 1;
+                    if count == 1 << 16 {
+                        if let Some(pl_lock) = pl_lock {
+                            pl_lock.lock().unwrap().update_with_count(count);
+                        }
+                        count = 0;
+                    }
+                });
+                if let Some(pl_lock) = pl_lock {
+                    pl_lock.lock().unwrap().update_with_count(count);
+                }
+            });
+        }
+    });
+
+    if let Some(pl_lock) = pl_lock {
+        pl_lock.into_inner().unwrap().done();
+    }
+
+    // The parent array is reused in place to store components: as parents
+    // are smaller than their children, when we reach a node its parent has
+    // already been replaced by its component, which is also the component
+    // of the node
+    let mut components = union_find
+        .parent
+        .into_vec()
+        .into_iter()
+        .map(AtomicUsize::into_inner)
+        .collect::<Vec<_>>();
+    let mut num_components = 0;
+    for node in 0..num_nodes {
+        let parent = components[node];
+        if parent == node {
+            components[node] = num_components;
+            num_components += 1;
+        } else {
+            components[node] = components[parent];
+        }
+    }
+
+    Wccs::new(num_components, components.into_boxed_slice())
+}
@@ -6,14 +6,15 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use crate::algo::visits::breadth_first;
 use crate::prelude::*;
 use anyhow::Result;
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
-use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::ops::ControlFlow;
 use std::path::PathBuf;
-use sux::prelude::BitVec;
 
 pub const COMMAND_NAME: &str = "bf-visit";
 
@@ -80,8 +81,7 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
 
 fn visit(graph: impl RandomAccessGraph) -> Result<()> {
     let num_nodes = graph.num_nodes();
-    let mut visited = BitVec::new(num_nodes);
-    let mut queue = VecDeque::new();
+    let mut visit = breadth_first::Seq::new(&graph);
 
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
@@ -90,24 +90,12 @@ fn visit(graph: impl RandomAccessGraph) -> Result<()> {
         .expected_updates(Some(num_nodes));
     pl.start("Visiting graph...");
 
-    for start in 0..num_nodes {
-        if visited[start] {
-            continue;
-        }
-        queue.push_back(start as _);
-        visited.set(start, true);
-
-        while !queue.is_empty() {
+    let _ = visit.visit_all(|event| {
+        if let breadth_first::Event::Unknown { .. } = event {
             pl.light_update();
-            let current_node = queue.pop_front().unwrap();
-            for succ in graph.successors(current_node) {
-                if !visited[succ] {
-                    queue.push_back(succ);
-                    visited.set(succ as _, true);
-                }
-            }
         }
-    }
+        ControlFlow::<Infallible>::Continue(())
+    });
 
     pl.done();
 
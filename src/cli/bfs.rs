@@ -5,12 +5,11 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
+use super::utils::store_vec;
 use crate::prelude::*;
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
-use epserde::prelude::Serialize;
-use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
 pub const COMMAND_NAME: &str = "bfs";
@@ -68,19 +67,7 @@ where
         perm[node_id] = i;
     }
 
-    if args.epserde {
-        perm.store(&args.perm)
-            .with_context(|| format!("Could not write permutation to {}", args.perm.display()))?;
-    } else {
-        let mut file = std::fs::File::create(&args.perm)
-            .with_context(|| format!("Could not create permutation at {}", args.perm.display()))?;
-        let mut buf = BufWriter::new(&mut file);
-        for word in perm.iter() {
-            buf.write_all(&word.to_be_bytes()).with_context(|| {
-                format!("Could not write permutation to {}", args.perm.display())
-            })?;
-        }
-    }
+    store_vec(perm, &args.perm, args.epserde)?;
     log::info!("Completed..");
     Ok(())
 }
@@ -0,0 +1,87 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "check-symmetric";
+
+#[derive(Args, Debug)]
+#[command(about = "Checks whether a graph is symmetric and computes the fraction of reciprocal arcs", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    #[arg(short, long, default_value_t = 0)]
+    /// The number of non-reciprocal arcs to list.
+    num_arcs: usize,
+
+    #[clap(short = 'b', long, value_parser = batch_size, default_value = "50%")]
+    /// The number of pairs to be used in batches when computing the transpose.
+    /// Two times this number of `usize` will be allocated to sort pairs. You
+    /// can use the SI and NIST multipliers k, M, G, T, P, ki, Mi, Gi, Ti, and
+    /// Pi. You can also use a percentage of the available memory by appending
+    /// a `%` to the number.
+    batch_size: usize,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => check_symmetric_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => check_symmetric_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn check_symmetric_impl<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    // the graph is scanned sequentially, so there is no need for offsets
+    let seq_graph = BVGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let reciprocity =
+        crate::algo::reciprocity(&seq_graph, args.batch_size, args.num_arcs, Some(&mut pl))?;
+
+    println!("Symmetric: {}", reciprocity.is_symmetric());
+    println!(
+        "Reciprocal arcs: {} out of {} ({:.3}%)",
+        reciprocity.num_reciprocal(),
+        reciprocity.num_arcs(),
+        100.0 * reciprocity.reciprocity()
+    );
+    if !reciprocity.non_reciprocal_arcs().is_empty() {
+        println!("Non-reciprocal arcs:");
+        for (x, y) in reciprocity.non_reciprocal_arcs() {
+            println!("{}\t{}", x, y);
+        }
+    }
+    Ok(())
+}
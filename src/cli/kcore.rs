@@ -0,0 +1,84 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "kcore";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the k-core decomposition of a symmetric graph", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph, which must be symmetric.
+    basename: PathBuf,
+
+    /// A filename for the core number of each node.
+    cores: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// A filename for the permutation associated with a degeneracy ordering.
+    perm: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Save the core numbers (and the permutation) in ε-serde format.
+    epserde: bool,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => kcore_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => kcore_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn kcore_impl<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BVGraph::with_basename(&args.basename)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true).local_speed(true);
+    let kcores = crate::algo::kcore(&graph, Threads::Num(args.num_cpus.num_cpus), Some(&mut pl));
+    log::info!("The degeneracy of the graph is {}", kcores.degeneracy());
+
+    if let Some(path) = &args.perm {
+        store_vec(kcores.perm().into(), path, args.epserde)?;
+    }
+    if let Some(path) = &args.cores {
+        store_vec(kcores.into_cores().into(), path, args.epserde)?;
+    }
+    log::info!("Completed.");
+    Ok(())
+}
@@ -13,13 +13,13 @@ use crate::prelude::*;
 use anyhow::{bail, Context, Result};
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
 use epserde::prelude::*;
 use llp::invert_permutation;
 use llp::preds::{MaxUpdates, MinGain, MinModified, PercModified};
 
 use predicates::prelude::*;
 use rayon::prelude::*;
-use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
 pub const COMMAND_NAME: &str = "llp";
@@ -83,6 +83,10 @@ struct CliArgs {
     /// The chunk size used to localize the random permutation
     /// (advanced option).
     chunk_size: Option<usize>,
+
+    #[arg(long)]
+    /// Check that the graph is symmetric before running LLP.
+    check_symmetry: bool,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -124,6 +128,12 @@ where
         .endianness::<E>()
         .load()?;
 
+    if args.check_symmetry {
+        let mut pl = ProgressLogger::default();
+        pl.display_memory(true);
+        llp::check_symmetry(&graph, Some(&mut pl))?;
+    }
+
     // Load degree cumulative function in THP memory
     log::info!("Loading DCF in THP memory...");
     let deg_cumul = DCF::load_mmap(
@@ -191,21 +201,7 @@ where
     log::info!("Elapsed: {}", start.elapsed().as_secs_f64());
     log::info!("Saving permutation...");
 
-    let perm = args.perm;
-
-    if args.epserde {
-        llp_inv_perm
-            .store(&perm)
-            .with_context(|| format!("Could not write permutation to {}", perm.display()))?;
-    } else {
-        let mut file = std::fs::File::create(&perm)
-            .with_context(|| format!("Could not create permutation at {}", perm.display()))?;
-        let mut buf = BufWriter::new(&mut file);
-        for word in llp_inv_perm.into_iter() {
-            buf.write_all(&word.to_be_bytes())
-                .with_context(|| format!("Could not write permutation to {}", perm.display()))?;
-        }
-    }
+    store_vec(llp_inv_perm, &args.perm, args.epserde)?;
     log::info!("Completed in {} seconds", start.elapsed().as_secs_f64());
     Ok(())
 }
@@ -14,18 +14,27 @@ pub mod bench;
 pub mod bfs;
 pub mod build;
 pub mod check_ef;
+pub mod check_symmetric;
 pub mod convert;
 pub mod from_csv;
+pub mod kcore;
 pub mod llp;
 pub mod merge_perms;
 pub mod optimize_codes;
 pub mod pad;
+pub mod pagerank;
 pub mod rand_perm;
 pub mod recompress;
+pub mod scc;
 pub mod simplify;
+pub mod stats;
+pub mod subgraph;
+pub mod sumsweep;
 pub mod to_csv;
 pub mod transpose;
+pub mod triangles;
 pub mod utils;
+pub mod wcc;
 
 /// Appends a string to the filename of a path.
 ///
@@ -76,13 +76,13 @@ where
                 "Type", "Code", "Improvement", "Weight", "Bytes", "Bits",
             );
             $(
-                let (_, new) = $stats.$code.best_code();
+                let (_, new) = $stats.$code.get_best_code();
                 $new_bits += new;
                 $old_bits += $old;
             )*
 
             $(
-                let (code, new) = $stats.$code.best_code();
+                let (code, new) = $stats.$code.get_best_code();
                 println!("{:>17} {:>16} {:>12} {:>8} {:>10} {:>16}",
                     stringify!($code), format!("{:?}", code),
                     format!("{:.3}%", 100.0 * ($old - new) as f64 / $old as f64),
@@ -127,6 +127,17 @@ where
         "  Improvement: {:>15.3}%",
         100.0 * (old_bits - new_bits) as f64 / old_bits as f64
     );
+
+    let comp_flags = stats.best_comp_flags(CompFlags::default());
+    println!();
+    println!("Properties for the optimal codes:");
+    for line in comp_flags
+        .to_properties::<E>(0, 0)?
+        .lines()
+        .filter(|line| line.starts_with("compressionflags=") || line.starts_with("zetak="))
+    {
+        println!("{}", line);
+    }
     Ok(())
 }
 
@@ -0,0 +1,156 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::algo::pagerank::preds::L1Norm;
+use crate::algo::pagerank::{Dangling, PageRank};
+use crate::algo::preds::MaxIters;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use predicates::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "pagerank";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes PageRank", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// A filename for the rank vector.
+    ranks: PathBuf,
+
+    #[arg(short, long)]
+    /// The basename of the transpose of the graph; if specified, PageRank is
+    /// computed iterating on the transpose, which requires its offsets and its
+    /// degree cumulative function. Otherwise, the graph is scanned
+    /// sequentially.
+    transpose: Option<PathBuf>,
+
+    #[arg(short, long, default_value_t = PageRank::DEFAULT_ALPHA)]
+    /// The damping factor.
+    alpha: f64,
+
+    #[arg(long, default_value_t = L1Norm::DEFAULT_THRESHOLD)]
+    /// The threshold on the ℓ₁ norm of the difference between the rank vectors
+    /// of two consecutive iterations used to stop the computation.
+    threshold: f64,
+
+    #[arg(short = 'i', long)]
+    /// If specified, the maximum number of iterations.
+    max_iters: Option<usize>,
+
+    #[arg(long)]
+    /// A file containing the preference (personalization) vector, which must
+    /// be a distribution over the nodes of the graph; if not specified, the
+    /// preference vector is uniform.
+    preference: Option<PathBuf>,
+
+    #[arg(long, requires = "preference")]
+    /// Distribute the rank of dangling nodes following the preference
+    /// vector (strongly preferential PageRank) instead of uniformly.
+    strongly: bool,
+
+    #[arg(short, long)]
+    /// Load the preference vector and save the rank vector in ε-serde format.
+    epserde: bool,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+
+    #[arg(long)]
+    /// The tentative number of arcs used define the size of a parallel job
+    /// when iterating on the transpose (advanced option).
+    granularity: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => pagerank_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => pagerank_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn pagerank_impl<E: Endianness + Clone + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let preference = args
+        .preference
+        .as_ref()
+        .map(|path| load_vec::<f64>(path, args.epserde))
+        .transpose()?;
+
+    let mut pagerank = PageRank::new().alpha(args.alpha);
+    if let Some(preference) = &preference {
+        pagerank = pagerank.preference(preference);
+    }
+    if args.strongly {
+        pagerank = pagerank.dangling(Dangling::Preference);
+    }
+    if let Some(granularity) = args.granularity {
+        pagerank = pagerank.granularity(granularity);
+    }
+
+    let predicate = L1Norm::try_from(args.threshold)?
+        .or(MaxIters::from(args.max_iters))
+        .boxed();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true);
+    let threads = Threads::Num(args.num_cpus.num_cpus);
+
+    let ranks = if let Some(transpose) = &args.transpose {
+        let graph = BVGraph::with_basename(transpose)
+            .mode::<LoadMmap>()
+            .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+            .endianness::<E>()
+            .load()?;
+        let deg_cumul = DCF::load_mmap(
+            transpose.with_extension(DEG_CUMUL_EXTENSION),
+            Flags::TRANSPARENT_HUGE_PAGES | Flags::RANDOM_ACCESS,
+        )
+        .with_context(|| {
+            format!(
+                "Could not load degree cumulative function for basename {}",
+                transpose.display()
+            )
+        })?;
+        pagerank.run_transpose(&graph, &*deg_cumul, predicate, threads, Some(&mut pl))?
+    } else {
+        // the graph is scanned sequentially, so there is no need for offsets
+        let seq_graph = BVGraphSeq::with_basename(&args.basename)
+            .endianness::<E>()
+            .load()?;
+        pagerank.run_seq(&seq_graph, predicate, threads, Some(&mut pl))?
+    };
+
+    log::info!("Saving rank vector...");
+    store_vec(ranks.into(), &args.ranks, args.epserde)?;
+    log::info!("Completed.");
+    Ok(())
+}
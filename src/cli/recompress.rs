@@ -11,6 +11,8 @@ use anyhow::Result;
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
 use mmap_rs::MmapFlags;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 use tempfile::Builder;
 
@@ -32,6 +34,48 @@ struct CliArgs {
 
     #[clap(flatten)]
     ca: CompressArgs,
+
+    #[clap(flatten)]
+    oa: OptimizeCodesArgs,
+
+    #[clap(flatten)]
+    la: LabelArgs,
+}
+
+#[derive(Args, Debug)]
+struct OptimizeCodesArgs {
+    #[clap(long, default_value_t = false)]
+    /// Choose the best code for each stream using a preliminary compression
+    /// pass; the codes specified by the other options are used only to guide
+    /// the preliminary pass.
+    optimize_codes: bool,
+
+    #[clap(long, requires = "optimize_codes")]
+    /// Use only about this number of nodes in the preliminary pass.
+    sample_size: Option<usize>,
+}
+
+/// Returns the given compression flags, with optimized codes if requested.
+fn optimize_codes<G: SplitLabeling + SequentialGraph>(
+    graph: &G,
+    comp_flags: CompFlags,
+    args: &OptimizeCodesArgs,
+    num_cpus: usize,
+) -> Result<CompFlags>
+where
+    for<'a> <G as SplitLabeling>::SplitLender<'a>: Send + Sync,
+{
+    if !args.optimize_codes {
+        return Ok(comp_flags);
+    }
+    let start = std::time::Instant::now();
+    let comp_flags =
+        BVComp::optimize_codes(graph, comp_flags, args.sample_size, Threads::Num(num_cpus))?;
+    log::info!(
+        "Optimized the codes. It took {:.3} seconds",
+        start.elapsed().as_secs_f64()
+    );
+    Ok(comp_flags)
 }
 
 pub fn cli(command: Command) -> Command {
@@ -70,14 +114,20 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     Ok(())
 }
 
-fn compress<E: Endianness + Clone + Send + Sync>(
+fn compress<E: Endianness + Clone + Send + Sync + 'static>(
     args: CliArgs,
     target_endianness: Option<String>,
     permutation: Option<JavaPermutation>,
 ) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
 {
+    if let Some(fixed_width) = args.la.fixed_width() {
+        return compress_labeled::<E>(args, target_endianness, permutation, fixed_width);
+    }
+
     let dir = Builder::new().prefix("Recompress").tempdir()?;
 
     if args.basename.with_extension(EF_EXTENSION).exists() {
@@ -121,7 +171,7 @@ where
                 args.new_basename,
                 &sorted,
                 sorted.num_nodes(),
-                args.ca.into(),
+                optimize_codes(&sorted, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
                 Threads::Num(args.num_cpus.num_cpus),
                 dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
@@ -131,7 +181,7 @@ where
                 args.new_basename,
                 &graph,
                 graph.num_nodes(),
-                args.ca.into(),
+                optimize_codes(&graph, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
                 Threads::Num(args.num_cpus.num_cpus),
                 dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
@@ -158,7 +208,7 @@ where
                 args.new_basename,
                 &permuted,
                 permuted.num_nodes(),
-                args.ca.into(),
+                optimize_codes(&permuted, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
                 Threads::Num(args.num_cpus.num_cpus),
                 dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
@@ -168,7 +218,7 @@ where
                 args.new_basename,
                 &seq_graph,
                 seq_graph.num_nodes(),
-                args.ca.into(),
+                optimize_codes(&seq_graph, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
                 Threads::Num(args.num_cpus.num_cpus),
                 dir,
                 &target_endianness.unwrap_or_else(|| E::NAME.into()),
@@ -177,3 +227,76 @@ where
     }
     Ok(())
 }
+
+fn compress_labeled<E: Endianness + Clone + Send + Sync + 'static>(
+    args: CliArgs,
+    target_endianness: Option<String>,
+    permutation: Option<JavaPermutation>,
+    fixed_width: FixedWidth,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
+{
+    let dir = Builder::new().prefix("Recompress").tempdir()?;
+    let target_endianness = target_endianness.unwrap_or_else(|| E::NAME.into());
+
+    if let Some(permutation) = permutation {
+        let seq_graph = BVGraphSeq::with_basename(&args.basename)
+            .endianness::<E>()
+            .load()?;
+        let labels = load_labels::<E, _>(
+            &args.basename,
+            seq_graph.num_nodes(),
+            seq_graph.num_arcs_hint(),
+            fixed_width,
+            MemoryFlags::empty(),
+        )?;
+        let batch_size = args.pa.batch_size;
+
+        log::info!("Permuting labeled graph with batch size {}", batch_size);
+        let start = std::time::Instant::now();
+        let permuted = Left(crate::transform::permute_labeled(
+            &Zip(seq_graph, labels),
+            &permutation,
+            batch_size,
+            fixed_width,
+            fixed_width,
+        )?);
+        log::info!(
+            "Permuted the graph. It took {:.3} seconds",
+            start.elapsed().as_secs_f64()
+        );
+
+        BVComp::parallel_labeled_endianness(
+            args.new_basename,
+            &permuted.0,
+            optimize_codes(&permuted, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
+            fixed_width,
+            Threads::Num(args.num_cpus.num_cpus),
+            dir,
+            &target_endianness,
+        )?;
+    } else {
+        // Offsets are built on the fly if necessary, as we need random access
+        // to split the labeled graph
+        let graph = Left(
+            BVGraph::with_basename(&args.basename)
+                .offsets_mode::<BuildEf>()
+                .endianness::<E>()
+                .load_labeled(fixed_width)?,
+        );
+
+        BVComp::parallel_labeled_endianness(
+            args.new_basename,
+            &graph.0,
+            optimize_codes(&graph, args.ca.into(), &args.oa, args.num_cpus.num_cpus)?,
+            fixed_width,
+            Threads::Num(args.num_cpus.num_cpus),
+            dir,
+            &target_endianness,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::store_vec;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "scc";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the strongly connected components of a graph", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// A filename for the component of each node.
+    components: PathBuf,
+
+    #[arg(short, long)]
+    /// Renumber components by non-increasing size, so that component zero is
+    /// the largest one.
+    sort: bool,
+
+    #[arg(long)]
+    /// A filename for the sizes of the components.
+    sizes: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Save the components (and the sizes) in ε-serde format.
+    epserde: bool,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => scc_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => scc_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn scc_impl<E: Endianness + 'static + Send + Sync>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    // load the graph
+    let graph = BVGraph::with_basename(&args.basename)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true).local_speed(true);
+    let mut sccs = crate::algo::scc(&graph, Some(&mut pl));
+
+    let sizes = if args.sort {
+        sccs.sort_by_size()
+    } else {
+        sccs.compute_sizes()
+    };
+    log::info!(
+        "Found {} strongly connected components; the largest one has {} nodes",
+        sccs.num_components(),
+        sizes.iter().max().copied().unwrap_or(0)
+    );
+
+    store_vec(sccs.into_components().into(), &args.components, args.epserde)?;
+    if let Some(path) = &args.sizes {
+        store_vec(sizes.into(), path, args.epserde)?;
+    }
+    log::info!("Completed.");
+    Ok(())
+}
@@ -8,8 +8,10 @@
 use super::{append, utils::*};
 use crate::prelude::*;
 use anyhow::Result;
-use clap::{ArgMatches, Args, Command, FromArgMatches};
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
 use dsi_bitstream::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 use tempfile::Builder;
 
@@ -31,6 +33,23 @@ struct CliArgs {
 
     #[clap(flatten)]
     ca: CompressArgs,
+
+    #[clap(flatten)]
+    la: LabelArgs,
+
+    #[arg(value_enum)]
+    #[clap(long, default_value = "max", requires = "label_width")]
+    /// How to merge the labels of duplicate arcs (sums saturate at the
+    /// largest label of the given width).
+    label_merge: LabelMerge,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The policies for merging the labels of duplicate arcs.
+enum LabelMerge {
+    Min,
+    Max,
+    Sum,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -58,12 +77,19 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
 fn simplify<E: Endianness + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
 {
     // TODO!: speed it up by using random access graph if possible
     let simplified = args
         .simplified
+        .clone()
         .unwrap_or_else(|| append(&args.basename, "-simple"));
 
+    if let Some(fixed_width) = args.la.fixed_width() {
+        return simplify_labeled::<E>(args, simplified, fixed_width);
+    }
+
     let seq_graph = crate::graphs::bvgraph::sequential::BVGraphSeq::with_basename(&args.basename)
         .endianness::<E>()
         .load()?;
@@ -85,3 +111,56 @@ where
 
     Ok(())
 }
+
+fn simplify_labeled<E: Endianness + 'static>(
+    args: CliArgs,
+    simplified: PathBuf,
+    fixed_width: FixedWidth,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
+{
+    let seq_graph = crate::graphs::bvgraph::sequential::BVGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let labels = load_labels::<E, _>(
+        &args.basename,
+        seq_graph.num_nodes(),
+        seq_graph.num_arcs_hint(),
+        fixed_width,
+        MemoryFlags::empty(),
+    )?;
+
+    let label_merge = args.label_merge;
+    let max_label = fixed_width.max_label();
+    let merge = move |a: u64, b: u64| match label_merge {
+        LabelMerge::Min => a.min(b),
+        LabelMerge::Max => a.max(b),
+        LabelMerge::Sum => a.saturating_add(b).min(max_label),
+    };
+
+    // simplify the graph together with its labels
+    let sorted = crate::transform::simplify_labeled(
+        &Zip(seq_graph, labels),
+        args.pa.batch_size,
+        fixed_width,
+        fixed_width,
+        merge,
+    )?;
+
+    let target_endianness = args.ca.endianess.clone();
+    let dir = Builder::new().prefix("CompressSimplified").tempdir()?;
+    BVComp::parallel_labeled_endianness(
+        simplified,
+        &sorted,
+        args.ca.into(),
+        fixed_width,
+        Threads::Num(args.num_cpus.num_cpus),
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+
+    Ok(())
+}
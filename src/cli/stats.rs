@@ -0,0 +1,258 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::algo::llp::gap_cost::compute_log_gap_cost;
+use crate::graphs::bvgraph::BuildEf;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use sux::prelude::*;
+
+pub const COMMAND_NAME: &str = "stats";
+
+/// The extension of the file containing the statistics of a graph.
+pub const STATS_EXTENSION: &str = "stats";
+/// The extension of the file containing the outdegree histogram of a graph.
+pub const OUTDEGREE_EXTENSION: &str = "outdegree";
+/// The extension of the file containing the indegree histogram of a graph.
+pub const INDEGREE_EXTENSION: &str = "indegree";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes statistics about a graph: degrees, loops, dangling nodes, bits per link and log-gap cost", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    #[arg(short, long)]
+    /// A basename for the machine-readable output: statistics will be written
+    /// in properties format in a file with extension .stats, and the outdegree
+    /// and indegree histograms (the i-th line contains the number of nodes
+    /// with degree i) in files with extensions .outdegree and .indegree.
+    output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+
+    #[arg(long)]
+    /// The tentative number of arcs used define the size of a parallel job
+    /// when computing the log-gap cost (advanced option).
+    granularity: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => stats_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => stats_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+/// Minimum, maximum, and nodes attaining them, of a degree sequence, plus its
+/// histogram.
+struct DegreeStats {
+    min: usize,
+    min_node: usize,
+    max: usize,
+    max_node: usize,
+    histogram: Vec<usize>,
+}
+
+impl DegreeStats {
+    fn new(degrees: impl IntoIterator<Item = usize>) -> Self {
+        let mut stats = DegreeStats {
+            min: usize::MAX,
+            min_node: 0,
+            max: 0,
+            max_node: 0,
+            histogram: vec![],
+        };
+        for (node, degree) in degrees.into_iter().enumerate() {
+            if degree < stats.min {
+                stats.min = degree;
+                stats.min_node = node;
+            }
+            if degree > stats.max {
+                stats.max = degree;
+                stats.max_node = node;
+            }
+            if degree >= stats.histogram.len() {
+                stats.histogram.resize(degree + 1, 0);
+            }
+            stats.histogram[degree] += 1;
+        }
+        if stats.histogram.is_empty() {
+            stats.min = 0;
+        }
+        stats
+    }
+}
+
+fn stats_impl<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    // Offsets are built on the fly if necessary, as we need random access
+    // to compute the log-gap cost in parallel
+    let graph = BVGraph::with_basename(&args.basename)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .offsets_mode::<BuildEf>()
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let num_arcs = graph.num_arcs();
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true)
+        .item_name("node")
+        .expected_updates(Some(num_nodes));
+
+    // Outdegrees, bit size and degree cumulative function from the offsets
+    pl.start("Scanning offsets and degrees...");
+    let mut outdegrees = Vec::with_capacity(num_nodes);
+    let mut efb = EliasFanoBuilder::new(num_nodes + 1, num_arcs as usize + 1);
+    efb.push(0)?;
+    let mut cumul_deg = 0;
+    let mut iter = graph.offset_deg_iter();
+    for (_offset, degree) in iter.by_ref() {
+        outdegrees.push(degree);
+        cumul_deg += degree;
+        efb.push(cumul_deg)?;
+        pl.light_update();
+    }
+    let num_bits = iter.get_pos();
+    pl.done();
+    let deg_cumul: DCF = efb.build().convert_to()?;
+    let out_stats = DegreeStats::new(outdegrees.iter().copied());
+    let dangling = out_stats.histogram.first().copied().unwrap_or(0);
+    drop(outdegrees);
+
+    // Indegrees and loops from the sequential iterator
+    pl.start("Scanning arcs...");
+    let mut indegrees = vec![0_usize; num_nodes];
+    let mut loops = 0_u64;
+    for_!((node, succ) in graph.iter() {
+        for s in succ {
+            indegrees[s] += 1;
+            if s == node {
+                loops += 1;
+            }
+        }
+        pl.light_update();
+    });
+    pl.done();
+    let in_stats = DegreeStats::new(indegrees.iter().copied());
+    drop(indegrees);
+
+    let mut threads = Threads::Num(args.num_cpus.num_cpus);
+    pl.start("Computing log-gap cost...");
+    let log_gap_cost = compute_log_gap_cost(
+        &graph,
+        args.granularity
+            .unwrap_or(((num_arcs >> 9) as usize).max(1024)),
+        &deg_cumul,
+        threads.as_mut(),
+        Some(&mut pl),
+    );
+    pl.done();
+
+    let avg_degree = num_arcs as f64 / num_nodes.max(1) as f64;
+    let bits_per_link = num_bits as f64 / num_arcs.max(1) as f64;
+    let avg_log_gap = log_gap_cost / num_arcs.max(1) as f64;
+
+    println!("Nodes: {}", num_nodes);
+    println!("Arcs: {}", num_arcs);
+    println!("Loops: {}", loops);
+    println!(
+        "Dangling nodes: {} ({:.3}%)",
+        dangling,
+        100.0 * dangling as f64 / num_nodes.max(1) as f64
+    );
+    println!("Average degree: {:.3}", avg_degree);
+    println!(
+        "Outdegree: min {} (node {}), max {} (node {})",
+        out_stats.min, out_stats.min_node, out_stats.max, out_stats.max_node
+    );
+    println!(
+        "Indegree: min {} (node {}), max {} (node {})",
+        in_stats.min, in_stats.min_node, in_stats.max, in_stats.max_node
+    );
+    println!("Bits per link: {:.3}", bits_per_link);
+    println!(
+        "Log-gap cost: {} ({:.3} bits per link)",
+        log_gap_cost, avg_log_gap
+    );
+
+    if let Some(output) = &args.output {
+        let stats_path = output.with_extension(STATS_EXTENSION);
+        let mut s = String::new();
+        s.push_str(&format!("nodes={}\n", num_nodes));
+        s.push_str(&format!("arcs={}\n", num_arcs));
+        s.push_str(&format!("loops={}\n", loops));
+        s.push_str(&format!("dangling={}\n", dangling));
+        s.push_str(&format!("avgdegree={}\n", avg_degree));
+        s.push_str(&format!("minoutdegree={}\n", out_stats.min));
+        s.push_str(&format!("minoutdegreenode={}\n", out_stats.min_node));
+        s.push_str(&format!("maxoutdegree={}\n", out_stats.max));
+        s.push_str(&format!("maxoutdegreenode={}\n", out_stats.max_node));
+        s.push_str(&format!("minindegree={}\n", in_stats.min));
+        s.push_str(&format!("minindegreenode={}\n", in_stats.min_node));
+        s.push_str(&format!("maxindegree={}\n", in_stats.max));
+        s.push_str(&format!("maxindegreenode={}\n", in_stats.max_node));
+        s.push_str(&format!("bitsperlink={}\n", bits_per_link));
+        s.push_str(&format!("loggapcost={}\n", log_gap_cost));
+        s.push_str(&format!("avgloggap={}\n", avg_log_gap));
+        std::fs::write(&stats_path, s)
+            .with_context(|| format!("Could not write {}", stats_path.display()))?;
+
+        store_histogram(
+            &out_stats.histogram,
+            output.with_extension(OUTDEGREE_EXTENSION),
+        )?;
+        store_histogram(
+            &in_stats.histogram,
+            output.with_extension(INDEGREE_EXTENSION),
+        )?;
+    }
+
+    log::info!("Completed.");
+    Ok(())
+}
+
+/// Store a histogram in ASCII format, one count per line.
+fn store_histogram(histogram: &[usize], path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let mut file = BufWriter::new(
+        std::fs::File::create(path)
+            .with_context(|| format!("Could not create {}", path.display()))?,
+    );
+    for count in histogram {
+        writeln!(file, "{}", count)
+            .with_context(|| format!("Could not write to {}", path.display()))?;
+    }
+    Ok(())
+}
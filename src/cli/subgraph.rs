@@ -0,0 +1,157 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::graphs::MaskedGraph;
+use crate::prelude::*;
+use anyhow::{ensure, Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use sux::prelude::BitVec;
+use tempfile::Builder;
+
+pub const COMMAND_NAME: &str = "subgraph";
+
+#[derive(Args, Debug)]
+#[command(about = "Extracts the subgraph of a BVGraph induced by a set of nodes", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+    /// A file containing the nodes of the subgraph in ASCII format, one per
+    /// line, in any order.
+    nodes: PathBuf,
+    /// The basename of the subgraph.
+    dst: PathBuf,
+
+    #[arg(short, long)]
+    /// Keep the node identifiers of the graph, so that nodes that are not in
+    /// the subgraph will have no successors, instead of renumbering nodes
+    /// compactly.
+    no_renumber: bool,
+
+    #[arg(short, long, conflicts_with = "no_renumber")]
+    /// A filename for the map from the nodes of the subgraph to the nodes of
+    /// the graph.
+    map: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Save the map in ε-serde format.
+    epserde: bool,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+
+    #[clap(flatten)]
+    ca: CompressArgs,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => subgraph::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => subgraph::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+/// Reads the nodes in `path` and returns them as a mask.
+fn load_mask(path: impl AsRef<Path>, num_nodes: usize) -> Result<BitVec> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Could not open node file {}", path.display()))?;
+    let mut mask = BitVec::new(num_nodes);
+    for (line_num, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("Could not read {}", path.display()))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let node = line.parse::<usize>().with_context(|| {
+            format!(
+                "Could not parse node at line {} of {}: {}",
+                line_num + 1,
+                path.display(),
+                line
+            )
+        })?;
+        ensure!(
+            node < num_nodes,
+            "Node {} at line {} of {} is out of range for a graph with {} nodes",
+            node,
+            line_num + 1,
+            path.display(),
+            num_nodes
+        );
+        mask.set(node, true);
+    }
+    Ok(mask)
+}
+
+fn subgraph<E: Endianness + Clone + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let seq_graph = crate::graphs::bvgraph::sequential::BVGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let mask = load_mask(&args.nodes, seq_graph.num_nodes())?;
+
+    let target_endianness = args.ca.endianess.clone().unwrap_or_else(|| E::NAME.into());
+    let threads = Threads::Num(args.num_cpus.num_cpus);
+    let dir = Builder::new().prefix("CompressSubgraph").tempdir()?;
+
+    if args.no_renumber {
+        let masked = MaskedGraph(seq_graph, mask);
+        BVComp::parallel_endianness(
+            &args.dst,
+            &masked,
+            masked.num_nodes(),
+            args.ca.into(),
+            threads,
+            dir,
+            &target_endianness,
+        )?;
+    } else {
+        let subgraph = crate::transform::subgraph(&seq_graph, &mask);
+        log::info!(
+            "The subgraph has {} nodes out of {}",
+            subgraph.num_nodes(),
+            seq_graph.num_nodes()
+        );
+        let num_nodes = subgraph.num_nodes();
+        let split = subgraph.split_iter(args.num_cpus.num_cpus);
+        BVComp::parallel_iter_endianness(
+            &args.dst,
+            split.into_iter(),
+            num_nodes,
+            args.ca.into(),
+            threads,
+            dir,
+            &target_endianness,
+        )?;
+        if let Some(path) = &args.map {
+            store_vec(subgraph.into_nodes().into(), path, args.epserde)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::algo::sumsweep::{DisplayBound, Level, SumSweep};
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches, ValueEnum};
+use dsi_bitstream::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "sumsweep";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+/// The quantities to compute, mirroring [`Level`].
+enum LevelArg {
+    Radius,
+    Diameter,
+    RadiusDiameter,
+    AllForward,
+    All,
+}
+
+impl From<LevelArg> for Level {
+    fn from(level: LevelArg) -> Self {
+        match level {
+            LevelArg::Radius => Level::Radius,
+            LevelArg::Diameter => Level::Diameter,
+            LevelArg::RadiusDiameter => Level::RadiusDiameter,
+            LevelArg::AllForward => Level::AllForward,
+            LevelArg::All => Level::All,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the radius and the diameter of a strongly connected graph using ExactSumSweep, printing bounds after each visit", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// The basename of the transpose of the graph. If not specified, the
+    /// graph is assumed to be symmetric.
+    transpose: Option<PathBuf>,
+
+    #[arg(short, long, value_enum, default_value_t = LevelArg::RadiusDiameter)]
+    /// The quantities to compute.
+    level: LevelArg,
+
+    #[arg(short = 'v', long)]
+    /// Stop after the given number of visits, printing the current bounds.
+    max_visits: Option<usize>,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => sumsweep_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => sumsweep_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn sumsweep_impl<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BVGraph::with_basename(&args.basename)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .endianness::<E>()
+        .load()?;
+
+    match &args.transpose {
+        Some(transpose) => {
+            let transpose = BVGraph::with_basename(transpose)
+                .mode::<LoadMmap>()
+                .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+                .endianness::<E>()
+                .load()?;
+            sumsweep(&graph, &transpose, &args)
+        }
+        None => sumsweep(&graph, &graph, &args),
+    }
+}
+
+fn sumsweep(
+    graph: &impl RandomAccessGraph,
+    transpose: &impl RandomAccessGraph,
+    args: &CliArgs,
+) -> Result<()> {
+    let mut sumsweep = SumSweep::new(graph, transpose, args.level.into());
+    let start = std::time::Instant::now();
+    loop {
+        let done = sumsweep.step()?;
+        println!(
+            "Visit {}: radius in [{}..{}], diameter in [{}..{}]",
+            sumsweep.num_visits(),
+            sumsweep.radius_lower_bound(),
+            DisplayBound(sumsweep.radius_upper_bound()),
+            sumsweep.diameter_lower_bound(),
+            DisplayBound(sumsweep.diameter_upper_bound()),
+        );
+        if done {
+            break;
+        }
+        if args.max_visits.is_some_and(|m| sumsweep.num_visits() >= m) {
+            println!("Stopping after {} visits", sumsweep.num_visits());
+            break;
+        }
+    }
+    log::info!("Elapsed: {}", start.elapsed().as_secs_f64());
+
+    if let Some(radius) = sumsweep.radius() {
+        println!(
+            "Radius: {} (radial vertex: {})",
+            radius,
+            sumsweep.radial_vertex()
+        );
+    }
+    if let Some(diameter) = sumsweep.diameter() {
+        println!(
+            "Diameter: {} (diametral vertex: {})",
+            diameter,
+            sumsweep.diametral_vertex()
+        );
+    }
+    println!("Visits: {}", sumsweep.num_visits());
+    Ok(())
+}
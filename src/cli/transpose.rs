@@ -10,6 +10,8 @@ use crate::prelude::*;
 use anyhow::Result;
 use clap::{ArgMatches, Args, Command, FromArgMatches};
 use dsi_bitstream::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
 use std::path::PathBuf;
 use tempfile::Builder;
 
@@ -31,6 +33,9 @@ struct CliArgs {
 
     #[clap(flatten)]
     ca: CompressArgs,
+
+    #[clap(flatten)]
+    la: LabelArgs,
 }
 
 pub fn cli(command: Command) -> Command {
@@ -55,29 +60,84 @@ pub fn main(submatches: &ArgMatches) -> Result<()> {
     }
 }
 
-fn transpose<E: Endianness + 'static>(args: CliArgs) -> Result<()>
+fn transpose<E: Endianness + Clone + Send + Sync + 'static>(args: CliArgs) -> Result<()>
 where
     for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
 {
-    // TODO!: speed it up by using random access graph if possible
     let transposed = args
         .transposed
+        .clone()
         .unwrap_or_else(|| append(&args.basename, "-t"));
 
+    if let Some(fixed_width) = args.la.fixed_width() {
+        return transpose_labeled::<E>(args, transposed, fixed_width);
+    }
+
+    let seq_graph = crate::graphs::bvgraph::sequential::BVGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+    let num_nodes = seq_graph.num_nodes();
+
+    // transpose the graph in parallel, one lender for each range of nodes
+    let split = crate::transform::transpose_split(
+        &seq_graph,
+        args.pa.batch_size,
+        Threads::Num(args.num_cpus.num_cpus),
+    )?;
+
+    let target_endianness = args.ca.endianess.clone();
+    let dir = Builder::new().prefix("CompressTransposed").tempdir()?;
+    BVComp::parallel_iter_endianness(
+        transposed,
+        split.into_iter(),
+        num_nodes,
+        args.ca.into(),
+        Threads::Num(args.num_cpus.num_cpus),
+        dir,
+        &target_endianness.unwrap_or_else(|| E::NAME.into()),
+    )?;
+
+    Ok(())
+}
+
+fn transpose_labeled<E: Endianness + 'static>(
+    args: CliArgs,
+    transposed: PathBuf,
+    fixed_width: FixedWidth,
+) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: GammaRead<E>,
+{
     let seq_graph = crate::graphs::bvgraph::sequential::BVGraphSeq::with_basename(&args.basename)
         .endianness::<E>()
         .load()?;
+    let labels = load_labels::<E, _>(
+        &args.basename,
+        seq_graph.num_nodes(),
+        seq_graph.num_arcs_hint(),
+        fixed_width,
+        MemoryFlags::empty(),
+    )?;
 
-    // transpose the graph
-    let sorted = crate::transform::transpose(&seq_graph, args.pa.batch_size).unwrap();
+    // transpose the graph together with its labels
+    let sorted = crate::transform::transpose_labeled(
+        &Zip(seq_graph, labels),
+        args.pa.batch_size,
+        fixed_width,
+        fixed_width,
+    )?;
 
     let target_endianness = args.ca.endianess.clone();
     let dir = Builder::new().prefix("CompressTransposed").tempdir()?;
-    BVComp::parallel_endianness(
+    BVComp::parallel_labeled_endianness(
         transposed,
         &sorted,
-        sorted.num_nodes(),
         args.ca.into(),
+        fixed_width,
         Threads::Num(args.num_cpus.num_cpus),
         dir,
         &target_endianness.unwrap_or_else(|| E::NAME.into()),
@@ -0,0 +1,114 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::prelude::*;
+use anyhow::{Context, Result};
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "triangles";
+
+#[derive(Args, Debug)]
+#[command(about = "Counts the triangles of a symmetric graph and computes its clustering coefficients", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph, which must be symmetric.
+    basename: PathBuf,
+
+    /// A filename for the number of triangles of each node.
+    triangles: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// A filename for the local clustering coefficient of each node.
+    clustering: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Save the triangles (and the clustering coefficients) in ε-serde format.
+    epserde: bool,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => triangles_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => triangles_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn triangles_impl<E: Endianness + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let graph = BVGraph::with_basename(&args.basename)
+        .mode::<LoadMmap>()
+        .flags(MemoryFlags::TRANSPARENT_HUGE_PAGES | MemoryFlags::RANDOM_ACCESS)
+        .endianness::<E>()
+        .load()?;
+    let deg_cumul = DCF::load_mmap(
+        args.basename.with_extension(DEG_CUMUL_EXTENSION),
+        Flags::TRANSPARENT_HUGE_PAGES | Flags::RANDOM_ACCESS,
+    )
+    .with_context(|| {
+        format!(
+            "Could not load degree cumulative function for basename {}",
+            args.basename.display()
+        )
+    })?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true).local_speed(true);
+    let triangles = crate::algo::triangles(
+        &graph,
+        &*deg_cumul,
+        Threads::Num(args.num_cpus.num_cpus),
+        Some(&mut pl),
+    );
+
+    println!("Triangles: {}", triangles.num_triangles());
+    println!("Wedges: {}", triangles.num_wedges());
+    println!(
+        "Global clustering coefficient: {}",
+        triangles.global_clustering_coefficient()
+    );
+    println!(
+        "Average clustering coefficient: {}",
+        triangles.average_clustering_coefficient()
+    );
+
+    if let Some(path) = &args.clustering {
+        store_vec(
+            triangles.local_clustering_coefficients().into(),
+            path,
+            args.epserde,
+        )?;
+    }
+    if let Some(path) = &args.triangles {
+        store_vec(triangles.into_triangles().into(), path, args.epserde)?;
+    }
+    log::info!("Completed.");
+    Ok(())
+}
@@ -5,15 +5,19 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 
 use crate::graphs::Code;
-use crate::prelude::CompFlags;
+use crate::labels::FixedWidth;
+use crate::prelude::{CompFlags, RefSelection};
 use anyhow::anyhow;
 use anyhow::ensure;
+use anyhow::Context;
 use clap::Args;
 use clap::ValueEnum;
-use common_traits::UnsignedInt;
+use common_traits::{FromBytes, ToBytes, UnsignedInt};
+use epserde::prelude::{Deserialize, Serialize};
 use sysinfo::System;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -30,6 +34,31 @@ pub enum PrivCode {
     Zeta5,
     Zeta6,
     Zeta7,
+    Pi1,
+    Pi2,
+    Pi3,
+    Pi4,
+    Golomb2,
+    Golomb3,
+    Golomb4,
+    Golomb5,
+    Golomb6,
+    Golomb7,
+    Golomb8,
+    ExpGolomb1,
+    ExpGolomb2,
+    ExpGolomb3,
+    ExpGolomb4,
+    ExpGolomb5,
+    ExpGolomb6,
+    ExpGolomb7,
+    Rice1,
+    Rice2,
+    Rice3,
+    Rice4,
+    Rice5,
+    Rice6,
+    Rice7,
 }
 
 impl From<PrivCode> for Code {
@@ -45,6 +74,31 @@ impl From<PrivCode> for Code {
             PrivCode::Zeta5 => Code::Zeta { k: 5 },
             PrivCode::Zeta6 => Code::Zeta { k: 6 },
             PrivCode::Zeta7 => Code::Zeta { k: 7 },
+            PrivCode::Pi1 => Code::Pi { k: 1 },
+            PrivCode::Pi2 => Code::Pi { k: 2 },
+            PrivCode::Pi3 => Code::Pi { k: 3 },
+            PrivCode::Pi4 => Code::Pi { k: 4 },
+            PrivCode::Golomb2 => Code::Golomb { b: 2 },
+            PrivCode::Golomb3 => Code::Golomb { b: 3 },
+            PrivCode::Golomb4 => Code::Golomb { b: 4 },
+            PrivCode::Golomb5 => Code::Golomb { b: 5 },
+            PrivCode::Golomb6 => Code::Golomb { b: 6 },
+            PrivCode::Golomb7 => Code::Golomb { b: 7 },
+            PrivCode::Golomb8 => Code::Golomb { b: 8 },
+            PrivCode::ExpGolomb1 => Code::ExpGolomb { k: 1 },
+            PrivCode::ExpGolomb2 => Code::ExpGolomb { k: 2 },
+            PrivCode::ExpGolomb3 => Code::ExpGolomb { k: 3 },
+            PrivCode::ExpGolomb4 => Code::ExpGolomb { k: 4 },
+            PrivCode::ExpGolomb5 => Code::ExpGolomb { k: 5 },
+            PrivCode::ExpGolomb6 => Code::ExpGolomb { k: 6 },
+            PrivCode::ExpGolomb7 => Code::ExpGolomb { k: 7 },
+            PrivCode::Rice1 => Code::Rice { log2_b: 1 },
+            PrivCode::Rice2 => Code::Rice { log2_b: 2 },
+            PrivCode::Rice3 => Code::Rice { log2_b: 3 },
+            PrivCode::Rice4 => Code::Rice { log2_b: 4 },
+            PrivCode::Rice5 => Code::Rice { log2_b: 5 },
+            PrivCode::Rice6 => Code::Rice { log2_b: 6 },
+            PrivCode::Rice7 => Code::Rice { log2_b: 7 },
         }
     }
 }
@@ -111,6 +165,24 @@ pub struct PermutationArgs {
     pub batch_size: usize,
 }
 
+#[derive(Args, Debug)]
+/// Shared cli arguments for graphs with labels
+pub struct LabelArgs {
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..=64))]
+    /// Transform also the labels of the graph, stored in the files with
+    /// extensions .labels and .labeloffsets, which must be unsigned integers
+    /// of the given width in bits.
+    pub label_width: Option<u64>,
+}
+
+impl LabelArgs {
+    /// Returns the serializer/deserializer for the labels, if any.
+    pub fn fixed_width(&self) -> Option<FixedWidth> {
+        self.label_width
+            .map(|width| FixedWidth::new(width as usize))
+    }
+}
+
 /// Parses a batch size.
 ///
 /// This function accepts either a number (possibly followed by a
@@ -194,10 +266,48 @@ pub struct CompressArgs {
     /// The code to use for the blocks
     pub blocks: PrivCode,
 
+    #[arg(value_enum)]
+    #[clap(long, default_value = "gamma")]
+    /// The code to use for the intervals
+    pub intervals: PrivCode,
+
     #[arg(value_enum)]
     #[clap(long, default_value = "zeta3")]
     /// The code to use for the residuals
     pub residuals: PrivCode,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// The code to use for the block counts, if different from the one of the blocks (the graph will not be readable by the Java version)
+    pub block_counts: Option<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// The code to use for the interval counts, if different from the one of the intervals (the graph will not be readable by the Java version)
+    pub interval_counts: Option<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// The code to use for the interval starts, if different from the one of the intervals (the graph will not be readable by the Java version)
+    pub interval_starts: Option<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// The code to use for the interval lengths, if different from the one of the intervals (the graph will not be readable by the Java version)
+    pub interval_lens: Option<PrivCode>,
+
+    #[arg(value_enum)]
+    #[clap(long)]
+    /// The code to use for the first residuals, if different from the one of the residuals (the graph will not be readable by the Java version)
+    pub first_residuals: Option<PrivCode>,
+
+    /// Choose references optimally on chunks of nodes instead of greedily (slower, but yields better compression)
+    #[clap(long)]
+    pub optimal_refs: bool,
+
+    /// The number of nodes of a chunk when choosing references optimally
+    #[clap(long, default_value_t = RefSelection::DEFAULT_CHUNK_SIZE, requires = "optimal_refs")]
+    pub ref_chunk_size: usize,
 }
 
 impl From<CompressArgs> for CompFlags {
@@ -206,14 +316,93 @@ impl From<CompressArgs> for CompFlags {
             outdegrees: value.outdegrees.into(),
             references: value.references.into(),
             blocks: value.blocks.into(),
-            intervals: PrivCode::Gamma.into(),
+            intervals: value.intervals.into(),
             residuals: value.residuals.into(),
+            block_counts: value.block_counts.map(Into::into),
+            interval_counts: value.interval_counts.map(Into::into),
+            interval_starts: value.interval_starts.map(Into::into),
+            interval_lens: value.interval_lens.map(Into::into),
+            first_residuals: value.first_residuals.map(Into::into),
             min_interval_length: value.min_interval_length,
             compression_window: value.compression_window,
             max_ref_count: match value.max_ref_count {
                 -1 => usize::MAX,
                 _ => value.max_ref_count as usize,
             },
+            ref_selection: if value.optimal_refs {
+                RefSelection::Optimal {
+                    chunk_size: value.ref_chunk_size,
+                }
+            } else {
+                RefSelection::Greedy
+            },
         }
     }
 }
+
+/// Store a vector of values (e.g., a permutation, the components of a graph,
+/// or a rank vector) either in ε-serde format or as a sequence of big-endian
+/// values.
+///
+/// The vector is taken by value as ε-serde serializes vectors, but not
+/// slices, so we avoid copying the data.
+pub fn store_vec<T: ToBytes + Copy>(
+    data: Vec<T>,
+    path: impl AsRef<Path>,
+    epserde: bool,
+) -> anyhow::Result<()>
+where
+    Vec<T>: Serialize,
+{
+    let path = path.as_ref();
+    if epserde {
+        data.store(path)
+            .with_context(|| format!("Could not write to {}", path.display()))?;
+    } else {
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Could not create {}", path.display()))?;
+        let mut buf = BufWriter::new(&mut file);
+        for value in data.iter() {
+            buf.write_all(value.to_be_bytes().as_ref())
+                .with_context(|| format!("Could not write to {}", path.display()))?;
+        }
+        buf.flush()
+            .with_context(|| format!("Could not flush {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Load a vector of values stored by [`store_vec`], either in ε-serde
+/// format or as a sequence of big-endian values.
+pub fn load_vec<T: FromBytes>(path: impl AsRef<Path>, epserde: bool) -> anyhow::Result<Vec<T>>
+where
+    Vec<T>: Deserialize,
+{
+    let path = path.as_ref();
+    if epserde {
+        return <Vec<T>>::load_full(path)
+            .with_context(|| format!("Could not read {}", path.display()));
+    }
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Could not open {}", path.display()))?;
+    let len = file
+        .metadata()
+        .with_context(|| format!("Could not read metadata of {}", path.display()))?
+        .len() as usize;
+    ensure!(
+        len % T::BYTES == 0,
+        "The length of {} ({}) is not a multiple of {}",
+        path.display(),
+        len,
+        T::BYTES
+    );
+    let mut buf = BufReader::new(file);
+    let mut data = Vec::with_capacity(len / T::BYTES);
+    for _ in 0..len / T::BYTES {
+        let mut bytes = T::Bytes::default();
+        buf.read_exact(bytes.as_mut())
+            .with_context(|| format!("Could not read from {}", path.display()))?;
+        data.push(T::from_be_bytes(bytes));
+    }
+    Ok(data)
+}
@@ -0,0 +1,104 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::utils::*;
+use crate::prelude::*;
+use anyhow::Result;
+use clap::{ArgMatches, Args, Command, FromArgMatches};
+use dsi_bitstream::prelude::*;
+use dsi_progress_logger::prelude::*;
+use std::path::PathBuf;
+
+pub const COMMAND_NAME: &str = "wcc";
+
+#[derive(Args, Debug)]
+#[command(about = "Computes the weakly connected components of a graph", long_about = None)]
+struct CliArgs {
+    /// The basename of the graph.
+    basename: PathBuf,
+
+    /// A filename for the component of each node.
+    components: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Renumber components by non-increasing size, so that component zero is
+    /// the giant component.
+    sort: bool,
+
+    #[arg(long)]
+    /// A filename for the sizes of the components.
+    sizes: Option<PathBuf>,
+
+    #[arg(short, long)]
+    /// Save the components (and the sizes) in ε-serde format.
+    epserde: bool,
+
+    #[clap(flatten)]
+    num_cpus: NumCpusArg,
+}
+
+pub fn cli(command: Command) -> Command {
+    command.subcommand(CliArgs::augment_args(Command::new(COMMAND_NAME)))
+}
+
+pub fn main(submatches: &ArgMatches) -> Result<()> {
+    let args = CliArgs::from_arg_matches(submatches)?;
+
+    match get_endianness(&args.basename)?.as_str() {
+        #[cfg(any(
+            feature = "be_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        BE::NAME => wcc_impl::<BE>(args),
+        #[cfg(any(
+            feature = "le_bins",
+            not(any(feature = "be_bins", feature = "le_bins"))
+        ))]
+        LE::NAME => wcc_impl::<LE>(args),
+        e => panic!("Unknown endianness: {}", e),
+    }
+}
+
+fn wcc_impl<E: Endianness + Clone + Send + Sync + 'static>(args: CliArgs) -> Result<()>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    // the graph is scanned sequentially, so there is no need for offsets
+    let seq_graph = BVGraphSeq::with_basename(&args.basename)
+        .endianness::<E>()
+        .load()?;
+
+    let mut pl = ProgressLogger::default();
+    pl.display_memory(true).local_speed(true);
+    let mut wccs = crate::algo::wcc(
+        &seq_graph,
+        Threads::Num(args.num_cpus.num_cpus),
+        Some(&mut pl),
+    );
+
+    let sizes = if args.sort {
+        wccs.sort_by_size()
+    } else {
+        wccs.compute_sizes()
+    };
+    let giant = sizes.iter().max().copied().unwrap_or(0);
+    log::info!(
+        "Found {} weakly connected components; the giant component has {} nodes ({:.3}% of the graph)",
+        wccs.num_components(),
+        giant,
+        100.0 * giant as f64 / seq_graph.num_nodes().max(1) as f64
+    );
+
+    if let Some(path) = &args.components {
+        store_vec(wccs.into_components().into(), path, args.epserde)?;
+    }
+    if let Some(path) = &args.sizes {
+        store_vec(sizes.into(), path, args.epserde)?;
+    }
+    log::info!("Completed.");
+    Ok(())
+}
@@ -17,6 +17,10 @@ pub enum CodeFuzz {
     Gamma,
     Delta,
     Zeta3,
+    Pi2,
+    Golomb3,
+    ExpGolomb2,
+    Rice3,
 }
 impl From<CodeFuzz> for Code {
     fn from(value: CodeFuzz) -> Self {
@@ -25,6 +29,10 @@ impl From<CodeFuzz> for Code {
             CodeFuzz::Gamma => Code::Gamma,
             CodeFuzz::Delta => Code::Delta,
             CodeFuzz::Zeta3 => Code::Zeta { k: 3 },
+            CodeFuzz::Pi2 => Code::Pi { k: 2 },
+            CodeFuzz::Golomb3 => Code::Golomb { b: 3 },
+            CodeFuzz::ExpGolomb2 => Code::ExpGolomb { k: 2 },
+            CodeFuzz::Rice3 => Code::Rice { log2_b: 3 },
         }
     }
 }
@@ -36,9 +44,15 @@ pub struct CompFlagsFuzz {
     pub blocks: CodeFuzz,
     pub intervals: CodeFuzz,
     pub residuals: CodeFuzz,
+    pub block_counts: Option<CodeFuzz>,
+    pub interval_counts: Option<CodeFuzz>,
+    pub interval_starts: Option<CodeFuzz>,
+    pub interval_lens: Option<CodeFuzz>,
+    pub first_residuals: Option<CodeFuzz>,
     pub min_interval_length: u8,
     pub compression_window: u8,
     pub max_ref_count: u8,
+    pub optimal_refs_chunk_size: Option<u8>,
 }
 
 impl From<CompFlagsFuzz> for CompFlags {
@@ -49,9 +63,20 @@ impl From<CompFlagsFuzz> for CompFlags {
             blocks: value.blocks.into(),
             intervals: value.intervals.into(),
             residuals: value.residuals.into(),
+            block_counts: value.block_counts.map(Into::into),
+            interval_counts: value.interval_counts.map(Into::into),
+            interval_starts: value.interval_starts.map(Into::into),
+            interval_lens: value.interval_lens.map(Into::into),
+            first_residuals: value.first_residuals.map(Into::into),
             min_interval_length: value.min_interval_length as usize,
             compression_window: value.compression_window as usize,
             max_ref_count: value.max_ref_count as usize,
+            ref_selection: match value.optimal_refs_chunk_size {
+                Some(chunk_size) => RefSelection::Optimal {
+                    chunk_size: chunk_size as usize,
+                },
+                None => RefSelection::Greedy,
+            },
         }
     }
 }
@@ -83,7 +108,8 @@ pub fn harness(data: FuzzCase) {
             comp_flags.max_ref_count,
             comp_flags.min_interval_length,
             0,
-        );
+        )
+        .with_ref_selection(comp_flags.ref_selection);
         bvcomp.extend(graph.iter()).unwrap();
         bvcomp.flush().unwrap();
     }
@@ -98,7 +124,8 @@ pub fn harness(data: FuzzCase) {
             comp_flags.max_ref_count,
             comp_flags.min_interval_length,
             0,
-        );
+        )
+        .with_ref_selection(comp_flags.ref_selection);
         bvcomp.extend(graph.iter()).unwrap();
         bvcomp.flush().unwrap();
     }
@@ -74,10 +74,18 @@ unsafe impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clon
 }
 
 impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)>> Iter<L, I> {
-    pub fn new(num_nodes: usize, mut iter: I::IntoIter) -> Self {
+    pub fn new(num_nodes: usize, iter: I::IntoIter) -> Self {
+        Self::new_from(num_nodes, 0, iter)
+    }
+
+    /// Create a new iterator returning the nodes from `from` (included) to
+    /// `num_nodes` (excluded), whose arcs are returned by `iter`.
+    ///
+    /// Arcs with a source smaller than `from` are skipped.
+    pub fn new_from(num_nodes: usize, from: usize, mut iter: I::IntoIter) -> Self {
         Iter {
             num_nodes,
-            curr_node: 0_usize.wrapping_sub(1), // No node seen yet
+            curr_node: from.wrapping_sub(1), // No node seen yet
             next_pair: iter.next().unwrap_or((usize::MAX, usize::MAX, unsafe {
                 #[allow(clippy::uninit_assumed_init)]
                 // SAFETY: L is Copy
@@ -172,6 +180,11 @@ impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone> Sequ
     }
 }
 
+impl<L: Clone + 'static, I: IntoIterator<Item = (usize, usize, L)> + Clone>
+    LabeledSequentialGraph<L> for ArcListGraph<I>
+{
+}
+
 /// Iter until we found a triple with src different than curr_node
 pub struct Succ<'succ, L, I: IntoIterator<Item = (usize, usize, L)>> {
     node_iter: &'succ mut Iter<L, I>,
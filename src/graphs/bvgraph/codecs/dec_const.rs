@@ -24,6 +24,14 @@ pub mod const_codes {
     pub const DELTA: usize = 2;
     /// The int associated to ZETA code
     pub const ZETA: usize = 3;
+    /// The int associated to PI code
+    pub const PI: usize = 4;
+    /// The int associated to GOLOMB code
+    pub const GOLOMB: usize = 5;
+    /// The int associated to EXP_GOLOMB code
+    pub const EXP_GOLOMB: usize = 6;
+    /// The int associated to RICE code
+    pub const RICE: usize = 7;
 }
 
 /// Temporary convertion function while const enum generics are not stable
@@ -33,9 +41,74 @@ pub(crate) fn code_to_const(code: Code) -> Result<usize> {
         Code::Gamma => const_codes::GAMMA,
         Code::Zeta { k: _ } => const_codes::ZETA,
         Code::Delta => const_codes::DELTA,
+        Code::Pi { k: _ } => const_codes::PI,
+        Code::Golomb { b: _ } => const_codes::GOLOMB,
+        Code::ExpGolomb { k: _ } => const_codes::EXP_GOLOMB,
+        Code::Rice { log2_b: _ } => const_codes::RICE,
     })
 }
 
+/// Return the parameter of a code, if any.
+fn code_param(code: Code) -> Option<usize> {
+    match code {
+        Code::Unary | Code::Gamma | Code::Delta => None,
+        Code::Zeta { k } | Code::Pi { k } | Code::ExpGolomb { k } => Some(k),
+        Code::Golomb { b } => Some(b),
+        Code::Rice { log2_b } => Some(log2_b),
+    }
+}
+
+/// Check that the codes in the [`CompFlags`] match the compile-time defined
+/// codes for outdegrees, references, blocks, intervals and residuals, and
+/// that the parameter of every parametric code is `k`, as a single
+/// compile-time parameter is shared by all components.
+fn check_codes(comp_flags: &CompFlags, codes: [usize; 5], k: usize) -> Result<()> {
+    let [outdegrees, references, blocks, intervals, residuals] = codes;
+    for (name, code, expected) in [
+        ("outdegrees", comp_flags.outdegrees, outdegrees),
+        ("references", comp_flags.references, references),
+        ("blocks", comp_flags.blocks, blocks),
+        ("block counts", comp_flags.block_count_code(), blocks),
+        ("intervals", comp_flags.intervals, intervals),
+        (
+            "interval counts",
+            comp_flags.interval_count_code(),
+            intervals,
+        ),
+        (
+            "interval starts",
+            comp_flags.interval_start_code(),
+            intervals,
+        ),
+        (
+            "interval lengths",
+            comp_flags.interval_len_code(),
+            intervals,
+        ),
+        ("residuals", comp_flags.residuals, residuals),
+        (
+            "first residuals",
+            comp_flags.first_residual_code(),
+            residuals,
+        ),
+    ] {
+        if code_to_const(code)? != expected {
+            bail!("Code for {} does not match", name);
+        }
+        if let Some(param) = code_param(code) {
+            if param != k {
+                bail!(
+                    "Parameter of code {:?} for {} does not match the compile-time parameter {}",
+                    code,
+                    name,
+                    k
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
 #[repr(transparent)]
 /// An implementation of [`BVGraphCodesReader`]  with compile-time defined codes
 #[derive(Debug, Clone)]
@@ -93,23 +166,14 @@ impl<
     /// Create a new [`ConstCodesReader`] from a [`CodeRead`] implementation
     /// and a [`CompFlags`] struct
     /// # Errors
-    /// If the codes in the [`CompFlags`] do not match the compile-time defined codes
+    /// If the codes in the [`CompFlags`] do not match the compile-time defined
+    /// codes, or if the parameter of a parametric code is not `K`
     pub fn new(code_reader: CR, comp_flags: &CompFlags) -> Result<Self> {
-        if code_to_const(comp_flags.outdegrees)? != OUTDEGREES {
-            bail!("Code for outdegrees does not match");
-        }
-        if code_to_const(comp_flags.references)? != REFERENCES {
-            bail!("Cod for references does not match");
-        }
-        if code_to_const(comp_flags.blocks)? != BLOCKS {
-            bail!("Code for blocks does not match");
-        }
-        if code_to_const(comp_flags.intervals)? != INTERVALS {
-            bail!("Code for intervals does not match");
-        }
-        if code_to_const(comp_flags.residuals)? != RESIDUALS {
-            bail!("Code for residuals does not match");
-        }
+        check_codes(
+            comp_flags,
+            [OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS],
+            K,
+        )?;
         Ok(Self {
             code_reader,
             _marker: core::marker::PhantomData,
@@ -126,7 +190,11 @@ macro_rules! select_code_read {
             const_codes::ZETA if $k == 1 => $self.code_reader.read_gamma().unwrap(),
             const_codes::ZETA if $k == 3 => $self.code_reader.read_zeta3().unwrap(),
             const_codes::ZETA => $self.code_reader.read_zeta(K as u64).unwrap(),
-            _ => panic!("Only values in the range [0..4) are allowed to represent codes"),
+            const_codes::PI => $self.code_reader.read_pi(K).unwrap(),
+            const_codes::GOLOMB => $self.code_reader.read_golomb(K as u64).unwrap(),
+            const_codes::EXP_GOLOMB => $self.code_reader.read_exp_golomb(K).unwrap(),
+            const_codes::RICE => $self.code_reader.read_rice(K).unwrap(),
+            _ => panic!("Only values in the range [0..8) are allowed to represent codes"),
         }
     };
 }
@@ -217,22 +285,15 @@ impl<
     > ConstCodesDecoderFactory<E, F, OFF, OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS, K>
 {
     /// Create a new builder from the given data and compression flags.
+    /// # Errors
+    /// If the codes in the [`CompFlags`] do not match the compile-time defined
+    /// codes, or if the parameter of a parametric code is not `K`
     pub fn new(factory: F, offsets: MemCase<OFF>, comp_flags: CompFlags) -> anyhow::Result<Self> {
-        if code_to_const(comp_flags.outdegrees)? != OUTDEGREES {
-            bail!("Code for outdegrees does not match");
-        }
-        if code_to_const(comp_flags.references)? != REFERENCES {
-            bail!("Cod for references does not match");
-        }
-        if code_to_const(comp_flags.blocks)? != BLOCKS {
-            bail!("Code for blocks does not match");
-        }
-        if code_to_const(comp_flags.intervals)? != INTERVALS {
-            bail!("Code for intervals does not match");
-        }
-        if code_to_const(comp_flags.residuals)? != RESIDUALS {
-            bail!("Code for residuals does not match");
-        }
+        check_codes(
+            &comp_flags,
+            [OUTDEGREES, REFERENCES, BLOCKS, INTERVALS, RESIDUALS],
+            K,
+        )?;
         Ok(Self {
             factory,
             offsets,
@@ -39,6 +39,31 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
     const READ_ZETA6: fn(&mut CR) -> u64 = |cr| cr.read_zeta(6).unwrap();
     const READ_ZETA7: fn(&mut CR) -> u64 = |cr| cr.read_zeta(7).unwrap();
     const READ_ZETA1: fn(&mut CR) -> u64 = Self::READ_GAMMA;
+    const READ_PI1: fn(&mut CR) -> u64 = |cr| cr.read_pi(1).unwrap();
+    const READ_PI2: fn(&mut CR) -> u64 = |cr| cr.read_pi(2).unwrap();
+    const READ_PI3: fn(&mut CR) -> u64 = |cr| cr.read_pi(3).unwrap();
+    const READ_PI4: fn(&mut CR) -> u64 = |cr| cr.read_pi(4).unwrap();
+    const READ_GOLOMB2: fn(&mut CR) -> u64 = |cr| cr.read_golomb(2).unwrap();
+    const READ_GOLOMB3: fn(&mut CR) -> u64 = |cr| cr.read_golomb(3).unwrap();
+    const READ_GOLOMB4: fn(&mut CR) -> u64 = |cr| cr.read_golomb(4).unwrap();
+    const READ_GOLOMB5: fn(&mut CR) -> u64 = |cr| cr.read_golomb(5).unwrap();
+    const READ_GOLOMB6: fn(&mut CR) -> u64 = |cr| cr.read_golomb(6).unwrap();
+    const READ_GOLOMB7: fn(&mut CR) -> u64 = |cr| cr.read_golomb(7).unwrap();
+    const READ_GOLOMB8: fn(&mut CR) -> u64 = |cr| cr.read_golomb(8).unwrap();
+    const READ_EXP_GOLOMB1: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(1).unwrap();
+    const READ_EXP_GOLOMB2: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(2).unwrap();
+    const READ_EXP_GOLOMB3: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(3).unwrap();
+    const READ_EXP_GOLOMB4: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(4).unwrap();
+    const READ_EXP_GOLOMB5: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(5).unwrap();
+    const READ_EXP_GOLOMB6: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(6).unwrap();
+    const READ_EXP_GOLOMB7: fn(&mut CR) -> u64 = |cr| cr.read_exp_golomb(7).unwrap();
+    const READ_RICE1: fn(&mut CR) -> u64 = |cr| cr.read_rice(1).unwrap();
+    const READ_RICE2: fn(&mut CR) -> u64 = |cr| cr.read_rice(2).unwrap();
+    const READ_RICE3: fn(&mut CR) -> u64 = |cr| cr.read_rice(3).unwrap();
+    const READ_RICE4: fn(&mut CR) -> u64 = |cr| cr.read_rice(4).unwrap();
+    const READ_RICE5: fn(&mut CR) -> u64 = |cr| cr.read_rice(5).unwrap();
+    const READ_RICE6: fn(&mut CR) -> u64 = |cr| cr.read_rice(6).unwrap();
+    const READ_RICE7: fn(&mut CR) -> u64 = |cr| cr.read_rice(7).unwrap();
 
     pub fn new(code_reader: CR, cf: &CompFlags) -> anyhow::Result<Self> {
         macro_rules! select_code {
@@ -54,8 +79,33 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
                     Code::Zeta { k: 5 } => Self::READ_ZETA5,
                     Code::Zeta { k: 6 } => Self::READ_ZETA6,
                     Code::Zeta { k: 7 } => Self::READ_ZETA7,
+                    Code::Pi { k: 1 } => Self::READ_PI1,
+                    Code::Pi { k: 2 } => Self::READ_PI2,
+                    Code::Pi { k: 3 } => Self::READ_PI3,
+                    Code::Pi { k: 4 } => Self::READ_PI4,
+                    Code::Golomb { b: 2 } => Self::READ_GOLOMB2,
+                    Code::Golomb { b: 3 } => Self::READ_GOLOMB3,
+                    Code::Golomb { b: 4 } => Self::READ_GOLOMB4,
+                    Code::Golomb { b: 5 } => Self::READ_GOLOMB5,
+                    Code::Golomb { b: 6 } => Self::READ_GOLOMB6,
+                    Code::Golomb { b: 7 } => Self::READ_GOLOMB7,
+                    Code::Golomb { b: 8 } => Self::READ_GOLOMB8,
+                    Code::ExpGolomb { k: 1 } => Self::READ_EXP_GOLOMB1,
+                    Code::ExpGolomb { k: 2 } => Self::READ_EXP_GOLOMB2,
+                    Code::ExpGolomb { k: 3 } => Self::READ_EXP_GOLOMB3,
+                    Code::ExpGolomb { k: 4 } => Self::READ_EXP_GOLOMB4,
+                    Code::ExpGolomb { k: 5 } => Self::READ_EXP_GOLOMB5,
+                    Code::ExpGolomb { k: 6 } => Self::READ_EXP_GOLOMB6,
+                    Code::ExpGolomb { k: 7 } => Self::READ_EXP_GOLOMB7,
+                    Code::Rice { log2_b: 1 } => Self::READ_RICE1,
+                    Code::Rice { log2_b: 2 } => Self::READ_RICE2,
+                    Code::Rice { log2_b: 3 } => Self::READ_RICE3,
+                    Code::Rice { log2_b: 4 } => Self::READ_RICE4,
+                    Code::Rice { log2_b: 5 } => Self::READ_RICE5,
+                    Code::Rice { log2_b: 6 } => Self::READ_RICE6,
+                    Code::Rice { log2_b: 7 } => Self::READ_RICE7,
                     code => bail!(
-                        "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                        "Only unary, ɣ, δ, ζ₁-ζ₇, π₁-π₄, Golomb (b = 2-8), exponential Golomb (k = 1-7), and Rice (log₂b = 1-7) codes are allowed, {:?} is not supported",
                         code
                     ),
                 }
@@ -66,12 +116,12 @@ impl<E: Endianness, CR: CodeRead<E>> DynCodesDecoder<E, CR> {
             code_reader,
             read_outdegree: select_code!(&cf.outdegrees),
             read_reference_offset: select_code!(&cf.references),
-            read_block_count: select_code!(&cf.blocks),
+            read_block_count: select_code!(cf.block_count_code()),
             read_block: select_code!(&cf.blocks),
-            read_interval_count: select_code!(&cf.intervals),
-            read_interval_start: select_code!(&cf.intervals),
-            read_interval_len: select_code!(&cf.intervals),
-            read_first_residual: select_code!(&cf.residuals),
+            read_interval_count: select_code!(cf.interval_count_code()),
+            read_interval_start: select_code!(cf.interval_start_code()),
+            read_interval_len: select_code!(cf.interval_len_code()),
+            read_first_residual: select_code!(cf.first_residual_code()),
             read_residual: select_code!(&cf.residuals),
             _marker: core::marker::PhantomData,
         })
@@ -187,6 +237,56 @@ where
         |cr| cr.read_zeta(7).unwrap();
     const READ_ZETA1: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
         Self::READ_GAMMA;
+    const READ_PI1: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_pi(1).unwrap();
+    const READ_PI2: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_pi(2).unwrap();
+    const READ_PI3: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_pi(3).unwrap();
+    const READ_PI4: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_pi(4).unwrap();
+    const READ_GOLOMB2: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(2).unwrap();
+    const READ_GOLOMB3: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(3).unwrap();
+    const READ_GOLOMB4: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(4).unwrap();
+    const READ_GOLOMB5: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(5).unwrap();
+    const READ_GOLOMB6: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(6).unwrap();
+    const READ_GOLOMB7: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(7).unwrap();
+    const READ_GOLOMB8: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_golomb(8).unwrap();
+    const READ_EXP_GOLOMB1: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(1).unwrap();
+    const READ_EXP_GOLOMB2: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(2).unwrap();
+    const READ_EXP_GOLOMB3: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(3).unwrap();
+    const READ_EXP_GOLOMB4: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(4).unwrap();
+    const READ_EXP_GOLOMB5: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(5).unwrap();
+    const READ_EXP_GOLOMB6: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(6).unwrap();
+    const READ_EXP_GOLOMB7: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_exp_golomb(7).unwrap();
+    const READ_RICE1: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(1).unwrap();
+    const READ_RICE2: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(2).unwrap();
+    const READ_RICE3: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(3).unwrap();
+    const READ_RICE4: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(4).unwrap();
+    const READ_RICE5: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(5).unwrap();
+    const READ_RICE6: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(6).unwrap();
+    const READ_RICE7: for<'a> fn(&mut <F as BitReaderFactory<E>>::BitReader<'a>) -> u64 =
+        |cr| cr.read_rice(7).unwrap();
 
     #[inline(always)]
     /// Return a clone of the compression flags.
@@ -209,8 +309,33 @@ where
                     Code::Zeta { k: 5 } => Self::READ_ZETA5,
                     Code::Zeta { k: 6 } => Self::READ_ZETA6,
                     Code::Zeta { k: 7 } => Self::READ_ZETA7,
+                    Code::Pi { k: 1 } => Self::READ_PI1,
+                    Code::Pi { k: 2 } => Self::READ_PI2,
+                    Code::Pi { k: 3 } => Self::READ_PI3,
+                    Code::Pi { k: 4 } => Self::READ_PI4,
+                    Code::Golomb { b: 2 } => Self::READ_GOLOMB2,
+                    Code::Golomb { b: 3 } => Self::READ_GOLOMB3,
+                    Code::Golomb { b: 4 } => Self::READ_GOLOMB4,
+                    Code::Golomb { b: 5 } => Self::READ_GOLOMB5,
+                    Code::Golomb { b: 6 } => Self::READ_GOLOMB6,
+                    Code::Golomb { b: 7 } => Self::READ_GOLOMB7,
+                    Code::Golomb { b: 8 } => Self::READ_GOLOMB8,
+                    Code::ExpGolomb { k: 1 } => Self::READ_EXP_GOLOMB1,
+                    Code::ExpGolomb { k: 2 } => Self::READ_EXP_GOLOMB2,
+                    Code::ExpGolomb { k: 3 } => Self::READ_EXP_GOLOMB3,
+                    Code::ExpGolomb { k: 4 } => Self::READ_EXP_GOLOMB4,
+                    Code::ExpGolomb { k: 5 } => Self::READ_EXP_GOLOMB5,
+                    Code::ExpGolomb { k: 6 } => Self::READ_EXP_GOLOMB6,
+                    Code::ExpGolomb { k: 7 } => Self::READ_EXP_GOLOMB7,
+                    Code::Rice { log2_b: 1 } => Self::READ_RICE1,
+                    Code::Rice { log2_b: 2 } => Self::READ_RICE2,
+                    Code::Rice { log2_b: 3 } => Self::READ_RICE3,
+                    Code::Rice { log2_b: 4 } => Self::READ_RICE4,
+                    Code::Rice { log2_b: 5 } => Self::READ_RICE5,
+                    Code::Rice { log2_b: 6 } => Self::READ_RICE6,
+                    Code::Rice { log2_b: 7 } => Self::READ_RICE7,
                     code => bail!(
-                        "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                        "Only unary, ɣ, δ, ζ₁-ζ₇, π₁-π₄, Golomb (b = 2-8), exponential Golomb (k = 1-7), and Rice (log₂b = 1-7) codes are allowed, {:?} is not supported",
                         code
                     ),
                 }
@@ -222,12 +347,12 @@ where
             offsets,
             read_outdegree: select_code!(cf.outdegrees),
             read_reference_offset: select_code!(cf.references),
-            read_block_count: select_code!(cf.blocks),
+            read_block_count: select_code!(cf.block_count_code()),
             read_blocks: select_code!(cf.blocks),
-            read_interval_count: select_code!(cf.intervals),
-            read_interval_start: select_code!(cf.intervals),
-            read_interval_len: select_code!(cf.intervals),
-            read_first_residual: select_code!(cf.residuals),
+            read_interval_count: select_code!(cf.interval_count_code()),
+            read_interval_start: select_code!(cf.interval_start_code()),
+            read_interval_len: select_code!(cf.interval_len_code()),
+            read_first_residual: select_code!(cf.first_residual_code()),
             read_residual: select_code!(cf.residuals),
             compression_flags: cf,
             _marker: core::marker::PhantomData,
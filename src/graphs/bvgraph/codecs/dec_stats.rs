@@ -6,7 +6,6 @@
  */
 
 use crate::prelude::*;
-use dsi_bitstream::prelude::CodesStats;
 use std::sync::Mutex;
 
 /// A struct that keeps track of how much bits each piece would take
@@ -34,7 +33,8 @@ pub struct DecoderStats {
 }
 
 impl DecoderStats {
-    fn update(&mut self, rhs: &Self) {
+    /// Combines additively this stats with another one.
+    pub fn update(&mut self, rhs: &Self) {
         self.outdegrees.add(&rhs.outdegrees);
         self.reference_offsets.add(&rhs.reference_offsets);
         self.block_counts.add(&rhs.block_counts);
@@ -45,6 +45,41 @@ impl DecoderStats {
         self.first_residuals.add(&rhs.first_residuals);
         self.residuals.add(&rhs.residuals);
     }
+
+    /// Return the given compression flags with the codes replaced by the best
+    /// ones for each stream.
+    ///
+    /// The code for [`intervals`](CompFlags::intervals), which does not
+    /// correspond to a single stream, is the best code for the interval
+    /// streams as a whole. The codes for single streams overriding the code
+    /// of their group (e.g., [`block_counts`](CompFlags::block_counts)) are set
+    /// only if they are different from the code of the group.
+    pub fn best_comp_flags(&self, comp_flags: CompFlags) -> CompFlags {
+        let mut intervals = self.interval_counts.clone();
+        intervals.add(&self.interval_starts);
+        intervals.add(&self.interval_lens);
+
+        let blocks = self.blocks.get_best_code().0;
+        let intervals = intervals.get_best_code().0;
+        let residuals = self.residuals.get_best_code().0;
+        let split = |stats: &CodesStats, group: Code| {
+            Some(stats.get_best_code().0).filter(|&code| code != group)
+        };
+
+        CompFlags {
+            outdegrees: self.outdegrees.get_best_code().0,
+            references: self.reference_offsets.get_best_code().0,
+            blocks,
+            intervals,
+            residuals,
+            block_counts: split(&self.block_counts, blocks),
+            interval_counts: split(&self.interval_counts, intervals),
+            interval_starts: split(&self.interval_starts, intervals),
+            interval_lens: split(&self.interval_lens, intervals),
+            first_residuals: split(&self.first_residuals, residuals),
+            ..comp_flags
+        }
+    }
 }
 
 /// A wrapper that keeps track of how much bits each piece would take using
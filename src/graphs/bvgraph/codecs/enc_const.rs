@@ -8,10 +8,16 @@
 use dsi_bitstream::prelude::*;
 use std::convert::Infallible;
 
-use super::{const_codes, CodeWrite, Encode, MeasurableEncoder};
+use super::{const_codes, len_pi, CodeWrite, Encode, MeasurableEncoder};
 
 #[repr(transparent)]
 /// An implementation of [`BVGraphCodesWriter`] with compile time defined codes
+///
+/// The parameter `K` is used by all parametric codes (ζ, π, Golomb,
+/// exponential Golomb and Rice), so the
+/// [`CompFlags`](crate::graphs::bvgraph::CompFlags) describing the resulting
+/// graph must use `K` as parameter for all of them, or
+/// [`ConstCodesDecoder`](super::ConstCodesDecoder) will refuse to read it.
 #[derive(Debug, Clone)]
 pub struct ConstCodesEncoder<
     E: Endianness,
@@ -79,7 +85,11 @@ macro_rules! select_code_write {
             const_codes::ZETA if $k == 1 => $self.code_writer.write_gamma($value),
             const_codes::ZETA if $k == 3 => $self.code_writer.write_zeta3($value),
             const_codes::ZETA => $self.code_writer.write_zeta($value, K),
-            _ => panic!("Only values in the range [0..4) are allowed to represent codes"),
+            const_codes::PI => $self.code_writer.write_pi($value, K as usize),
+            const_codes::GOLOMB => $self.code_writer.write_golomb($value, K),
+            const_codes::EXP_GOLOMB => $self.code_writer.write_exp_golomb($value, K as usize),
+            const_codes::RICE => $self.code_writer.write_rice($value, K as usize),
+            _ => panic!("Only values in the range [0..8) are allowed to represent codes"),
         }
     };
 }
@@ -204,7 +214,11 @@ macro_rules! select_code_mock_write {
             const_codes::GAMMA => len_gamma($value),
             const_codes::DELTA => len_delta($value),
             const_codes::ZETA => len_zeta($value, K),
-            _ => panic!("Only values in the range [0..4) are allowed to represent codes"),
+            const_codes::PI => len_pi($value, K as usize),
+            const_codes::GOLOMB => len_golomb($value, K),
+            const_codes::EXP_GOLOMB => len_exp_golomb($value, K as usize),
+            const_codes::RICE => len_rice($value, K as usize),
+            _ => panic!("Only values in the range [0..8) are allowed to represent codes"),
         })
     };
 }
@@ -5,7 +5,7 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use super::{CodeWrite, Encode, MeasurableEncoder};
+use super::{len_pi, CodeWrite, Encode, MeasurableEncoder};
 use crate::{graphs::Code, prelude::CompFlags};
 use dsi_bitstream::prelude::*;
 use std::convert::Infallible;
@@ -50,6 +50,34 @@ fn write_zeta7<E: Endianness, CW: CodeWrite<E>>(cw: &mut CW, x: u64) -> WriteRes
     CW::write_zeta(cw, x, 7)
 }
 
+fn write_pi<E: Endianness, CW: CodeWrite<E>, const K: usize>(
+    cw: &mut CW,
+    x: u64,
+) -> WriteResult<E, CW> {
+    CW::write_pi(cw, x, K)
+}
+
+fn write_golomb<E: Endianness, CW: CodeWrite<E>, const B: u64>(
+    cw: &mut CW,
+    x: u64,
+) -> WriteResult<E, CW> {
+    CW::write_golomb(cw, x, B)
+}
+
+fn write_exp_golomb<E: Endianness, CW: CodeWrite<E>, const K: usize>(
+    cw: &mut CW,
+    x: u64,
+) -> WriteResult<E, CW> {
+    CW::write_exp_golomb(cw, x, K)
+}
+
+fn write_rice<E: Endianness, CW: CodeWrite<E>, const LOG2_B: usize>(
+    cw: &mut CW,
+    x: u64,
+) -> WriteResult<E, CW> {
+    CW::write_rice(cw, x, LOG2_B)
+}
+
 impl<E: Endianness, CW: CodeWrite<E>> DynCodesEncoder<E, CW> {
     #[allow(clippy::type_complexity)]
     fn select_code(code: Code) -> fn(&mut CW, u64) -> WriteResult<E, CW> {
@@ -64,9 +92,34 @@ impl<E: Endianness, CW: CodeWrite<E>> DynCodesEncoder<E, CW> {
             Code::Zeta { k: 5 } => write_zeta5,
             Code::Zeta { k: 6 } => write_zeta6,
             Code::Zeta { k: 7 } => write_zeta7,
+            Code::Pi { k: 1 } => write_pi::<E, CW, 1>,
+            Code::Pi { k: 2 } => write_pi::<E, CW, 2>,
+            Code::Pi { k: 3 } => write_pi::<E, CW, 3>,
+            Code::Pi { k: 4 } => write_pi::<E, CW, 4>,
+            Code::Golomb { b: 2 } => write_golomb::<E, CW, 2>,
+            Code::Golomb { b: 3 } => write_golomb::<E, CW, 3>,
+            Code::Golomb { b: 4 } => write_golomb::<E, CW, 4>,
+            Code::Golomb { b: 5 } => write_golomb::<E, CW, 5>,
+            Code::Golomb { b: 6 } => write_golomb::<E, CW, 6>,
+            Code::Golomb { b: 7 } => write_golomb::<E, CW, 7>,
+            Code::Golomb { b: 8 } => write_golomb::<E, CW, 8>,
+            Code::ExpGolomb { k: 1 } => write_exp_golomb::<E, CW, 1>,
+            Code::ExpGolomb { k: 2 } => write_exp_golomb::<E, CW, 2>,
+            Code::ExpGolomb { k: 3 } => write_exp_golomb::<E, CW, 3>,
+            Code::ExpGolomb { k: 4 } => write_exp_golomb::<E, CW, 4>,
+            Code::ExpGolomb { k: 5 } => write_exp_golomb::<E, CW, 5>,
+            Code::ExpGolomb { k: 6 } => write_exp_golomb::<E, CW, 6>,
+            Code::ExpGolomb { k: 7 } => write_exp_golomb::<E, CW, 7>,
+            Code::Rice { log2_b: 1 } => write_rice::<E, CW, 1>,
+            Code::Rice { log2_b: 2 } => write_rice::<E, CW, 2>,
+            Code::Rice { log2_b: 3 } => write_rice::<E, CW, 3>,
+            Code::Rice { log2_b: 4 } => write_rice::<E, CW, 4>,
+            Code::Rice { log2_b: 5 } => write_rice::<E, CW, 5>,
+            Code::Rice { log2_b: 6 } => write_rice::<E, CW, 6>,
+            Code::Rice { log2_b: 7 } => write_rice::<E, CW, 7>,
             code => {
                 panic!(
-                    "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                    "Only unary, ɣ, δ, ζ₁-ζ₇, π₁-π₄, Golomb (b = 2-8), exponential Golomb (k = 1-7), and Rice (log₂b = 1-7) codes are allowed, {:?} is not supported",
                     code
                 )
             }
@@ -78,12 +131,12 @@ impl<E: Endianness, CW: CodeWrite<E>> DynCodesEncoder<E, CW> {
             code_writer,
             write_outdegree: Self::select_code(cf.outdegrees),
             write_reference_offset: Self::select_code(cf.references),
-            write_block_count: Self::select_code(cf.blocks),
+            write_block_count: Self::select_code(cf.block_count_code()),
             write_block: Self::select_code(cf.blocks),
-            write_interval_count: Self::select_code(cf.intervals),
-            write_interval_start: Self::select_code(cf.intervals),
-            write_interval_len: Self::select_code(cf.intervals),
-            write_first_residual: Self::select_code(cf.residuals),
+            write_interval_count: Self::select_code(cf.interval_count_code()),
+            write_interval_start: Self::select_code(cf.interval_start_code()),
+            write_interval_len: Self::select_code(cf.interval_len_code()),
+            write_first_residual: Self::select_code(cf.first_residual_code()),
             write_residual: Self::select_code(cf.residuals),
             estimator: DynCodesEstimator::new(cf),
             _marker: core::marker::PhantomData,
@@ -208,8 +261,33 @@ impl DynCodesEstimator {
             Code::Zeta { k: 5 } => |x| len_zeta(x, 5),
             Code::Zeta { k: 6 } => |x| len_zeta(x, 6),
             Code::Zeta { k: 7 } => |x| len_zeta(x, 7),
+            Code::Pi { k: 1 } => |x| len_pi(x, 1),
+            Code::Pi { k: 2 } => |x| len_pi(x, 2),
+            Code::Pi { k: 3 } => |x| len_pi(x, 3),
+            Code::Pi { k: 4 } => |x| len_pi(x, 4),
+            Code::Golomb { b: 2 } => |x| len_golomb(x, 2),
+            Code::Golomb { b: 3 } => |x| len_golomb(x, 3),
+            Code::Golomb { b: 4 } => |x| len_golomb(x, 4),
+            Code::Golomb { b: 5 } => |x| len_golomb(x, 5),
+            Code::Golomb { b: 6 } => |x| len_golomb(x, 6),
+            Code::Golomb { b: 7 } => |x| len_golomb(x, 7),
+            Code::Golomb { b: 8 } => |x| len_golomb(x, 8),
+            Code::ExpGolomb { k: 1 } => |x| len_exp_golomb(x, 1),
+            Code::ExpGolomb { k: 2 } => |x| len_exp_golomb(x, 2),
+            Code::ExpGolomb { k: 3 } => |x| len_exp_golomb(x, 3),
+            Code::ExpGolomb { k: 4 } => |x| len_exp_golomb(x, 4),
+            Code::ExpGolomb { k: 5 } => |x| len_exp_golomb(x, 5),
+            Code::ExpGolomb { k: 6 } => |x| len_exp_golomb(x, 6),
+            Code::ExpGolomb { k: 7 } => |x| len_exp_golomb(x, 7),
+            Code::Rice { log2_b: 1 } => |x| len_rice(x, 1),
+            Code::Rice { log2_b: 2 } => |x| len_rice(x, 2),
+            Code::Rice { log2_b: 3 } => |x| len_rice(x, 3),
+            Code::Rice { log2_b: 4 } => |x| len_rice(x, 4),
+            Code::Rice { log2_b: 5 } => |x| len_rice(x, 5),
+            Code::Rice { log2_b: 6 } => |x| len_rice(x, 6),
+            Code::Rice { log2_b: 7 } => |x| len_rice(x, 7),
             code => panic!(
-                "Only unary, ɣ, δ, and ζ₁-ζ₇ codes are allowed, {:?} is not supported",
+                "Only unary, ɣ, δ, ζ₁-ζ₇, π₁-π₄, Golomb (b = 2-8), exponential Golomb (k = 1-7), and Rice (log₂b = 1-7) codes are allowed, {:?} is not supported",
                 code
             ),
         }
@@ -219,12 +297,12 @@ impl DynCodesEstimator {
         Self {
             len_outdegree: Self::select_code(cf.outdegrees),
             len_reference_offset: Self::select_code(cf.references),
-            len_block_count: Self::select_code(cf.blocks),
+            len_block_count: Self::select_code(cf.block_count_code()),
             len_block: Self::select_code(cf.blocks),
-            len_interval_count: Self::select_code(cf.intervals),
-            len_interval_start: Self::select_code(cf.intervals),
-            len_interval_len: Self::select_code(cf.intervals),
-            len_first_residual: Self::select_code(cf.residuals),
+            len_interval_count: Self::select_code(cf.interval_count_code()),
+            len_interval_start: Self::select_code(cf.interval_start_code()),
+            len_interval_len: Self::select_code(cf.interval_len_code()),
+            len_first_residual: Self::select_code(cf.first_residual_code()),
             len_residual: Self::select_code(cf.residuals),
         }
     }
@@ -0,0 +1,119 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use super::{DecoderStats, DynCodesEstimator, Encode, MeasurableEncoder};
+use crate::prelude::CompFlags;
+use std::convert::Infallible;
+use std::sync::Mutex;
+
+/// An encoder that does not write anything, but keeps track of how much bits
+/// each written value would take using different codes.
+///
+/// The statistics are gathered in a [`DecoderStats`], as the streams are the
+/// same, and they are added to the given global statistics when the encoder
+/// is dropped. The estimator of this encoder is a [`DynCodesEstimator`] for
+/// the given compression flags, so the compressor makes its choices as it
+/// would using those flags.
+pub struct StatsEncoder<'a> {
+    estimator: DynCodesEstimator,
+    stats: DecoderStats,
+    glob_stats: &'a Mutex<DecoderStats>,
+}
+
+impl<'a> StatsEncoder<'a> {
+    /// Create a new encoder that will add its statistics to `glob_stats`.
+    pub fn new(cf: &CompFlags, glob_stats: &'a Mutex<DecoderStats>) -> Self {
+        Self {
+            estimator: DynCodesEstimator::new(cf),
+            stats: DecoderStats::default(),
+            glob_stats,
+        }
+    }
+}
+
+impl<'a> Drop for StatsEncoder<'a> {
+    fn drop(&mut self) {
+        self.glob_stats.lock().unwrap().update(&self.stats);
+    }
+}
+
+impl<'a> Encode for StatsEncoder<'a> {
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn start_node(&mut self, _node: usize) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+
+    #[inline(always)]
+    fn end_node(&mut self, _node: usize) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+
+    #[inline(always)]
+    fn write_outdegree(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.outdegrees.update(value);
+        self.estimator.write_outdegree(value)
+    }
+
+    #[inline(always)]
+    fn write_reference_offset(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.reference_offsets.update(value);
+        self.estimator.write_reference_offset(value)
+    }
+
+    #[inline(always)]
+    fn write_block_count(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.block_counts.update(value);
+        self.estimator.write_block_count(value)
+    }
+    #[inline(always)]
+    fn write_block(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.blocks.update(value);
+        self.estimator.write_block(value)
+    }
+
+    #[inline(always)]
+    fn write_interval_count(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.interval_counts.update(value);
+        self.estimator.write_interval_count(value)
+    }
+    #[inline(always)]
+    fn write_interval_start(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.interval_starts.update(value);
+        self.estimator.write_interval_start(value)
+    }
+    #[inline(always)]
+    fn write_interval_len(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.interval_lens.update(value);
+        self.estimator.write_interval_len(value)
+    }
+
+    #[inline(always)]
+    fn write_first_residual(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.first_residuals.update(value);
+        self.estimator.write_first_residual(value)
+    }
+    #[inline(always)]
+    fn write_residual(&mut self, value: u64) -> Result<usize, Self::Error> {
+        self.stats.residuals.update(value);
+        self.estimator.write_residual(value)
+    }
+
+    fn flush(&mut self) -> Result<usize, Self::Error> {
+        Ok(0)
+    }
+}
+
+impl<'a> MeasurableEncoder for StatsEncoder<'a> {
+    type Estimator<'b> = &'b mut DynCodesEstimator
+        where Self: 'b;
+
+    fn estimator(&mut self) -> Self::Estimator<'_> {
+        &mut self.estimator
+    }
+}
@@ -27,24 +27,72 @@ pub use enc_const::*;
 mod enc_dyn;
 pub use enc_dyn::*;
 
+mod enc_stats;
+pub use enc_stats::*;
+
+mod pi;
+pub use pi::*;
+
+mod stats;
+pub use stats::*;
+
 use dsi_bitstream::{
-    codes::{DeltaRead, DeltaWrite, GammaRead, GammaWrite, ZetaRead, ZetaWrite},
+    codes::{
+        DeltaRead, DeltaWrite, ExpGolombRead, ExpGolombWrite, GammaRead, GammaWrite, GolombRead,
+        GolombWrite, RiceRead, RiceWrite, ZetaRead, ZetaWrite,
+    },
     traits::Endianness,
 };
 
 use std::error::Error;
 
 /// A trait combining the codes used by [`DynCodesDecoder`] and [`ConstCodesDecoder`].
-pub trait CodeRead<E: Endianness>: GammaRead<E> + DeltaRead<E> + ZetaRead<E> {}
+pub trait CodeRead<E: Endianness>:
+    GammaRead<E>
+    + DeltaRead<E>
+    + ZetaRead<E>
+    + PiRead<E>
+    + GolombRead<E>
+    + ExpGolombRead<E>
+    + RiceRead<E>
+{
+}
 /// A trait combining the codes used by [`DynCodesEncoder`] and [`ConstCodesEncoder`].
-pub trait CodeWrite<E: Endianness>: GammaWrite<E> + DeltaWrite<E> + ZetaWrite<E> {}
+pub trait CodeWrite<E: Endianness>:
+    GammaWrite<E>
+    + DeltaWrite<E>
+    + ZetaWrite<E>
+    + PiWrite<E>
+    + GolombWrite<E>
+    + ExpGolombWrite<E>
+    + RiceWrite<E>
+{
+}
 
 /// Blanket implementation so we can consider [`CodeRead`] just as an alias for
 /// a sum of traits.
-impl<E: Endianness, T> CodeRead<E> for T where T: GammaRead<E> + DeltaRead<E> + ZetaRead<E> {}
+impl<E: Endianness, T> CodeRead<E> for T where
+    T: GammaRead<E>
+        + DeltaRead<E>
+        + ZetaRead<E>
+        + PiRead<E>
+        + GolombRead<E>
+        + ExpGolombRead<E>
+        + RiceRead<E>
+{
+}
 /// Blanket implementation so we can consider [`CodeWrite`] just as an alias for
 /// a sum of traits.
-impl<E: Endianness, T> CodeWrite<E> for T where T: GammaWrite<E> + DeltaWrite<E> + ZetaWrite<E> {}
+impl<E: Endianness, T> CodeWrite<E> for T where
+    T: GammaWrite<E>
+        + DeltaWrite<E>
+        + ZetaWrite<E>
+        + PiWrite<E>
+        + GolombWrite<E>
+        + ExpGolombWrite<E>
+        + RiceWrite<E>
+{
+}
 
 /// Methods to decode the component of a [`BVGraph`].
 pub trait Decode {
@@ -0,0 +1,102 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+/*!
+
+π codes.
+
+π codes are a family of instantaneous codes introduced by Apostolico and
+Drovandi in “[Graph Compression by
+BFS](https://doi.org/10.3390/a2031031)”. Given a parameter `k`, the π code
+of a natural number `x` is obtained by writing `n = x + 1` as `2^r + m`,
+where `r = ⌊log₂ n⌋`, splitting `h = r + 1` as `l · 2^k – v`, with
+`0 ≤ v < 2^k`, and then writing `l – 1` in unary, `v` in binary using `k`
+bits, and finally `m` in binary using `r` bits.
+
+The π code for `k = 0` is exactly [γ code](dsi_bitstream::codes::gamma).
+π codes behave like [ζ codes](dsi_bitstream::codes::zeta) for power-law
+distributions, but they have a more regular structure.
+
+*/
+
+use dsi_bitstream::prelude::*;
+
+/// Return the length of the π code for `n` with parameter `k`.
+///
+/// `n` must be smaller than [`u64::MAX`].
+#[must_use]
+#[inline]
+pub fn len_pi(n: u64, k: usize) -> usize {
+    debug_assert!(n < u64::MAX);
+    let r = (n + 1).ilog2() as usize;
+    let l = (r + 1).div_ceil(1 << k);
+    l + k + r
+}
+
+/// Trait for reading π codes.
+pub trait PiRead<E: Endianness>: BitRead<E> {
+    #[inline(always)]
+    fn read_pi(&mut self, k: usize) -> Result<u64, Self::Error> {
+        let l = self.read_unary()? as usize + 1;
+        let v = self.read_bits(k)? as usize;
+        let r = (l << k) - v - 1;
+        Ok((1 << r) + self.read_bits(r)? - 1)
+    }
+}
+
+/// Trait for writing π codes.
+pub trait PiWrite<E: Endianness>: BitWrite<E> {
+    /// Write `n` using the π code with parameter `k`.
+    ///
+    /// `n` must be smaller than [`u64::MAX`], as π codes encode `n + 1`.
+    #[inline]
+    fn write_pi(&mut self, n: u64, k: usize) -> Result<usize, Self::Error> {
+        debug_assert!(n < u64::MAX);
+        let n = n + 1;
+        let r = n.ilog2() as usize;
+        let l = (r + 1).div_ceil(1 << k);
+        let v = (l << k) - r - 1;
+        Ok(self.write_unary(l as u64 - 1)?
+            + self.write_bits(v as u64, k)?
+            + self.write_bits(n ^ (1 << r), r)?)
+    }
+}
+
+impl<E: Endianness, B: BitRead<E>> PiRead<E> for B {}
+impl<E: Endianness, B: BitWrite<E>> PiWrite<E> for B {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pi() -> anyhow::Result<()> {
+        for k in 0..5 {
+            let mut buffer = Vec::<u64>::new();
+            let mut len = 0;
+            let mut writer = <BufBitWriter<BE, _>>::new(MemWordWriterVec::new(&mut buffer));
+            for n in (0..1000).chain([u32::MAX as u64, (u64::MAX >> 1) - 1, u64::MAX - 1]) {
+                let written = writer.write_pi(n, k)?;
+                assert_eq!(written, len_pi(n, k));
+                len += written;
+            }
+            writer.flush()?;
+            drop(writer);
+
+            let mut reader = <BufBitReader<BE, _>>::new(MemWordReader::new(&buffer));
+            for n in (0..1000).chain([u32::MAX as u64, (u64::MAX >> 1) - 1, u64::MAX - 1]) {
+                assert_eq!(reader.read_pi(k)?, n);
+            }
+            assert_eq!(reader.bit_pos()?, len as u64);
+        }
+        // π₀ is γ
+        for n in 0..1000 {
+            assert_eq!(len_pi(n, 0), len_gamma(n));
+        }
+        Ok(())
+    }
+}
@@ -4,54 +4,111 @@
  * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
  */
 
-use super::*;
-use core::sync::atomic::{AtomicUsize, Ordering};
+use super::len_pi;
+use crate::graphs::Code;
+use dsi_bitstream::prelude::*;
 
-fn len_golomb(value: u64, b: u64) -> usize {
-    (value / b) as usize + 1 + len_minimal_binary(value % b, b)
-}
+/// How many ζ codes to consider (ζ₁-ζ₇).
+const ZETA: usize = 7;
+/// How many π codes to consider (π₁-π₄).
+const PI: usize = 4;
+/// How many Golomb codes to consider (moduli 2-8).
+const GOLOMB: usize = 7;
+/// How many exponential Golomb codes to consider (parameters 1-7).
+const EXP_GOLOMB: usize = 7;
+/// How many Rice codes to consider (parameters 1-7).
+const RICE: usize = 7;
 
 #[derive(Default, Clone, Debug)]
 /// Keeps track of the space needed to store a stream of integers using different codes.
 ///
 /// This structure can be used to determine empirically which code
-/// provides the best compression for a given stream.
+/// provides the best compression for a given stream. Only codes that
+/// are supported by [`DynCodesEncoder`](super::DynCodesEncoder) and
+/// [`DynCodesDecoder`](super::DynCodesDecoder) are considered.
 pub struct CodesStats {
-    pub unary: AtomicUsize,
-    pub gamma: AtomicUsize,
-    pub delta: AtomicUsize,
-    pub zeta: [AtomicUsize; 10],
-    pub golomb: [AtomicUsize; 20],
+    pub unary: u64,
+    pub gamma: u64,
+    pub delta: u64,
+    pub zeta: [u64; ZETA],
+    pub pi: [u64; PI],
+    pub golomb: [u64; GOLOMB],
+    pub exp_golomb: [u64; EXP_GOLOMB],
+    pub rice: [u64; RICE],
 }
 
 impl CodesStats {
     /// Update the stats with the lengths of the codes for `n` and return back
     /// `n` for convenience.
-    pub fn update(&self, n: u64) -> u64 {
-        self.unary.fetch_add(n as usize + 1, Ordering::Relaxed);
-        self.gamma.fetch_add(len_gamma(n), Ordering::Relaxed);
-        self.delta.fetch_add(len_delta(n), Ordering::Relaxed);
+    pub fn update(&mut self, n: u64) -> u64 {
+        self.unary += n + 1;
+        self.gamma += len_gamma(n) as u64;
+        self.delta += len_delta(n) as u64;
 
-        for (k, val) in self.zeta.iter().enumerate() {
-            val.fetch_add(len_zeta(n, (k + 1) as _), Ordering::Relaxed);
+        for (k, val) in self.zeta.iter_mut().enumerate() {
+            *val += len_zeta(n, (k + 1) as _) as u64;
         }
-        for (b, val) in self.golomb.iter().enumerate() {
-            val.fetch_add(len_golomb(n, (b + 1) as _), Ordering::Relaxed);
+        for (k, val) in self.pi.iter_mut().enumerate() {
+            *val += len_pi(n, k + 1) as u64;
+        }
+        for (b, val) in self.golomb.iter_mut().enumerate() {
+            *val += len_golomb(n, (b + 2) as _) as u64;
+        }
+        for (k, val) in self.exp_golomb.iter_mut().enumerate() {
+            *val += len_exp_golomb(n, k + 1) as u64;
+        }
+        for (log2_b, val) in self.rice.iter_mut().enumerate() {
+            *val += len_rice(n, log2_b + 1) as u64;
         }
         n
     }
 
+    /// Combines additively this stats with another one.
+    pub fn add(&mut self, rhs: &Self) {
+        self.unary += rhs.unary;
+        self.gamma += rhs.gamma;
+        self.delta += rhs.delta;
+        for (a, b) in self.zeta.iter_mut().zip(rhs.zeta.iter()) {
+            *a += *b;
+        }
+        for (a, b) in self.pi.iter_mut().zip(rhs.pi.iter()) {
+            *a += *b;
+        }
+        for (a, b) in self.golomb.iter_mut().zip(rhs.golomb.iter()) {
+            *a += *b;
+        }
+        for (a, b) in self.exp_golomb.iter_mut().zip(rhs.exp_golomb.iter()) {
+            *a += *b;
+        }
+        for (a, b) in self.rice.iter_mut().zip(rhs.rice.iter()) {
+            *a += *b;
+        }
+    }
+
+    /// Return the space usage of the given code, or `None` if the code
+    /// is not tracked by this structure.
+    pub fn get(&self, code: Code) -> Option<u64> {
+        match code {
+            Code::Unary => Some(self.unary),
+            Code::Gamma => Some(self.gamma),
+            Code::Delta => Some(self.delta),
+            Code::Zeta { k } => self.zeta.get(k.checked_sub(1)?).copied(),
+            Code::Pi { k } => self.pi.get(k.checked_sub(1)?).copied(),
+            Code::Golomb { b } => self.golomb.get(b.checked_sub(2)?).copied(),
+            Code::ExpGolomb { k } => self.exp_golomb.get(k.checked_sub(1)?).copied(),
+            Code::Rice { log2_b } => self.rice.get(log2_b.checked_sub(1)?).copied(),
+        }
+    }
+
     /// Return the best code for the stream and its space usage.
-    pub fn get_best_code(&self) -> (Code, usize) {
-        // TODO!: make cleaner
-        let mut best = self.unary.load(Ordering::Relaxed);
+    pub fn get_best_code(&self) -> (Code, u64) {
+        let mut best = self.unary;
         let mut best_code = Code::Unary;
 
         macro_rules! check {
             ($code:expr, $len:expr) => {
-                let len = $len.load(Ordering::Relaxed);
-                if len < best {
-                    best = len;
+                if $len < best {
+                    best = $len;
                     best_code = $code;
                 }
             };
@@ -61,10 +118,19 @@ impl CodesStats {
         check!(Code::Delta, self.delta);
 
         for (k, val) in self.zeta.iter().enumerate() {
-            check!(Code::Zeta { k: (k + 1) as _ }, *val);
+            check!(Code::Zeta { k: k + 1 }, *val);
+        }
+        for (k, val) in self.pi.iter().enumerate() {
+            check!(Code::Pi { k: k + 1 }, *val);
         }
         for (b, val) in self.golomb.iter().enumerate() {
-            check!(Code::Golomb { b: (b + 1) as _ }, *val);
+            check!(Code::Golomb { b: b + 2 }, *val);
+        }
+        for (k, val) in self.exp_golomb.iter().enumerate() {
+            check!(Code::ExpGolomb { k: k + 1 }, *val);
+        }
+        for (log2_b, val) in self.rice.iter().enumerate() {
+            check!(Code::Rice { log2_b: log2_b + 1 }, *val);
         }
 
         (best_code, best)
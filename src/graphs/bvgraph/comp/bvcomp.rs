@@ -38,6 +38,14 @@ pub struct BVComp<E> {
     /// The first node we are compressing, this is needed because during
     /// parallel compression we need to work on different chunks
     start_node: usize,
+    /// The number of nodes on which references are chosen optimally, or zero
+    /// if references are chosen greedily
+    chunk_size: usize,
+    /// The first node of the chunk being buffered
+    chunk_start: usize,
+    /// The lengths in bits of the nodes written by the last call to
+    /// [`push`](BVComp::push) or [`write_pending`](BVComp::write_pending)
+    last_written: Vec<u64>,
     /// The number of arcs compressed so far
     pub arcs: u64,
 }
@@ -324,6 +332,9 @@ impl<E: MeasurableEncoder> BVComp<E> {
             max_ref_count,
             start_node,
             curr_node: start_node,
+            chunk_size: 0,
+            chunk_start: start_node,
+            last_written: Vec::new(),
             compressors: (0..compression_window + 1)
                 .map(|_| Compressor::new())
                 .collect(),
@@ -331,11 +342,89 @@ impl<E: MeasurableEncoder> BVComp<E> {
         }
     }
 
+    /// Set the strategy used to choose references.
+    ///
+    /// With [`RefSelection::Optimal`] nodes are buffered and written a chunk
+    /// at a time, so a call to [`push`](Self::push) might write no node, or
+    /// many nodes. The lengths of the nodes actually written are available
+    /// from [`last_written`](Self::last_written).
+    ///
+    /// # Panics
+    ///
+    /// If some node has already been pushed, or if the compression window
+    /// has been restored with [`restore_window`](Self::restore_window).
+    pub fn with_ref_selection(mut self, ref_selection: RefSelection) -> Self {
+        assert_eq!(
+            self.curr_node, self.start_node,
+            "The reference selection must be set before pushing nodes or restoring the window"
+        );
+        self.chunk_size = match ref_selection {
+            // Without a window there is no reference to choose
+            _ if self.compression_window == 0 => 0,
+            RefSelection::Greedy => 0,
+            RefSelection::Optimal { chunk_size } => chunk_size.max(1),
+        };
+        // We need the successors of the chunk and of the window before it
+        let capacity = self.chunk_size + self.compression_window + 1;
+        self.backrefs = CircularBuffer::new(capacity);
+        self.ref_counts = CircularBuffer::new(capacity);
+        self
+    }
+
+    /// Restore the compression window, so that the compressor can continue
+    /// the compression of a graph whose nodes before the current one have
+    /// already been compressed.
+    ///
+    /// `window` must return, in order, the successors of the nodes preceding
+    /// the current one, together with the length of the chain of references
+    /// of each node (0 if the node has no reference). Only the last
+    /// `compression_window` nodes are used.
+    ///
+    /// # Panics
+    ///
+    /// If some node has already been pushed, or if `window` contains more
+    /// nodes than those preceding the current one.
+    pub fn restore_window(&mut self, window: impl IntoIterator<Item = (Vec<usize>, usize)>) {
+        assert_eq!(
+            self.curr_node, self.start_node,
+            "The compression window must be restored before pushing nodes"
+        );
+        let mut window = window.into_iter().collect::<Vec<_>>();
+        assert!(
+            window.len() <= self.curr_node,
+            "The window contains {} nodes, but there are only {} nodes before node {}",
+            window.len(),
+            self.curr_node,
+            self.curr_node
+        );
+        let len = window.len().min(self.compression_window);
+        let first = self.curr_node - len;
+        for (node, (successors, ref_count)) in (first..).zip(window.drain(window.len() - len..)) {
+            self.backrefs.replace(node, successors);
+            self.ref_counts[node] = ref_count;
+        }
+        self.start_node = first;
+    }
+
+    /// Return the lengths in bits of the nodes written by the last call to
+    /// [`push`](Self::push) or [`write_pending`](Self::write_pending).
+    ///
+    /// When references are chosen greedily, [`push`](Self::push) writes
+    /// exactly one node.
+    pub fn last_written(&self) -> &[u64] {
+        &self.last_written
+    }
+
     /// Push a new node to the compressor.
     /// The iterator must yield the successors of the node and the nodes HAVE
     /// TO BE CONTIGUOUS (i.e. if a node has no neighbours you have to pass an
     /// empty iterator)
+    ///
+    /// This returns the number of bits written, which, when references are
+    /// chosen optimally, are those of all the nodes of a chunk (or zero, if
+    /// the chunk is not complete yet).
     pub fn push<I: IntoIterator<Item = usize>>(&mut self, succ_iter: I) -> anyhow::Result<u64> {
+        self.last_written.clear();
         // collect the iterator inside the backrefs, to reuse the capacity already
         // allocated
         {
@@ -344,9 +433,16 @@ impl<E: MeasurableEncoder> BVComp<E> {
             succ_vec.extend(succ_iter);
             self.backrefs.replace(self.curr_node, succ_vec);
         }
+        self.arcs += self.backrefs[self.curr_node].len() as u64;
+        if self.chunk_size != 0 {
+            self.curr_node += 1;
+            if self.curr_node - self.chunk_start == self.chunk_size {
+                return self.write_chunk();
+            }
+            return Ok(0);
+        }
         // get the ref
         let curr_list = &self.backrefs[self.curr_node];
-        self.arcs += curr_list.len() as u64;
         // first try to compress the current node without references
         let compressor = &mut self.compressors[0];
         // Compute how we would compress this
@@ -359,6 +455,7 @@ impl<E: MeasurableEncoder> BVComp<E> {
                 None,
                 self.min_interval_length,
             )?;
+            self.last_written.push(written_bits);
             // update the current node
             self.curr_node += 1;
             return Ok(written_bits);
@@ -428,17 +525,200 @@ impl<E: MeasurableEncoder> BVComp<E> {
         self.ref_counts[self.curr_node] = ref_count;
         // consistency check
         debug_assert_eq!(written_bits, min_bits);
+        self.last_written.push(written_bits);
         // update the current node
         self.curr_node += 1;
         Ok(written_bits)
     }
 
+    /// Write the nodes buffered when references are chosen optimally,
+    /// returning the number of bits written.
+    ///
+    /// Nodes pushed afterwards will be part of a new chunk. This method does
+    /// nothing if references are chosen greedily.
+    pub fn write_pending(&mut self) -> anyhow::Result<u64> {
+        self.last_written.clear();
+        if self.chunk_size == 0 {
+            return Ok(0);
+        }
+        self.write_chunk()
+    }
+
+    /// Choose the references of the nodes of the current chunk and write them.
+    ///
+    /// We first pick for each node its best reference disregarding chain
+    /// lengths inside the chunk, obtaining a forest. Then, we compute by
+    /// dynamic programming on the forest which references to remove so that
+    /// no chain is longer than `max_ref_count` while losing as few bits as
+    /// possible. Since this is not necessarily optimal among all possible
+    /// reference choices, we also simulate the greedy choice, and keep the
+    /// best of the two.
+    fn write_chunk(&mut self) -> anyhow::Result<u64> {
+        let start = self.chunk_start;
+        let len = self.curr_node - start;
+        let window = self.compression_window;
+        let max_ref_count = self.max_ref_count;
+
+        // costs[i * (window + 1) + delta] is the number of bits needed to
+        // write the i-th node of the chunk using delta as reference offset,
+        // or u64::MAX if the reference is not usable
+        let mut costs = vec![u64::MAX; len * (window + 1)];
+        // the best reference offset of each node, 0 if no reference
+        let mut parent = vec![0; len];
+        let compressor = &mut self.compressors[0];
+        for i in 0..len {
+            let node = start + i;
+            let curr_list = &self.backrefs[node];
+            let node_costs = &mut costs[i * (window + 1)..(i + 1) * (window + 1)];
+            compressor.compress(curr_list, None, self.min_interval_length)?;
+            node_costs[0] = compressor.write(
+                &mut self.encoder.estimator(),
+                node,
+                Some(0),
+                self.min_interval_length,
+            )?;
+            if max_ref_count == 0 {
+                continue;
+            }
+            for delta in 1..1 + window.min(node - self.start_node) {
+                let ref_node = node - delta;
+                // Nodes of previous chunks have already been written
+                if delta > i && self.ref_counts[ref_node] >= max_ref_count {
+                    continue;
+                }
+                let ref_list = &self.backrefs[ref_node];
+                if ref_list.is_empty() {
+                    continue;
+                }
+                compressor.compress(curr_list, Some(ref_list), self.min_interval_length)?;
+                node_costs[delta] = compressor.write(
+                    &mut self.encoder.estimator(),
+                    node,
+                    Some(delta),
+                    self.min_interval_length,
+                )?;
+                // strictly less, so we keep the nearest reference among equals
+                if node_costs[delta] < node_costs[parent[i]] {
+                    parent[i] = delta;
+                }
+            }
+        }
+        let cost = |i: usize, delta: usize| costs[i * (window + 1) + delta];
+        let saving = |i: usize| cost(i, 0) - cost(i, parent[i]);
+
+        // The height of the subtree of each node in the forest of references
+        let mut height = vec![0; len];
+        for i in (0..len).rev() {
+            if parent[i] != 0 && parent[i] <= i {
+                height[i - parent[i]] = height[i - parent[i]].max(height[i] + 1);
+            }
+        }
+        // The allowance of a node is the number of further references that
+        // can be chained to it; allowances larger than the maximum height of
+        // the forest are all equivalent, as they do not constrain anything
+        let max_allowance = max_ref_count.min(height.iter().copied().max().unwrap_or(0));
+        let row = max_allowance + 1;
+        // best[i * row + a] is the maximum saving obtainable in the subtree of
+        // the i-th node when it has allowance a; children have larger
+        // indices, so they are completed before their parent
+        let mut best = vec![0_u64; len * row];
+        for i in (0..len).rev() {
+            if parent[i] == 0 || parent[i] > i {
+                continue;
+            }
+            let p = i - parent[i];
+            let cut = best[i * row + max_allowance];
+            // with no allowance the reference must be removed
+            best[p * row] += cut;
+            for a in 1..row {
+                let keep = saving(i) + best[i * row + a - 1];
+                best[p * row + a] += keep.max(cut);
+            }
+        }
+
+        // Reconstruct the optimal references, parents first
+        let mut allowance = vec![0_usize; len];
+        let mut opt_refs = vec![0; len];
+        let mut opt_cost = 0;
+        for i in 0..len {
+            let delta = parent[i];
+            let keep_allowance = if delta == 0 {
+                None
+            } else if delta <= i {
+                allowance[i - delta].checked_sub(1)
+            } else {
+                // the depth of nodes of previous chunks is smaller than
+                // max_ref_count, or they would not be a parent
+                Some((max_ref_count - self.ref_counts[start + i - delta] - 1).min(max_allowance))
+            };
+            match keep_allowance {
+                Some(a) if saving(i) + best[i * row + a] >= best[i * row + max_allowance] => {
+                    opt_refs[i] = delta;
+                    allowance[i] = a;
+                }
+                _ => allowance[i] = max_allowance,
+            }
+            opt_cost += cost(i, opt_refs[i]);
+        }
+
+        // Simulate the greedy choice
+        let mut depth = vec![0; len];
+        let mut greedy_refs = vec![0; len];
+        let mut greedy_cost = 0;
+        for i in 0..len {
+            for delta in 1..1 + window.min(start + i - self.start_node) {
+                let ref_depth = if delta <= i {
+                    depth[i - delta]
+                } else {
+                    self.ref_counts[start + i - delta]
+                };
+                if ref_depth < max_ref_count && cost(i, delta) < cost(i, greedy_refs[i]) {
+                    greedy_refs[i] = delta;
+                    depth[i] = ref_depth + 1;
+                }
+            }
+            greedy_cost += cost(i, greedy_refs[i]);
+        }
+
+        let refs = if greedy_cost < opt_cost {
+            greedy_refs
+        } else {
+            opt_refs
+        };
+
+        let mut written_bits = 0;
+        let compressor = &mut self.compressors[0];
+        for (i, &delta) in refs.iter().enumerate() {
+            let node = start + i;
+            let ref_list = (delta != 0).then(|| self.backrefs[node - delta].as_slice());
+            compressor.compress(&self.backrefs[node], ref_list, self.min_interval_length)?;
+            let bits = compressor.write(
+                &mut self.encoder,
+                node,
+                Some(delta),
+                self.min_interval_length,
+            )?;
+            // consistency check
+            debug_assert_eq!(bits, cost(i, delta));
+            self.ref_counts[node] = if delta == 0 {
+                0
+            } else {
+                self.ref_counts[node - delta] + 1
+            };
+            self.last_written.push(bits);
+            written_bits += bits;
+        }
+        self.chunk_start = self.curr_node;
+        Ok(written_bits)
+    }
+
     /// Given an iterator over the nodes successors iterators, push them all.
     /// The iterator must yield the successors of the node and the nodes HAVE
     /// TO BE CONTIGUOUS (i.e. if a node has no neighbours you have to pass an
     /// empty iterator).
     ///
-    /// This most commonly is called with a reference to a graph.
+    /// This most commonly is called with a reference to a graph. At the end,
+    /// all nodes buffered to choose references optimally are written.
     pub fn extend<L>(&mut self, iter_nodes: L) -> anyhow::Result<u64>
     where
         L: IntoLender,
@@ -450,13 +730,24 @@ impl<E: MeasurableEncoder> BVComp<E> {
         });
         // WAS
         // iter_nodes.for_each(|(_, succ)| self.push(succ)).sum()
+        count += self.write_pending()?;
         Ok(count)
     }
 
     /// Consume the compressor return the number of bits written by
-    /// flushing the encoder (0 for instantaneous codes)
-    pub fn flush(mut self) -> Result<usize, E::Error> {
-        self.encoder.flush()
+    /// flushing the encoder (0 for instantaneous codes).
+    ///
+    /// Buffered nodes are written before flushing, but their length is not
+    /// included in the result: use [`write_pending`](Self::write_pending)
+    /// if you need it.
+    ///
+    /// Since buffered nodes are written using
+    /// [`write_pending`](Self::write_pending), this method returns an
+    /// [`anyhow::Result`], like [`push`](Self::push), rather than the
+    /// `Result<usize, E::Error>` of the encoder it used to return.
+    pub fn flush(mut self) -> anyhow::Result<usize> {
+        self.write_pending()?;
+        Ok(self.encoder.flush()?)
     }
 }
 
@@ -9,6 +9,12 @@ use anyhow::{bail, ensure, Result};
 use dsi_bitstream::traits::{BigEndian, Endianness, LittleEndian};
 use std::collections::HashMap;
 
+/// The codes that can be used for the streams of a graph.
+///
+/// The parameter of ζ codes and exponential Golomb codes must be in the
+/// range 1-7, the parameter of π codes in the range 1-4, the modulus of
+/// Golomb codes in the range 2-8, and the base-2 logarithm of the modulus of
+/// Rice codes in the range 1-7.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum Code {
@@ -16,17 +22,73 @@ pub enum Code {
     Gamma,
     Delta,
     Zeta { k: usize },
+    Pi { k: usize },
+    Golomb { b: usize },
+    ExpGolomb { k: usize },
+    Rice { log2_b: usize },
+}
+
+impl Code {
+    /// Return whether the parameter of the code is in the range supported by
+    /// the decoders.
+    fn is_supported(&self) -> bool {
+        match *self {
+            Code::Unary | Code::Gamma | Code::Delta => true,
+            Code::Zeta { k } | Code::ExpGolomb { k } => (1..=7).contains(&k),
+            Code::Pi { k } => (1..=4).contains(&k),
+            Code::Golomb { b } => (2..=8).contains(&b),
+            Code::Rice { log2_b } => (1..=7).contains(&log2_b),
+        }
+    }
+}
+
+/// The strategy used by [`BVComp`](super::BVComp) to choose the reference
+/// of each node.
+///
+/// The choice does not influence the format of the graph, but just its size
+/// and the compression time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum RefSelection {
+    /// Choose for each node the reference yielding the shortest
+    /// representation among those that would not make the reference chain
+    /// longer than the maximum reference count.
+    #[default]
+    Greedy,
+    /// Buffer nodes in chunks of `chunk_size` nodes, build the forest of the
+    /// best references of the nodes in the chunk, and remove optimally
+    /// (by dynamic programming) the references breaking the maximum
+    /// reference count. The result is used only if it is better than the
+    /// greedy choice on the same chunk.
+    Optimal { chunk_size: usize },
+}
+
+impl RefSelection {
+    /// A reasonable chunk size for [`RefSelection::Optimal`].
+    pub const DEFAULT_CHUNK_SIZE: usize = 10_000;
 }
 
 #[derive(Clone, Copy, Debug)]
 #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 /// The compression flags for reading or compressing a graph.
 ///
-/// As documented, one code might sets multiple values. This is done for
-/// compatibility with the previous Java version of the library.
-/// But the codes optimizers will return the optimal codes for each of them,
-/// so if it identify some big save from using different codes, we can consider
-/// splitting them.
+/// A graph is made of nine streams (outdegrees, reference offsets, block
+/// counts, blocks, interval counts, interval starts, interval lengths, first
+/// residuals, and residuals), but for compatibility with the Java version of
+/// the library, as documented, one code might set multiple values. Each of
+/// the streams sharing a code can however be assigned its own code using the
+/// optional fields [`block_counts`](CompFlags::block_counts),
+/// [`interval_counts`](CompFlags::interval_counts),
+/// [`interval_starts`](CompFlags::interval_starts),
+/// [`interval_lens`](CompFlags::interval_lens), and
+/// [`first_residuals`](CompFlags::first_residuals): when they are `None`,
+/// the code of the respective group is used.
+///
+/// Setting any of the optional fields breaks interoperability: the
+/// resulting `.properties` file contains compression flags that neither the
+/// Java version nor older versions of this crate can parse, so the graph can
+/// be read only by versions of this crate supporting them. A warning is
+/// logged when such a file is written.
 pub struct CompFlags {
     /// The instantaneous code to use to encode the `outdegrees`
     pub outdegrees: Code,
@@ -38,6 +100,21 @@ pub struct CompFlags {
     pub intervals: Code,
     /// The instantaneous code to use to encode the `first_residual` and `residual`
     pub residuals: Code,
+    /// If not `None`, the instantaneous code to use to encode the `block_count`
+    /// in place of [`blocks`](CompFlags::blocks)
+    pub block_counts: Option<Code>,
+    /// If not `None`, the instantaneous code to use to encode the
+    /// `interval_count` in place of [`intervals`](CompFlags::intervals)
+    pub interval_counts: Option<Code>,
+    /// If not `None`, the instantaneous code to use to encode the
+    /// `interval_start` in place of [`intervals`](CompFlags::intervals)
+    pub interval_starts: Option<Code>,
+    /// If not `None`, the instantaneous code to use to encode the
+    /// `interval_len` in place of [`intervals`](CompFlags::intervals)
+    pub interval_lens: Option<Code>,
+    /// If not `None`, the instantaneous code to use to encode the
+    /// `first_residual` in place of [`residuals`](CompFlags::residuals)
+    pub first_residuals: Option<Code>,
     /// The minimum length of an interval to be compressed as (start, len)
     pub min_interval_length: usize,
     /// The number of previous nodes to use for reference compression
@@ -45,6 +122,9 @@ pub struct CompFlags {
     /// The maximum recursion depth during decoding, this modulates the tradeoff
     /// between compression ratio and decoding speed
     pub max_ref_count: usize,
+    /// How references are chosen during compression; this setting is not
+    /// stored in the `.properties` file, as it is not needed for decoding
+    pub ref_selection: RefSelection,
 }
 
 impl core::default::Default for CompFlags {
@@ -55,34 +135,119 @@ impl core::default::Default for CompFlags {
             blocks: Code::Gamma,
             intervals: Code::Gamma,
             residuals: Code::Zeta { k: 3 },
+            block_counts: None,
+            interval_counts: None,
+            interval_starts: None,
+            interval_lens: None,
+            first_residuals: None,
             min_interval_length: 4,
             compression_window: 7,
             max_ref_count: 3,
+            ref_selection: RefSelection::Greedy,
         }
     }
 }
 
 impl CompFlags {
+    /// Return the code used for the `block_count` stream.
+    pub fn block_count_code(&self) -> Code {
+        self.block_counts.unwrap_or(self.blocks)
+    }
+
+    /// Return the code used for the `interval_count` stream.
+    pub fn interval_count_code(&self) -> Code {
+        self.interval_counts.unwrap_or(self.intervals)
+    }
+
+    /// Return the code used for the `interval_start` stream.
+    pub fn interval_start_code(&self) -> Code {
+        self.interval_starts.unwrap_or(self.intervals)
+    }
+
+    /// Return the code used for the `interval_len` stream.
+    pub fn interval_len_code(&self) -> Code {
+        self.interval_lens.unwrap_or(self.intervals)
+    }
+
+    /// Return the code used for the `first_residual` stream.
+    pub fn first_residual_code(&self) -> Code {
+        self.first_residuals.unwrap_or(self.residuals)
+    }
+
+    /// Return whether a code is set for a single stream, that is, whether one
+    /// of [`block_counts`](CompFlags::block_counts),
+    /// [`interval_counts`](CompFlags::interval_counts),
+    /// [`interval_starts`](CompFlags::interval_starts),
+    /// [`interval_lens`](CompFlags::interval_lens), or
+    /// [`first_residuals`](CompFlags::first_residuals) is not `None`.
+    ///
+    /// Such codes are written in the `.properties` file using flags (e.g.,
+    /// `BLOCK_COUNTS_GAMMA`) that the Java version and older versions of this
+    /// crate cannot parse.
+    pub fn has_split_codes(&self) -> bool {
+        self.block_counts.is_some()
+            || self.interval_counts.is_some()
+            || self.interval_starts.is_some()
+            || self.interval_lens.is_some()
+            || self.first_residuals.is_some()
+    }
+
     /// Convert a string from the `compflags` field from the `.properties` file
     /// into which code to use.
     ///
-    /// Returns `None` if the string is not recognized.
+    /// The parameter of ζ codes is usually specified separately by `k` (it
+    /// is the `zetak` property), but it can be also part of the string (e.g.,
+    /// `ZETA2`), as it happens for π, Golomb, exponential Golomb, and Rice
+    /// codes (e.g., `PI2`).
+    ///
+    /// Returns `None` if the string is not recognized, or if the parameter of
+    /// the code is not in the range supported by the decoders (see [`Code`]).
     pub fn code_from_str(s: &str, k: usize) -> Option<Code> {
-        match s.to_uppercase().as_str() {
-            "UNARY" => Some(Code::Unary),
-            "GAMMA" => Some(Code::Gamma),
-            "DELTA" => Some(Code::Delta),
-            "ZETA" => Some(Code::Zeta { k }),
-            _ => None,
+        Self::parse_code(s, k).filter(Code::is_supported)
+    }
+
+    fn parse_code(s: &str, k: usize) -> Option<Code> {
+        let s = s.to_uppercase();
+        match s.as_str() {
+            "UNARY" => return Some(Code::Unary),
+            "GAMMA" => return Some(Code::Gamma),
+            "DELTA" => return Some(Code::Delta),
+            "ZETA" => return Some(Code::Zeta { k }),
+            _ => {}
+        }
+        if let Some(k) = s.strip_prefix("ZETA") {
+            return Some(Code::Zeta { k: k.parse().ok()? });
+        }
+        // Longer prefixes first, as GOLOMB is a suffix of EXPGOLOMB
+        if let Some(k) = s.strip_prefix("EXPGOLOMB") {
+            return Some(Code::ExpGolomb { k: k.parse().ok()? });
         }
+        if let Some(b) = s.strip_prefix("GOLOMB") {
+            return Some(Code::Golomb { b: b.parse().ok()? });
+        }
+        if let Some(log2_b) = s.strip_prefix("RICE") {
+            return Some(Code::Rice {
+                log2_b: log2_b.parse().ok()?,
+            });
+        }
+        if let Some(k) = s.strip_prefix("PI") {
+            return Some(Code::Pi { k: k.parse().ok()? });
+        }
+        None
     }
 
-    pub fn code_to_str(c: Code) -> Option<&'static str> {
+    /// Convert a code into the string used in the `compflags` field of the
+    /// `.properties` file. The inverse of [`CompFlags::code_from_str`].
+    pub fn code_to_str(c: Code) -> Option<String> {
         match c {
-            Code::Unary => Some("UNARY"),
-            Code::Gamma => Some("GAMMA"),
-            Code::Delta => Some("DELTA"),
-            Code::Zeta { k: _ } => Some("ZETA"),
+            Code::Unary => Some("UNARY".to_string()),
+            Code::Gamma => Some("GAMMA".to_string()),
+            Code::Delta => Some("DELTA".to_string()),
+            Code::Zeta { k: _ } => Some("ZETA".to_string()),
+            Code::Pi { k } => Some(format!("PI{}", k)),
+            Code::Golomb { b } => Some(format!("GOLOMB{}", b)),
+            Code::ExpGolomb { k } => Some(format!("EXPGOLOMB{}", k)),
+            Code::Rice { log2_b } => Some(format!("RICE{}", log2_b)),
         }
     }
 
@@ -101,71 +266,75 @@ impl CompFlags {
         s.push_str(&format!("nodes={}\n", num_nodes));
         s.push_str(&format!("arcs={}\n", num_arcs));
         s.push_str(&format!("minintervallength={}\n", self.min_interval_length));
-        s.push_str(&format!("maxrefcount={}\n", self.max_ref_count));
+        // The Java version uses -1 for unbounded chains
+        if self.max_ref_count == usize::MAX {
+            s.push_str("maxrefcount=-1\n");
+        } else {
+            s.push_str(&format!("maxrefcount={}\n", self.max_ref_count));
+        }
         s.push_str(&format!("windowsize={}\n", self.compression_window));
-        s.push_str("compressionflags=");
-        let mut cflags = false;
+        // The parameter of ζ codes is stored in the zetak property, using
+        // preferably the one of the residuals; ζ codes with a different
+        // parameter are written explicitly (e.g., ZETA2)
+        let zeta_k = [
+            self.residuals,
+            self.first_residual_code(),
+            self.outdegrees,
+            self.references,
+            self.blocks,
+            self.block_count_code(),
+            self.intervals,
+            self.interval_count_code(),
+            self.interval_start_code(),
+            self.interval_len_code(),
+        ]
+        .into_iter()
+        .find_map(|code| match code {
+            Code::Zeta { k } => Some(k),
+            _ => None,
+        })
+        .unwrap_or(3);
+        let code_to_flag = |code: Code| match code {
+            Code::Zeta { k } if k != zeta_k => format!("ZETA{}", k),
+            code => Self::code_to_str(code).unwrap(),
+        };
+
+        let mut cflags = vec![];
         if self.outdegrees != Code::Gamma {
-            s.push_str(&format!(
-                "OUTDEGREES_{}|",
-                Self::code_to_str(self.outdegrees).unwrap()
-            ));
-            cflags = true;
+            cflags.push(format!("OUTDEGREES_{}", code_to_flag(self.outdegrees)));
         }
         if self.references != Code::Unary {
-            s.push_str(&format!(
-                "REFERENCES_{}|",
-                Self::code_to_str(self.references).unwrap()
-            ));
-            cflags = true;
+            cflags.push(format!("REFERENCES_{}", code_to_flag(self.references)));
         }
         if self.blocks != Code::Gamma {
-            s.push_str(&format!(
-                "BLOCKS_{}|",
-                Self::code_to_str(self.blocks).unwrap()
-            ));
-            cflags = true;
+            cflags.push(format!("BLOCKS_{}", code_to_flag(self.blocks)));
         }
         if self.intervals != Code::Gamma {
-            s.push_str(&format!(
-                "INTERVALS_{}|",
-                Self::code_to_str(self.intervals).unwrap()
-            ));
-            cflags = true;
-        }
-        if matches!(self.residuals, Code::Zeta { k: _ }) {
-            s.push_str(&format!(
-                "RESIDUALS_{}|",
-                Self::code_to_str(self.residuals).unwrap()
-            ));
-            cflags = true;
-        }
-        if cflags {
-            s.pop();
-        }
-        s.push('\n');
-        // check that if a k is specified, it is the same for all codes
-        let mut k = None;
-        macro_rules! check_and_set_k {
-            ($code:expr) => {
-                match $code {
-                    Code::Zeta { k: new_k } => {
-                        if let Some(old_k) = k {
-                            ensure!(old_k == new_k, "Only one value of k is supported")
-                        }
-                        k = Some(new_k)
-                    }
-                    _ => {}
-                }
-            };
+            cflags.push(format!("INTERVALS_{}", code_to_flag(self.intervals)));
+        }
+        if self.residuals != (Code::Zeta { k: zeta_k }) {
+            cflags.push(format!("RESIDUALS_{}", code_to_flag(self.residuals)));
         }
-        check_and_set_k!(self.outdegrees);
-        check_and_set_k!(self.references);
-        check_and_set_k!(self.blocks);
-        check_and_set_k!(self.intervals);
-        check_and_set_k!(self.residuals);
-        // if no k was specified, use the default one (3)
-        s.push_str(&format!("zetak={}\n", k.unwrap_or(3)));
+        // Codes for single streams, which are not supported by the Java
+        // version, are written only when set
+        if self.has_split_codes() {
+            log::warn!(
+                "Codes for single streams are set: the graph will not be readable by the Java version or by older versions of this crate"
+            );
+        }
+        for (name, code) in [
+            ("BLOCK_COUNTS", self.block_counts),
+            ("INTERVAL_COUNTS", self.interval_counts),
+            ("INTERVAL_STARTS", self.interval_starts),
+            ("INTERVAL_LENS", self.interval_lens),
+            ("FIRST_RESIDUALS", self.first_residuals),
+        ] {
+            if let Some(code) = code {
+                cflags.push(format!("{}_{}", name, code_to_flag(code)));
+            }
+        }
+        s.push_str(&format!("compressionflags={}\n", cflags.join("|")));
+        s.push_str(&format!("zetak={}\n", zeta_k));
         Ok(s)
     }
 
@@ -195,26 +364,38 @@ impl CompFlags {
 
         let mut cf = CompFlags::default();
         let mut k = 3;
-        if let Some(spec_k) = map.get("zeta_k") {
+        if let Some(spec_k) = map.get("zetak").or_else(|| map.get("zeta_k")) {
             let spec_k = spec_k.parse::<usize>()?;
             if !(1..=7).contains(&spec_k) {
                 bail!("Only ζ₁-ζ₇ are supported");
             }
             k = spec_k;
         }
+        // As in the Java version, residuals use ζ codes with parameter zetak
+        // unless otherwise specified
+        cf.residuals = Code::Zeta { k };
         if let Some(comp_flags) = map.get("compressionflags") {
             if !comp_flags.is_empty() {
                 for flag in comp_flags.split('|') {
-                    let s: Vec<_> = flag.split('_').collect();
+                    let Some((component, code)) = flag.rsplit_once('_') else {
+                        bail!("Malformed compression flag {}", flag);
+                    };
                     // FIXME: this is a hack to avoid having to implement
                     // FromStr for Code
-                    let code = CompFlags::code_from_str(s[1], k).unwrap();
-                    match s[0] {
+                    let Some(code) = CompFlags::code_from_str(code, k) else {
+                        bail!("Unknown code in compression flag {}", flag);
+                    };
+                    match component {
                         "OUTDEGREES" => cf.outdegrees = code,
                         "REFERENCES" => cf.references = code,
                         "BLOCKS" => cf.blocks = code,
                         "INTERVALS" => cf.intervals = code,
                         "RESIDUALS" => cf.residuals = code,
+                        "BLOCK_COUNTS" => cf.block_counts = Some(code),
+                        "INTERVAL_COUNTS" => cf.interval_counts = Some(code),
+                        "INTERVAL_STARTS" => cf.interval_starts = Some(code),
+                        "INTERVAL_LENS" => cf.interval_lens = Some(code),
+                        "FIRST_RESIDUALS" => cf.first_residuals = Some(code),
                         "OFFSETS" => {
                             ensure!(code == Code::Gamma, "Only γ code is supported for offsets")
                         }
@@ -229,6 +410,13 @@ impl CompFlags {
         if let Some(min_interval_length) = map.get("minintervallength") {
             cf.min_interval_length = min_interval_length.parse()?;
         }
+        if let Some(max_ref_count) = map.get("maxrefcount") {
+            // The Java version uses -1 for unbounded chains
+            cf.max_ref_count = match max_ref_count.as_str() {
+                "-1" => usize::MAX,
+                _ => max_ref_count.parse()?,
+            };
+        }
         Ok(cf)
     }
 }
@@ -9,10 +9,12 @@ use crate::prelude::*;
 use anyhow::{ensure, Context, Result};
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
+use epserde::prelude::*;
 use lender::prelude::*;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom};
 use std::path::Path;
+use sux::traits::{ConvertTo, IndexedDict};
 
 /// A queue that pulls jobs with ids in a contiguous initial segment of the
 /// natural numbers from an iterator out of order and implement an iterator in
@@ -81,6 +83,181 @@ impl JobId for Job {
     }
 }
 
+/// A labeled compression job.
+#[derive(Debug, PartialEq, PartialOrd, Eq, Ord, Clone, Copy)]
+struct LabeledJob {
+    job_id: usize,
+    first_node: usize,
+    last_node: usize,
+    written_bits: u64,
+    num_arcs: u64,
+    labels: BitStreamLabelingStats,
+}
+
+impl JobId for LabeledJob {
+    fn id(&self) -> usize {
+        self.job_id
+    }
+}
+
+/// Creates a buffered bit writer on a new file.
+fn create_bit_writer<E: Endianness>(
+    path: &Path,
+) -> Result<BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: BitWrite<E>,
+{
+    Ok(<BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(
+        BufWriter::with_capacity(
+            1 << 20,
+            File::create(path).with_context(|| format!("Could not create {}", path.display()))?,
+        ),
+    )))
+}
+
+/// Creates a buffered bit writer that appends bits to the first `bits` bits
+/// of an existing file.
+///
+/// The file is not truncated: the bits following the first `bits` bits are
+/// overwritten as new bits are written, and the first `bits` bits are left
+/// unchanged, so the file remains readable by readers using only its first
+/// `bits` bits.
+fn append_bit_writer<E: Endianness>(
+    path: &Path,
+    bits: u64,
+) -> Result<BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: BitWrite<E>,
+{
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .with_context(|| format!("Could not open {}", path.display()))?;
+    // We start writing at the word containing the last bit, rewriting its
+    // first bits, so that the file is laid out as if it were written at once;
+    // the word might be incomplete if the file was not written by this crate
+    let pos = bits / 64 * 8;
+    let rem = (bits % 64) as usize;
+    let mut bytes = [0; 8];
+    if rem != 0 {
+        let mut word = Vec::with_capacity(8);
+        file.seek(SeekFrom::Start(pos))
+            .and_then(|_| (&mut file).take(8).read_to_end(&mut word))
+            .with_context(|| format!("Could not read the last word of {}", path.display()))?;
+        bytes[..word.len()].copy_from_slice(&word);
+    }
+    file.seek(SeekFrom::Start(pos))
+        .with_context(|| format!("Could not seek {}", path.display()))?;
+    let mut writer = <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(
+        BufWriter::with_capacity(1 << 20, file),
+    ));
+    if rem != 0 {
+        let last = if E::IS_BIG {
+            u64::from_be_bytes(bytes) >> (64 - rem)
+        } else {
+            u64::from_le_bytes(bytes) & ((1 << rem) - 1)
+        };
+        writer
+            .write_bits(last, rem)
+            .with_context(|| format!("Could not write to {}", path.display()))?;
+    }
+    Ok(writer)
+}
+
+/// Appends the first `bits` bits of the file at `path` to `writer`.
+fn copy_bits<E: Endianness>(
+    writer: &mut BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>,
+    path: &Path,
+    bits: u64,
+) -> Result<()>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: BitWrite<E>,
+    BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+{
+    let mut reader = <BufBitReader<E, _>>::new(<WordAdapter<u32, _>>::new(BufReader::new(
+        File::open(path).with_context(|| format!("Could not open {}", path.display()))?,
+    )));
+    writer
+        .copy_from(&mut reader, bits)
+        .with_context(|| format!("Could not copy from {}", path.display()))
+}
+
+/// Compresses a labeled lender into the graph and labels bitstreams with
+/// the given basename, returning the resulting job, or `None` if the lender
+/// is empty.
+fn compress_labeled_lender<E, L, S>(
+    job_id: usize,
+    lender: &mut L,
+    basename: &Path,
+    cp_flags: &CompFlags,
+    serializer: &S,
+) -> Result<Option<LabeledJob>>
+where
+    E: Endianness,
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = (usize, S::SerType)>,
+    S: BitSerializer<E, FileBitWriter<E>>,
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+{
+    let Some((first_node, labeled_successors)) = lender.next() else {
+        return Ok(None);
+    };
+    let mut bvcomp = BVComp::new(
+        <DynCodesEncoder<E, _>>::new(
+            create_bit_writer::<E>(&basename.with_extension(GRAPH_EXTENSION))?,
+            cp_flags,
+        ),
+        cp_flags.compression_window,
+        cp_flags.max_ref_count,
+        cp_flags.min_interval_length,
+        first_node,
+    )
+    .with_ref_selection(cp_flags.ref_selection);
+    let mut labels_writer = BitStreamLabelingWriter::<E, _>::new(basename, serializer)?;
+
+    let mut written_bits = 0;
+    let mut successors = Vec::new();
+    let mut labels = Vec::new();
+    let mut push = |labeled_successors: &mut dyn Iterator<Item = (usize, S::SerType)>| {
+        successors.clear();
+        for (succ, label) in labeled_successors {
+            successors.push(succ);
+            labels.push(label);
+        }
+        written_bits += bvcomp
+            .push(successors.iter().copied())
+            .context("Could not push successors")?;
+        labels_writer.push(labels.drain(..))
+    };
+
+    push(&mut labeled_successors.into_iter())?;
+    let mut last_node = first_node;
+    while let Some((node_id, labeled_successors)) = lender.next() {
+        push(&mut labeled_successors.into_iter())?;
+        last_node = node_id;
+    }
+
+    written_bits += bvcomp.write_pending().context("Could not write nodes")?;
+    let num_arcs = bvcomp.arcs;
+    bvcomp.flush().context("Could not flush bvcomp")?;
+    labels_writer.flush()?;
+    let labels = labels_writer.stats();
+    log::info!(
+        "Finished Compression thread {} and wrote {} bits ({} bits of labels)",
+        job_id,
+        written_bits,
+        labels.labels_bits
+    );
+    Ok(Some(LabeledJob {
+        job_id,
+        first_node,
+        last_node,
+        written_bits,
+        num_arcs,
+        labels,
+    }))
+}
+
 impl BVComp<()> {
     /// Compresses s [`NodeLabelsLender`] and returns the lenght in bits of the
     /// graph bitstream.
@@ -106,11 +283,7 @@ impl BVComp<()> {
                 .with_context(|| format!("Could not create {}", graph_path.display()))?,
         )));
 
-        let comp_flags = CompFlags {
-            ..Default::default()
-        };
-
-        let codes_writer = DynCodesEncoder::new(bit_write, &comp_flags);
+        let codes_writer = DynCodesEncoder::new(bit_write, &compression_flags);
 
         let mut bvcomp = BVComp::new(
             codes_writer,
@@ -118,7 +291,8 @@ impl BVComp<()> {
             compression_flags.max_ref_count,
             compression_flags.min_interval_length,
             0,
-        );
+        )
+        .with_ref_selection(compression_flags.ref_selection);
 
         let mut pl = ProgressLogger::default();
         pl.display_memory(true)
@@ -141,18 +315,24 @@ impl BVComp<()> {
                 .write_gamma(0)
                 .context("Could not write initial delta")?;
             for_! ( (_node_id, successors) in iter {
-                let delta = bvcomp.push(successors).context("Could not push successors")?;
-                result += delta;
-                writer.write_gamma(delta).context("Could not write delta")?;
+                result += bvcomp.push(successors).context("Could not push successors")?;
+                for &delta in bvcomp.last_written() {
+                    writer.write_gamma(delta).context("Could not write delta")?;
+                }
                 pl.update();
                 real_num_nodes += 1;
             });
+            result += bvcomp.write_pending().context("Could not write nodes")?;
+            for &delta in bvcomp.last_written() {
+                writer.write_gamma(delta).context("Could not write delta")?;
+            }
         } else {
             for_! ( (_node_id, successors) in iter {
                 result += bvcomp.push(successors).context("Could not push successors")?;
                 pl.update();
                 real_num_nodes += 1;
             });
+            result += bvcomp.write_pending().context("Could not write nodes")?;
         }
         pl.done();
 
@@ -178,6 +358,262 @@ impl BVComp<()> {
         Ok(result)
     }
 
+    /// Appends the nodes returned by a [`NodeLabelsLender`] to the graph with
+    /// given basename and returns the length in bits of the appended part of
+    /// the graph bitstream.
+    ///
+    /// The lender must return contiguous nodes starting from the number of
+    /// nodes of the existing graph. The compression parameters are those of
+    /// the existing graph, as stored in its `.properties` file, but
+    /// references can be chosen using any [`RefSelection`]. The compression
+    /// window and the length of the reference chains are restored from the
+    /// last nodes of the existing graph, so with [`RefSelection::Greedy`] the
+    /// result is identical to that of compressing the whole graph at once.
+    ///
+    /// The `.ef` file of the existing graph is built if missing. The new nodes
+    /// are written directly at the end of the `.graph` and `.offsets` files,
+    /// leaving their existing content untouched, and the Elias–Fano
+    /// representation of the offsets is extended with the offsets of the new
+    /// nodes. The `.properties` file is replaced last, so until then (e.g., if
+    /// the process is interrupted) the files still describe the old graph.
+    pub fn append<E, L>(
+        basename: impl AsRef<Path>,
+        iter: L,
+        ref_selection: RefSelection,
+    ) -> Result<u64>
+    where
+        E: Endianness,
+        L: IntoLender,
+        L::Lender: for<'next> NodeLabelsLender<'next, Label = usize>,
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+        for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+    {
+        let basename = basename.as_ref();
+        let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+        let (num_nodes, num_arcs, mut compression_flags) = parse_properties::<E>(&properties_path)?;
+        compression_flags.ref_selection = ref_selection;
+
+        // This builds and stores the .ef file, if necessary
+        let graph = BVGraph::with_basename(basename)
+            .endianness::<E>()
+            .offsets_mode::<BuildEf<true>>()
+            .load()?;
+        let ef_path = basename.with_extension(EF_EXTENSION);
+        let offsets = EF::load_mmap(&ef_path, MemoryFlags::empty().into()).with_context(|| {
+            format!("Cannot load Elias-Fano pointer list {}", ef_path.display())
+        })?;
+        let graph_bits = offsets.get(num_nodes) as u64;
+
+        // Recover the successors of the nodes in the compression window and
+        // the length of their reference chains
+        let first = num_nodes.saturating_sub(compression_flags.compression_window);
+        let window = (first..num_nodes)
+            .map(|node| graph.successors(node).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let factory = graph.into_inner();
+        let mut ref_counts = Vec::with_capacity(window.len());
+        for node in first..num_nodes {
+            let mut ref_count = 0;
+            let mut curr = node;
+            loop {
+                let mut decoder = factory.new_decoder(curr)?;
+                if decoder.read_outdegree() == 0 {
+                    break;
+                }
+                let reference = decoder.read_reference_offset() as usize;
+                if reference == 0 {
+                    break;
+                }
+                ref_count += 1;
+                curr -= reference;
+            }
+            ref_counts.push(ref_count);
+        }
+        drop(factory);
+
+        let offsets_path = basename.with_extension(OFFSETS_EXTENSION);
+        let old_offsets = || (0..num_nodes + 1).map(|node| offsets.get(node) as u64);
+        let old_deltas = || {
+            old_offsets().scan(0, |offset, next_offset| {
+                let delta = next_offset - *offset;
+                *offset = next_offset;
+                Some(delta)
+            })
+        };
+        // If there is no .offsets file, we write the offsets of the old
+        // graph to a new one, which replaces the missing one once complete
+        let tmp_offsets_path = basename.with_extension(format!("{}.tmp", OFFSETS_EXTENSION));
+        let new_offsets_file = !offsets_path.exists();
+        let mut offsets_writer = if new_offsets_file {
+            let mut writer = create_bit_writer::<E>(&tmp_offsets_path)?;
+            for delta in old_deltas() {
+                writer.write_gamma(delta).context("Could not write delta")?;
+            }
+            writer
+        } else {
+            let offsets_bits = old_deltas().map(|delta| len_gamma(delta) as u64).sum();
+            append_bit_writer::<E>(&offsets_path, offsets_bits)?
+        };
+
+        let graph_writer =
+            append_bit_writer::<E>(&basename.with_extension(GRAPH_EXTENSION), graph_bits)?;
+        let mut bvcomp = BVComp::new(
+            DynCodesEncoder::new(graph_writer, &compression_flags),
+            compression_flags.compression_window,
+            compression_flags.max_ref_count,
+            compression_flags.min_interval_length,
+            num_nodes,
+        )
+        .with_ref_selection(compression_flags.ref_selection);
+        bvcomp.restore_window(window.into_iter().zip(ref_counts));
+
+        let mut pl = ProgressLogger::default();
+        pl.display_memory(true).item_name("node");
+        pl.start("Appending successors...");
+        let mut result = 0;
+        // the lengths in bits of the appended nodes
+        let mut lens = Vec::new();
+        let mut next_node = num_nodes;
+        for_! ( (node_id, successors) in iter {
+            ensure!(node_id == next_node, "Expected node {} but got node {}", next_node, node_id);
+            result += bvcomp.push(successors).context("Could not push successors")?;
+            lens.extend_from_slice(bvcomp.last_written());
+            next_node += 1;
+            pl.light_update();
+        });
+        result += bvcomp.write_pending().context("Could not write nodes")?;
+        lens.extend_from_slice(bvcomp.last_written());
+        pl.done();
+
+        let new_num_nodes = next_node;
+        let new_num_arcs = num_arcs + bvcomp.arcs;
+        bvcomp.flush().context("Could not flush bvcomp")?;
+
+        log::info!("Writing the offsets");
+        for &delta in &lens {
+            offsets_writer
+                .write_gamma(delta)
+                .context("Could not write delta")?;
+        }
+        offsets_writer.flush().context("Could not flush offsets")?;
+        drop(offsets_writer);
+        if new_offsets_file {
+            std::fs::rename(&tmp_offsets_path, &offsets_path)
+                .with_context(|| format!("Could not create {}", offsets_path.display()))?;
+        }
+
+        // The Elias-Fano representation of the old offsets is extended with
+        // the offsets of the new nodes
+        let mut efb =
+            sux::dict::EliasFanoBuilder::new(new_num_nodes + 1, (graph_bits + result) as usize);
+        let new_offsets = lens.iter().scan(graph_bits, |offset, len| {
+            *offset += len;
+            Some(*offset)
+        });
+        for offset in old_offsets().chain(new_offsets) {
+            efb.push(offset as usize)
+                .with_context(|| format!("Cannot add offset {}", offset))?;
+        }
+        let ef: EF = efb.build().convert_to()?;
+        drop(offsets);
+        let tmp_ef_path = basename.with_extension(format!("{}.tmp", EF_EXTENSION));
+        ef.store(&tmp_ef_path)
+            .with_context(|| format!("Could not store {}", tmp_ef_path.display()))?;
+        std::fs::rename(&tmp_ef_path, &ef_path)
+            .with_context(|| format!("Could not replace {}", ef_path.display()))?;
+
+        // All files must be on disk before the .properties file is replaced
+        for path in [
+            basename.with_extension(GRAPH_EXTENSION),
+            offsets_path,
+            ef_path,
+        ] {
+            File::open(&path)
+                .and_then(|file| file.sync_all())
+                .with_context(|| format!("Could not sync {}", path.display()))?;
+        }
+
+        log::info!("Writing the .properties file");
+        let properties = compression_flags
+            .to_properties::<E>(new_num_nodes, new_num_arcs)
+            .context("Could not serialize properties")?;
+        let tmp_properties_path = basename.with_extension(format!("{}.tmp", PROPERTIES_EXTENSION));
+        std::fs::write(&tmp_properties_path, properties)
+            .with_context(|| format!("Could not write {}", tmp_properties_path.display()))?;
+        std::fs::rename(&tmp_properties_path, &properties_path)
+            .with_context(|| format!("Could not replace {}", properties_path.display()))?;
+
+        log::info!(
+            "Appended {} nodes and {} arcs in {} bits",
+            new_num_nodes - num_nodes,
+            new_num_arcs - num_arcs,
+            result
+        );
+        Ok(result)
+    }
+
+    /// Returns the given compression flags with the codes of each stream
+    /// replaced by the best ones for the given graph.
+    ///
+    /// The graph is compressed in parallel using the given compression
+    /// flags, but instead of writing a bitstream the values of each stream
+    /// are recorded using a [`StatsEncoder`]; the best codes are then
+    /// chosen using [`DecoderStats::best_comp_flags`].
+    ///
+    /// If `sample_size` is not `None`, only about `sample_size` nodes will be
+    /// used, taken from the beginning of each of the parts in which the graph
+    /// is split.
+    pub fn optimize_codes<G: SplitLabeling + SequentialGraph>(
+        graph: &G,
+        compression_flags: CompFlags,
+        sample_size: Option<usize>,
+        mut threads: impl AsMut<rayon::ThreadPool>,
+    ) -> Result<CompFlags>
+    where
+        for<'a> <G as SplitLabeling>::SplitLender<'a>: Send + Sync,
+    {
+        let thread_pool = threads.as_mut();
+        let num_threads = thread_pool.current_num_threads();
+        let nodes_per_thread = sample_size.map(|s| s.div_ceil(num_threads).max(1));
+        let stats = std::sync::Mutex::new(DecoderStats::default());
+
+        thread_pool.in_place_scope(|s| {
+            let cp_flags = &compression_flags;
+            let stats = &stats;
+
+            for mut thread_lender in graph.split_iter(num_threads) {
+                s.spawn(move |_| {
+                    let Some((node_id, successors)) = thread_lender.next() else {
+                        return;
+                    };
+                    let mut bvcomp = BVComp::new(
+                        StatsEncoder::new(cp_flags, stats),
+                        cp_flags.compression_window,
+                        cp_flags.max_ref_count,
+                        cp_flags.min_interval_length,
+                        node_id,
+                    )
+                    .with_ref_selection(cp_flags.ref_selection);
+                    bvcomp.push(successors).unwrap();
+                    match nodes_per_thread {
+                        Some(n) => bvcomp.extend(thread_lender.take(n - 1)),
+                        None => bvcomp.extend(thread_lender),
+                    }
+                    .unwrap();
+                    // Dropping the compressor updates the global stats
+                    bvcomp.flush().unwrap();
+                });
+            }
+        });
+
+        let optimized = stats
+            .into_inner()
+            .unwrap()
+            .best_comp_flags(compression_flags);
+        log::info!("Optimized compression flags: {:?}", optimized);
+        Ok(optimized)
+    }
+
     /// A wrapper over [`parallel_graph`](Self::parallel_graph) that takes the
     /// endianness as a string.
     ///
@@ -314,7 +750,8 @@ impl BVComp<()> {
                                 cp_flags.max_ref_count,
                                 cp_flags.min_interval_length,
                                 node_id,
-                            );
+                            )
+                            .with_ref_selection(cp_flags.ref_selection);
                             let written_bits = bvcomp.push(successors).unwrap();
                             (bvcomp, written_bits)
                         } else {
@@ -435,4 +872,384 @@ impl BVComp<()> {
             Ok(total_written_bits)
         })
     }
+
+    /// A wrapper over [`parallel_iter`](Self::parallel_iter) that takes the
+    /// endianness as a string.
+    ///
+    /// Endianess can only be [`BE::NAME`](BE) or [`LE::NAME`](LE).
+    ///
+    /// A given endianess is enabled only if the corresponding feature is
+    /// enabled, `be_bins` for big endian and `le_bins` for little endian, or if
+    /// neither features are enabled.
+    pub fn parallel_iter_endianness<
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + Send,
+    >(
+        basename: impl AsRef<Path> + Send + Sync,
+        iter: impl Iterator<Item = L>,
+        num_nodes: usize,
+        compression_flags: CompFlags,
+        threads: impl AsMut<rayon::ThreadPool>,
+        tmp_dir: impl AsRef<Path>,
+        endianess: &str,
+    ) -> Result<u64> {
+        match endianess {
+            #[cfg(any(
+                feature = "be_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            BE::NAME => Self::parallel_iter::<BigEndian, _>(
+                basename,
+                iter,
+                num_nodes,
+                compression_flags,
+                threads,
+                tmp_dir,
+            ),
+            #[cfg(any(
+                feature = "le_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            LE::NAME => Self::parallel_iter::<LittleEndian, _>(
+                basename,
+                iter,
+                num_nodes,
+                compression_flags,
+                threads,
+                tmp_dir,
+            ),
+            x => anyhow::bail!("Unknown endianness {}", x),
+        }
+    }
+
+    /// Compresses a labeled [`NodeLabelsLender`] and returns the length in
+    /// bits of the graph bitstream.
+    ///
+    /// Besides the graph, this method writes the labels serialized with
+    /// `serializer` using a [`BitStreamLabelingWriter`]. The resulting
+    /// labeled graph can be loaded with
+    /// [`load_labeled`](crate::graphs::bvgraph::LoadConfig::load_labeled).
+    pub fn single_thread_labeled<E, L, S>(
+        basename: impl AsRef<Path>,
+        iter: L,
+        compression_flags: CompFlags,
+        serializer: S,
+        build_offsets: bool,
+        num_nodes: Option<usize>,
+    ) -> Result<u64>
+    where
+        E: Endianness,
+        L: IntoLender,
+        L::Lender: for<'next> NodeLabelsLender<'next, Label = (usize, S::SerType)>,
+        S: BitSerializer<E, FileBitWriter<E>>,
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    {
+        let basename = basename.as_ref();
+
+        let codes_writer = DynCodesEncoder::new(
+            create_bit_writer::<E>(&basename.with_extension(GRAPH_EXTENSION))?,
+            &compression_flags,
+        );
+
+        let mut bvcomp = BVComp::new(
+            codes_writer,
+            compression_flags.compression_window,
+            compression_flags.max_ref_count,
+            compression_flags.min_interval_length,
+            0,
+        )
+        .with_ref_selection(compression_flags.ref_selection);
+
+        let mut offsets_writer = if build_offsets {
+            let mut writer = create_bit_writer::<E>(&basename.with_extension(OFFSETS_EXTENSION))?;
+            writer
+                .write_gamma(0)
+                .context("Could not write initial delta")?;
+            Some(writer)
+        } else {
+            None
+        };
+
+        let mut labels_writer = BitStreamLabelingWriter::<E, S>::new(basename, serializer)?;
+
+        let mut pl = ProgressLogger::default();
+        pl.display_memory(true)
+            .item_name("node")
+            .expected_updates(num_nodes);
+        pl.start("Compressing successors and labels...");
+        let mut result = 0;
+
+        let mut real_num_nodes = 0;
+        let mut successors = Vec::new();
+        let mut labels = Vec::new();
+        for_! ( (_node_id, labeled_successors) in iter {
+            successors.clear();
+            for (succ, label) in labeled_successors {
+                successors.push(succ);
+                labels.push(label);
+            }
+            result += bvcomp.push(successors.iter().copied()).context("Could not push successors")?;
+            if let Some(writer) = offsets_writer.as_mut() {
+                for &delta in bvcomp.last_written() {
+                    writer.write_gamma(delta).context("Could not write delta")?;
+                }
+            }
+            labels_writer.push(labels.drain(..))?;
+            pl.update();
+            real_num_nodes += 1;
+        });
+        result += bvcomp.write_pending().context("Could not write nodes")?;
+        if let Some(writer) = offsets_writer.as_mut() {
+            for &delta in bvcomp.last_written() {
+                writer.write_gamma(delta).context("Could not write delta")?;
+            }
+        }
+        pl.done();
+
+        if let Some(num_nodes) = num_nodes {
+            if num_nodes != real_num_nodes {
+                log::warn!(
+                    "The expected number of nodes is {} but the actual number of nodes is {}",
+                    num_nodes,
+                    real_num_nodes
+                );
+            }
+        }
+
+        log::info!("Writing the .properties file");
+        let properties = compression_flags
+            .to_properties::<E>(real_num_nodes, bvcomp.arcs)
+            .context("Could not serialize properties")?;
+        let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+        std::fs::write(&properties_path, properties)
+            .with_context(|| format!("Could not write {}", properties_path.display()))?;
+
+        bvcomp.flush().context("Could not flush bvcomp")?;
+        if let Some(mut writer) = offsets_writer {
+            writer.flush().context("Could not flush offsets")?;
+        }
+        labels_writer.flush()?;
+        Ok(result)
+    }
+
+    /// Compresses a labeled graph in parallel and returns the length in bits
+    /// of the graph bitstream.
+    ///
+    /// See [`parallel_labeled_iter`](Self::parallel_labeled_iter).
+    pub fn parallel_labeled_graph<E: Endianness, S>(
+        basename: impl AsRef<Path> + Send + Sync,
+        graph: &(impl LabeledSequentialGraph<S::SerType> + SplitLabeling),
+        compression_flags: CompFlags,
+        serializer: S,
+        mut threads: impl AsMut<rayon::ThreadPool>,
+        tmp_dir: impl AsRef<Path>,
+    ) -> Result<u64>
+    where
+        S: BitSerializer<E, FileBitWriter<E>> + Sync,
+        S::SerType: Send,
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+        BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+    {
+        Self::parallel_labeled_iter(
+            basename,
+            graph
+                .split_iter(threads.as_mut().current_num_threads())
+                .into_iter(),
+            graph.num_nodes(),
+            compression_flags,
+            serializer,
+            threads,
+            tmp_dir,
+        )
+    }
+
+    /// A wrapper over [`parallel_labeled_graph`](Self::parallel_labeled_graph)
+    /// that takes the endianness as a string.
+    ///
+    /// Endianess can only be [`BE::NAME`](BE) or [`LE::NAME`](LE), and it is
+    /// used both for the graph and for the labels.
+    ///
+    /// A given endianess is enabled only if the corresponding feature is
+    /// enabled, `be_bins` for big endian and `le_bins` for little endian, or if
+    /// neither features are enabled.
+    pub fn parallel_labeled_endianness<L, S, G>(
+        basename: impl AsRef<Path> + Send + Sync,
+        graph: &G,
+        compression_flags: CompFlags,
+        serializer: S,
+        threads: impl AsMut<rayon::ThreadPool>,
+        tmp_dir: impl AsRef<Path>,
+        endianess: &str,
+    ) -> Result<u64>
+    where
+        L: Send,
+        S: BitSerializer<BE, FileBitWriter<BE>, SerType = L>
+            + BitSerializer<LE, FileBitWriter<LE>, SerType = L>
+            + Sync,
+        G: LabeledSequentialGraph<L> + SplitLabeling,
+    {
+        match endianess {
+            #[cfg(any(
+                feature = "be_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            BE::NAME => Self::parallel_labeled_graph::<BigEndian, S>(
+                basename,
+                graph,
+                compression_flags,
+                serializer,
+                threads,
+                tmp_dir,
+            ),
+            #[cfg(any(
+                feature = "le_bins",
+                not(any(feature = "be_bins", feature = "le_bins"))
+            ))]
+            LE::NAME => Self::parallel_labeled_graph::<LittleEndian, S>(
+                basename,
+                graph,
+                compression_flags,
+                serializer,
+                threads,
+                tmp_dir,
+            ),
+            x => anyhow::bail!("Unknown endianness {}", x),
+        }
+    }
+
+    /// Compresses multiple labeled [`NodeLabelsLender`] in parallel and
+    /// returns the length in bits of the graph bitstream.
+    ///
+    /// The lenders must return adjacent, increasing ranges of nodes. Besides
+    /// the graph, this method writes `.labels` and `.labeloffsets` files as
+    /// [`single_thread_labeled`](Self::single_thread_labeled).
+    pub fn parallel_labeled_iter<E, L, S>(
+        basename: impl AsRef<Path> + Send + Sync,
+        iter: impl Iterator<Item = L>,
+        num_nodes: usize,
+        compression_flags: CompFlags,
+        serializer: S,
+        mut threads: impl AsMut<rayon::ThreadPool>,
+        tmp_dir: impl AsRef<Path>,
+    ) -> Result<u64>
+    where
+        E: Endianness,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = (usize, S::SerType)> + Send,
+        S: BitSerializer<E, FileBitWriter<E>> + Sync,
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+        BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+    {
+        let thread_pool = threads.as_mut();
+
+        let tmp_dir = tmp_dir.as_ref();
+        let basename = basename.as_ref();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let thread_basename = |thread_id: usize| tmp_dir.join(format!("{:016x}", thread_id));
+
+        thread_pool.in_place_scope(|s| {
+            let cp_flags = &compression_flags;
+            let serializer = &serializer;
+
+            for (thread_id, mut thread_lender) in iter.enumerate() {
+                let basename = thread_basename(thread_id);
+                let tx = tx.clone();
+                // Spawn the thread
+                s.spawn(move |_| {
+                    log::info!("Thread {} started", thread_id);
+                    let result = compress_labeled_lender::<E, _, _>(
+                        thread_id,
+                        &mut thread_lender,
+                        &basename,
+                        cp_flags,
+                        serializer,
+                    );
+                    // Empty lenders do not generate jobs
+                    if let Some(result) = result.transpose() {
+                        tx.send(result).unwrap()
+                    }
+                });
+            }
+
+            drop(tx);
+
+            let mut graph_writer =
+                create_bit_writer::<E>(&basename.with_extension(GRAPH_EXTENSION))?;
+            let mut labels_writer = BitStreamLabelingWriter::<E, _>::new(basename, serializer)?;
+
+            let mut total_written_bits: u64 = 0;
+            let mut total_arcs: u64 = 0;
+
+            let mut next_node = 0;
+            // stop at the first error returned by a thread
+            let mut error = None;
+            let jobs = rx
+                .iter()
+                .map_while(|result: Result<LabeledJob>| result.map_err(|e| error = Some(e)).ok());
+            // glue toghether the bitstreams as they finish, this allows us to do
+            // task pipelining for better performance
+            for LabeledJob {
+                job_id,
+                first_node,
+                last_node,
+                written_bits,
+                num_arcs,
+                labels,
+            } in TaskQueue::new(jobs)
+            {
+                ensure!(
+                    first_node == next_node,
+                    "Non-adjacent lenders: lender {} has first node {} instead of {}",
+                    job_id,
+                    first_node,
+                    next_node
+                );
+
+                next_node = last_node + 1;
+                total_arcs += num_arcs;
+                total_written_bits += written_bits;
+                log::info!("Copying the bitstreams of lender {}", job_id);
+
+                copy_bits(
+                    &mut graph_writer,
+                    &thread_basename(job_id).with_extension(GRAPH_EXTENSION),
+                    written_bits,
+                )?;
+                labels_writer.append(thread_basename(job_id), labels)?;
+            }
+            if let Some(error) = error {
+                return Err(error);
+            }
+
+            log::info!("Flushing the merged bitstreams");
+            graph_writer.flush()?;
+            labels_writer.flush()?;
+
+            log::info!("Writing the .properties file");
+            let properties = compression_flags
+                .to_properties::<E>(num_nodes, total_arcs)
+                .context("Could not serialize properties")?;
+            let properties_path = basename.with_extension(PROPERTIES_EXTENSION);
+            std::fs::write(&properties_path, properties).with_context(|| {
+                format!(
+                    "Could not write properties to {}",
+                    properties_path.display()
+                )
+            })?;
+
+            log::info!(
+                "Compressed {} arcs into {} bits for {:.4} bits/arc, and {} bits of labels",
+                total_arcs,
+                total_written_bits,
+                total_written_bits as f64 / total_arcs as f64,
+                labels_writer.stats().labels_bits
+            );
+
+            // cleanup the temp files
+            std::fs::remove_dir_all(tmp_dir).with_context(|| {
+                format!("Could not clean temporary directory {}", tmp_dir.display())
+            })?;
+            Ok(total_written_bits)
+        })
+    }
 }
@@ -15,7 +15,7 @@ use std::{
     io::BufReader,
     path::{Path, PathBuf},
 };
-use sux::traits::IndexedDict;
+use sux::traits::{ConvertTo, IndexedDict};
 
 /// Sequential or random access.
 #[doc(hidden)]
@@ -203,6 +203,105 @@ impl LoadMode for LoadMmap {
     }
 }
 
+/// The graph is memory mapped; offsets are loaded from the `.ef` file if
+/// present, or built in memory otherwise.
+///
+/// If the `.ef` file is missing, an Elias–Fano representation of the offsets
+/// is built in memory from the `.offsets` file or, if the latter is missing
+/// too, by scanning the graph with an [`OffsetDegIter`]. In this way, a graph
+/// (e.g., a graph generated by the Java version) can be accessed randomly
+/// without building beforehand the `.ef` file. If `PERSIST` is true, the
+/// resulting Elias–Fano representation is stored in the `.ef` file, so that
+/// subsequent loads will not need to build it again.
+///
+/// Usually you will want to set this mode only for offsets using
+/// [`LoadConfig::offsets_mode`].
+#[derive(Debug, Clone)]
+pub struct BuildEf<const PERSIST: bool = false> {}
+#[sealed]
+impl<const PERSIST: bool> LoadMode for BuildEf<PERSIST> {
+    type Factory<E: Endianness> = MmapHelper<u32>;
+    type Offsets = EF;
+
+    fn new_factory<E: Endianness, P: AsRef<Path>>(
+        graph: P,
+        flags: MemoryFlags,
+    ) -> Result<Self::Factory<E>> {
+        MmapHelper::mmap(graph, flags.into())
+    }
+
+    fn load_offsets<P: AsRef<Path>>(
+        offsets: P,
+        _flags: MemoryFlags,
+    ) -> Result<MemCase<Self::Offsets>> {
+        let path = offsets.as_ref();
+        if path.exists() {
+            return Ok(EF::load_full(path)
+                .with_context(|| format!("Cannot load Elias-Fano pointer list {}", path.display()))?
+                .into());
+        }
+        // The endianness of the graph is read from its properties
+        let ef = match get_endianness(path)?.as_str() {
+            BE::NAME => build_offsets_ef::<BE>(path)?,
+            LE::NAME => build_offsets_ef::<LE>(path)?,
+            e => anyhow::bail!("Unknown endianness: {}", e),
+        };
+        if PERSIST {
+            ef.store(path).with_context(|| {
+                format!("Cannot store Elias-Fano pointer list {}", path.display())
+            })?;
+        }
+        Ok(ef.into())
+    }
+}
+
+/// Builds the Elias–Fano representation of the offsets of the graph with
+/// given basename, using the `.offsets` file if present, or scanning the
+/// graph otherwise.
+fn build_offsets_ef<E: Endianness>(basename: &Path) -> Result<EF>
+where
+    for<'a> BufBitReader<E, MemWordReader<u32, &'a [u32]>>: CodeRead<E> + BitSeek,
+{
+    let (num_nodes, _, _) = parse_properties::<E>(basename.with_extension(PROPERTIES_EXTENSION))?;
+    let graph_path = basename.with_extension(GRAPH_EXTENSION);
+    let upper_bound = 8 * graph_path
+        .metadata()
+        .with_context(|| format!("Cannot stat {}", graph_path.display()))?
+        .len() as usize;
+    let offsets_path = basename.with_extension(OFFSETS_EXTENSION);
+    if offsets_path.exists() {
+        log::info!(
+            "Building Elias-Fano pointer list from {}",
+            offsets_path.display()
+        );
+        let offsets = MmapHelper::<u32>::mmap(&offsets_path, MemoryFlags::empty().into())
+            .with_context(|| format!("Cannot mmap {}", offsets_path.display()))?;
+        return build_ef_from_reader(
+            BufBitReader::<E, _>::new(MemWordReader::new(offsets.as_ref())),
+            &offsets_path,
+            num_nodes,
+            upper_bound,
+        );
+    }
+
+    log::info!(
+        "Building Elias-Fano pointer list by scanning {}",
+        graph_path.display()
+    );
+    let seq_graph = BVGraphSeq::with_basename(basename)
+        .endianness::<E>()
+        .load()?;
+    let mut efb = sux::dict::EliasFanoBuilder::new(num_nodes + 1, upper_bound);
+    let mut iter = seq_graph.offset_deg_iter();
+    for (offset, _degree) in iter.by_ref() {
+        efb.push(offset as usize)
+            .with_context(|| format!("Cannot add offset {}", offset))?;
+    }
+    efb.push(iter.get_pos() as usize)
+        .context("Cannot add final offset")?;
+    efb.build().convert_to()
+}
+
 #[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct LoadConfig<E: Endianness, A: Access, D: Dispatch, GLM: LoadMode, OLM: LoadMode> {
@@ -518,6 +617,120 @@ impl<
     }
 }
 
+impl<E: Endianness, GLM: LoadMode, OLM: LoadMode> LoadConfig<E, Random, Dynamic, GLM, OLM> {
+    /// Load a random-access labeled graph with dynamic dispatch.
+    ///
+    /// The graph is loaded as in [`load`](LoadConfig::load), and it is
+    /// [zipped](Zip) with the labeling returned by [`load_labels`].
+    #[allow(clippy::type_complexity)]
+    pub fn load_labeled<D: BitDeserializer<E, MmapBitReader<E>>>(
+        self,
+        deserializer: D,
+    ) -> anyhow::Result<
+        Zip<
+            BVGraph<DynCodesDecoderFactory<E, GLM::Factory<E>, OLM::Offsets>>,
+            BitStreamLabeling<E, MmapBitReader<E>, D, EF>,
+        >,
+    >
+    where
+        for<'a> <<GLM as LoadMode>::Factory<E> as BitReaderFactory<E>>::BitReader<'a>:
+            CodeRead<E> + BitSeek,
+        MmapBitReader<E>: BitRead<E> + BitSeek,
+        BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: GammaRead<E>,
+    {
+        let flags = self.graph_load_flags;
+        let basename = self.basename.clone();
+        let graph = self.load()?;
+        let labels = load_labels(
+            basename,
+            graph.num_nodes(),
+            Some(graph.num_arcs()),
+            deserializer,
+            flags,
+        )?;
+        Ok(Zip(graph, labels))
+    }
+}
+
+/// Load a labeling written by a
+/// [`BitStreamLabelingWriter`](crate::labels::BitStreamLabelingWriter), such
+/// as the labels of a graph compressed with
+/// [`BVComp::single_thread_labeled`] or [`BVComp::parallel_labeled_iter`].
+///
+/// The `.labels` file is memory mapped using the given flags, whereas
+/// the `.labeloffsets` file is scanned to build in memory an Elias–Fano
+/// representation of the offsets.
+pub fn load_labels<E: Endianness, D: BitDeserializer<E, MmapBitReader<E>>>(
+    basename: impl AsRef<Path>,
+    num_nodes: usize,
+    num_arcs: Option<u64>,
+    deserializer: D,
+    flags: MemoryFlags,
+) -> Result<BitStreamLabeling<E, MmapBitReader<E>, D, EF>>
+where
+    MmapBitReader<E>: BitRead<E> + BitSeek,
+    BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: GammaRead<E>,
+{
+    let basename = basename.as_ref();
+    let labels_path = basename.with_extension(LABELS_EXTENSION);
+    let labels = MmapHelper::<u32>::mmap(&labels_path, flags.into())
+        .with_context(|| format!("Could not mmap {}", labels_path.display()))?;
+    let offsets = build_ef::<E>(
+        basename.with_extension(LABELOFFSETS_EXTENSION),
+        num_nodes,
+        8 * std::mem::size_of_val(labels.as_ref()),
+    )?;
+    Ok(BitStreamLabeling::new(
+        BufBitReader::<E, _>::new(MemWordReader::new(ArcMmapHelper(std::sync::Arc::new(
+            labels,
+        )))),
+        deserializer,
+        offsets.into(),
+        num_arcs,
+    ))
+}
+
+/// Builds in memory an Elias–Fano representation of the `num_nodes + 1`
+/// offsets stored in an offsets file as γ-coded differences.
+///
+/// `upper_bound` must be an upper bound on the offsets, such as the length in
+/// bits of the corresponding bitstream.
+pub(crate) fn build_ef<E: Endianness>(
+    offsets: impl AsRef<Path>,
+    num_nodes: usize,
+    upper_bound: usize,
+) -> Result<EF>
+where
+    BufBitReader<E, WordAdapter<u32, BufReader<std::fs::File>>>: GammaRead<E>,
+{
+    let path = offsets.as_ref();
+    let reader = BufBitReader::<E, _>::new(<WordAdapter<u32, _>>::new(BufReader::new(
+        std::fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?,
+    )));
+    build_ef_from_reader(reader, path, num_nodes, upper_bound)
+}
+
+/// Builds in memory an Elias–Fano representation of the `num_nodes + 1`
+/// offsets read as γ-coded differences from `reader`, which reads from the
+/// file at `path`.
+fn build_ef_from_reader<E: Endianness>(
+    mut reader: impl GammaRead<E>,
+    path: &Path,
+    num_nodes: usize,
+    upper_bound: usize,
+) -> Result<EF> {
+    let mut efb = sux::dict::EliasFanoBuilder::new(num_nodes + 1, upper_bound);
+    let mut offset = 0;
+    for _ in 0..num_nodes + 1 {
+        offset += reader
+            .read_gamma()
+            .with_context(|| format!("Cannot read offset from {}", path.display()))?;
+        efb.push(offset as usize)
+            .with_context(|| format!("Cannot add offset {} from {}", offset, path.display()))?;
+    }
+    efb.build().convert_to()
+}
+
 /// Read the .properties file and return the endianness
 pub fn get_endianness<P: AsRef<Path>>(basename: P) -> Result<String> {
     let path = basename.as_ref().with_extension(PROPERTIES_EXTENSION);
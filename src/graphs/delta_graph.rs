@@ -0,0 +1,492 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// The arcs added to and removed from the successors of a node, as sorted
+/// lists.
+///
+/// Added arcs are never arcs of the underlying graph, and removed arcs are
+/// always arcs of the underlying graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Delta {
+    added: Vec<usize>,
+    removed: Vec<usize>,
+}
+
+/// A mutable layer on top of an immutable random-access graph.
+///
+/// This structure keeps track in memory of arcs added to and removed from an
+/// underlying graph, and exhibits the modified graph as a
+/// [`RandomAccessGraph`]. Only nodes whose successors have been modified
+/// use memory, so this structure is suitable to apply small changes to a
+/// large (e.g., [`BVGraph`]) graph. Once the changes are complete, the
+/// modified graph can be written as a new [`BVGraph`] using
+/// [`compact`](DeltaGraph::compact).
+///
+/// Successors are returned in the same order as in the underlying graph,
+/// which must return them sorted.
+#[derive(Debug, Clone)]
+pub struct DeltaGraph<G: RandomAccessGraph> {
+    graph: G,
+    /// The modified nodes.
+    deltas: BTreeMap<usize, Delta>,
+    number_of_nodes: usize,
+    number_of_arcs: u64,
+}
+
+impl<G: RandomAccessGraph> DeltaGraph<G> {
+    /// Create a new overlay on the given graph, with no modifications.
+    pub fn new(graph: G) -> Self {
+        Self {
+            number_of_nodes: graph.num_nodes(),
+            number_of_arcs: graph.num_arcs(),
+            graph,
+            deltas: BTreeMap::new(),
+        }
+    }
+
+    /// Return a reference to the underlying graph.
+    pub fn graph(&self) -> &G {
+        &self.graph
+    }
+
+    /// Consume self and return the underlying graph, discarding the
+    /// modifications.
+    pub fn into_inner(self) -> G {
+        self.graph
+    }
+
+    /// Return the number of nodes whose successors have been modified.
+    pub fn num_modified_nodes(&self) -> usize {
+        self.deltas.len()
+    }
+
+    /// Add an isolated node to the graph and return true if is a new node.
+    ///
+    /// All nodes between the current number of nodes and `node` are added, too.
+    pub fn add_node(&mut self, node: usize) -> bool {
+        let result = node >= self.number_of_nodes;
+        self.number_of_nodes = self.number_of_nodes.max(node + 1);
+        result
+    }
+
+    /// Return whether the arc is in the underlying graph.
+    fn in_graph(&self, u: usize, v: usize) -> bool {
+        u < self.graph.num_nodes() && self.graph.has_arc(u, v)
+    }
+
+    fn check_nodes(&self, u: usize, v: usize) {
+        let max = u.max(v);
+        if max >= self.number_of_nodes {
+            panic!(
+                "Node {} does not exist (the graph has {} nodes)",
+                max, self.number_of_nodes,
+            );
+        }
+    }
+
+    /// Add an arc to the graph and return whether it is a new one.
+    pub fn add_arc(&mut self, u: usize, v: usize) -> bool {
+        self.check_nodes(u, v);
+        let in_graph = self.in_graph(u, v);
+        let delta = self.deltas.entry(u).or_default();
+        let result = if in_graph {
+            // The arc is new only if it was removed
+            match delta.removed.binary_search(&v) {
+                Ok(pos) => {
+                    delta.removed.remove(pos);
+                    true
+                }
+                Err(_) => false,
+            }
+        } else {
+            match delta.added.binary_search(&v) {
+                Ok(_) => false,
+                Err(pos) => {
+                    delta.added.insert(pos, v);
+                    true
+                }
+            }
+        };
+        if delta.added.is_empty() && delta.removed.is_empty() {
+            self.deltas.remove(&u);
+        }
+        self.number_of_arcs += result as u64;
+        result
+    }
+
+    /// Remove an arc from the graph and return whether it was present or not.
+    pub fn remove_arc(&mut self, u: usize, v: usize) -> bool {
+        self.check_nodes(u, v);
+        let in_graph = self.in_graph(u, v);
+        let delta = self.deltas.entry(u).or_default();
+        let result = if in_graph {
+            match delta.removed.binary_search(&v) {
+                Ok(_) => false,
+                Err(pos) => {
+                    delta.removed.insert(pos, v);
+                    true
+                }
+            }
+        } else {
+            // The arc is present only if it was added
+            match delta.added.binary_search(&v) {
+                Ok(pos) => {
+                    delta.added.remove(pos);
+                    true
+                }
+                Err(_) => false,
+            }
+        };
+        if delta.added.is_empty() && delta.removed.is_empty() {
+            self.deltas.remove(&u);
+        }
+        self.number_of_arcs -= result as u64;
+        result
+    }
+
+    /// Add arcs from an [`IntoIterator`].
+    ///
+    /// The items must be pairs of the form `(usize, usize)` specifying
+    /// an arc.
+    ///
+    /// Note that new nodes will be added as needed.
+    pub fn add_arc_list(&mut self, arcs: impl IntoIterator<Item = (usize, usize)>) {
+        for (u, v) in arcs {
+            self.add_node(u);
+            self.add_node(v);
+            self.add_arc(u, v);
+        }
+    }
+
+    /// Remove arcs from an [`IntoIterator`].
+    ///
+    /// The items must be pairs of the form `(usize, usize)` specifying
+    /// an arc. Arcs that are not present are ignored.
+    pub fn remove_arc_list(&mut self, arcs: impl IntoIterator<Item = (usize, usize)>) {
+        for (u, v) in arcs {
+            if u.max(v) < self.number_of_nodes {
+                self.remove_arc(u, v);
+            }
+        }
+    }
+
+    /// Compress the modified graph as a new [`BVGraph`] with given basename,
+    /// including its offsets, and return the length in bits of the graph
+    /// bitstream.
+    ///
+    /// The basename must be different from that of the underlying graph, if
+    /// the latter is a [`BVGraph`].
+    pub fn compact<E: Endianness>(
+        &self,
+        basename: impl AsRef<Path>,
+        compression_flags: CompFlags,
+    ) -> Result<u64>
+    where
+        BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    {
+        BVComp::single_thread::<E, _>(
+            basename,
+            self,
+            compression_flags,
+            true,
+            Some(self.number_of_nodes),
+        )
+    }
+}
+
+impl<G: RandomAccessGraph> SequentialLabeling for DeltaGraph<G> {
+    type Label = usize;
+    type Lender<'a> = Iter<'a, G::Lender<'a>> where Self: 'a;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.number_of_nodes
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        Some(self.number_of_arcs)
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        let base_num_nodes = self.graph.num_nodes();
+        Iter {
+            iter: self.graph.iter_from(from.min(base_num_nodes)),
+            deltas: &self.deltas,
+            node: from,
+            base_num_nodes,
+            num_nodes: self.number_of_nodes,
+        }
+    }
+}
+
+impl<G: RandomAccessGraph> SequentialGraph for DeltaGraph<G> {}
+
+impl<G: RandomAccessGraph> RandomAccessLabeling for DeltaGraph<G> {
+    type Labels<'a> = Succ<'a, <G::Labels<'a> as IntoIterator>::IntoIter> where Self: 'a;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.number_of_arcs
+    }
+
+    fn outdegree(&self, node: usize) -> usize {
+        let outdegree = if node < self.graph.num_nodes() {
+            self.graph.outdegree(node)
+        } else {
+            0
+        };
+        match self.deltas.get(&node) {
+            Some(delta) => outdegree + delta.added.len() - delta.removed.len(),
+            None => outdegree,
+        }
+    }
+
+    fn labels(&self, node: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        assert!(
+            node < self.number_of_nodes,
+            "Node {} does not exist (the graph has {} nodes)",
+            node,
+            self.number_of_nodes
+        );
+        let base = (node < self.graph.num_nodes()).then(|| self.graph.successors(node).into_iter());
+        Succ::new(base, self.deltas.get(&node))
+    }
+}
+
+impl<G: RandomAccessGraph> RandomAccessGraph for DeltaGraph<G> {
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        if let Some(delta) = self.deltas.get(&src_node_id) {
+            if delta.added.binary_search(&dst_node_id).is_ok() {
+                return true;
+            }
+            if delta.removed.binary_search(&dst_node_id).is_ok() {
+                return false;
+            }
+        }
+        self.in_graph(src_node_id, dst_node_id)
+    }
+}
+
+impl<G: RandomAccessGraph> SplitLabeling for DeltaGraph<G>
+where
+    for<'a> G::Lender<'a>: Send + Sync,
+{
+    type SplitLender<'a> = split::ra::Lender<'a, DeltaGraph<G>> where Self: 'a;
+    type IntoIterator<'a> = split::ra::IntoIterator<'a, DeltaGraph<G>> where Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
+impl<'a, G: RandomAccessGraph> IntoLender for &'a DeltaGraph<G> {
+    type Lender = <DeltaGraph<G> as SequentialLabeling>::Lender<'a>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// The lender of a [`DeltaGraph`], which merges the successors returned by
+/// the lender of the underlying graph with the modifications.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L> {
+    iter: L,
+    deltas: &'a BTreeMap<usize, Delta>,
+    node: usize,
+    base_num_nodes: usize,
+    num_nodes: usize,
+}
+
+impl<'a, 'succ, L> NodeLabelsLender<'succ> for Iter<'a, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    type Label = usize;
+    type IntoIterator = Succ<'succ, LenderIntoIter<'succ, L>>;
+}
+
+impl<'a, 'succ, L> Lending<'succ> for Iter<'a, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<'a, L> Lender for Iter<'a, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        if self.node >= self.num_nodes {
+            return None;
+        }
+        let node = self.node;
+        self.node += 1;
+        let deltas = self.deltas;
+        let base = if node < self.base_num_nodes {
+            Some(self.iter.next()?.1.into_iter())
+        } else {
+            None
+        };
+        Some((node, Succ::new(base, deltas.get(&node))))
+    }
+}
+
+impl<'a, L> ExactSizeLender for Iter<'a, L>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    fn len(&self) -> usize {
+        self.num_nodes - self.node
+    }
+}
+
+unsafe impl<'a, L> SortedLender for Iter<'a, L> where
+    L: SortedLender + for<'next> NodeLabelsLender<'next, Label = usize>
+{
+}
+
+/// An iterator over the successors of a node of a [`DeltaGraph`].
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Succ<'a, I: Iterator<Item = usize>> {
+    base: Option<core::iter::Peekable<I>>,
+    added: core::iter::Peekable<core::slice::Iter<'a, usize>>,
+    removed: core::iter::Peekable<core::slice::Iter<'a, usize>>,
+}
+
+impl<'a, I: Iterator<Item = usize>> Succ<'a, I> {
+    fn new(base: Option<I>, delta: Option<&'a Delta>) -> Self {
+        let (added, removed): (&[usize], &[usize]) = match delta {
+            Some(delta) => (&delta.added, &delta.removed),
+            None => (&[], &[]),
+        };
+        Self {
+            base: base.map(Iterator::peekable),
+            added: added.iter().peekable(),
+            removed: removed.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = usize>> Iterator for Succ<'a, I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next_base = self.base.as_mut().and_then(|iter| iter.peek().copied());
+            let next_added = self.added.peek().map(|&&x| x);
+            match (next_base, next_added) {
+                (None, None) => return None,
+                (Some(x), Some(y)) if y < x => return self.added.next().copied(),
+                (None, Some(_)) => return self.added.next().copied(),
+                (Some(x), _) => {
+                    self.base.as_mut().unwrap().next();
+                    // Removed arcs are arcs of the graph, so we can skip
+                    // them as we meet them
+                    if self.removed.peek() == Some(&&x) {
+                        self.removed.next();
+                    } else {
+                        return Some(x);
+                    }
+                }
+            }
+        }
+    }
+}
+
+unsafe impl<'a, I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<'a, I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+
+    #[test]
+    fn test_delta_graph() -> anyhow::Result<()> {
+        let arcs = [(0, 1), (0, 3), (1, 2), (2, 0), (2, 2), (2, 4), (3, 4)];
+        let mut delta = DeltaGraph::new(Left(VecGraph::from_arc_list(arcs)));
+        let mut expected = VecGraph::from_arc_list(arcs);
+
+        assert!(delta.add_arc(0, 2));
+        assert!(!delta.add_arc(0, 2));
+        assert!(!delta.add_arc(0, 1));
+        assert!(delta.remove_arc(2, 2));
+        assert!(!delta.remove_arc(2, 2));
+        assert!(!delta.remove_arc(3, 0));
+        assert!(delta.add_arc(2, 2));
+        assert!(delta.remove_arc(2, 0));
+        assert!(delta.remove_arc(0, 2));
+        assert!(delta.add_arc(0, 2));
+        delta.add_arc_list([(5, 1), (1, 0), (1, 4)]);
+        delta.remove_arc_list([(3, 4), (7, 0)]);
+
+        expected.add_arc_list([(0, 2), (5, 1), (1, 0), (1, 4)]);
+        expected.remove_arc(2, 0);
+        expected.remove_arc(3, 4);
+        let expected = Left(expected);
+
+        assert_eq!(delta.num_nodes(), 6);
+        assert_eq!(delta.num_arcs(), expected.num_arcs());
+        assert_eq!(delta.num_arcs_hint(), Some(expected.num_arcs()));
+        for node in 0..delta.num_nodes() {
+            let succ = expected.successors(node).into_iter().collect::<Vec<_>>();
+            assert_eq!(delta.successors(node).collect::<Vec<_>>(), succ);
+            assert_eq!(delta.outdegree(node), succ.len());
+            for succ in 0..delta.num_nodes() {
+                assert_eq!(delta.has_arc(node, succ), expected.has_arc(node, succ));
+            }
+        }
+        for from in 0..delta.num_nodes() {
+            let mut iter = delta.iter_from(from);
+            let mut node = from;
+            while let Some((x, s)) = iter.next() {
+                assert_eq!(x, node);
+                assert_eq!(
+                    s.collect::<Vec<_>>(),
+                    expected.successors(node).into_iter().collect::<Vec<_>>()
+                );
+                node += 1;
+            }
+            assert_eq!(node, delta.num_nodes());
+        }
+
+        // Only nodes with actual modifications are stored
+        assert_eq!(delta.num_modified_nodes(), 5);
+
+        let tmp = tempfile::TempDir::new()?;
+        let basename = tmp.path().join("delta");
+        delta.compact::<BE>(&basename, CompFlags::default())?;
+        let compacted = BVGraph::with_basename(&basename)
+            .endianness::<BE>()
+            .offsets_mode::<BuildEf>()
+            .load()?;
+        assert_eq!(compacted.num_nodes(), delta.num_nodes());
+        assert_eq!(compacted.num_arcs(), delta.num_arcs());
+        for node in 0..delta.num_nodes() {
+            assert_eq!(
+                compacted.successors(node).collect::<Vec<_>>(),
+                delta.successors(node).collect::<Vec<_>>()
+            );
+        }
+        Ok(())
+    }
+}
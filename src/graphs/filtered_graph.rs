@@ -0,0 +1,253 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use lender::*;
+
+#[derive(Debug, Clone)]
+/// A wrapper filtering the arcs of a labeling using a predicate.
+///
+/// The predicate is applied to the source of each arc and to the
+/// corresponding label returned by the underlying labeling, and the arc is
+/// kept only if the predicate returns true. For a
+/// [graph](SequentialGraph), the label is the target of the arc, whereas for
+/// a [labeled graph](LabeledSequentialGraph) it is a pair made of the target
+/// and the label of the arc. The number of nodes is unchanged.
+///
+/// For example, `FilteredGraph(graph, |src, &dst| src != dst)` removes loops
+/// from a graph, and `FilteredGraph(graph, |_, &(_, label)| label >= 10)`
+/// removes from a labeled graph the arcs with a label smaller than 10.
+///
+/// Sortedness of the underlying lender and of its iterators is preserved. If
+/// the underlying labeling implements [`SplitLabeling`], so does the
+/// filtered labeling, using the split lenders of the underlying labeling: in
+/// particular, the result of [`split_iter`](SplitLabeling::split_iter) can be
+/// passed to [`BVComp::parallel_iter`].
+pub struct FilteredGraph<G: SequentialLabeling, F: Fn(usize, &G::Label) -> bool>(pub G, pub F);
+
+impl<G: SequentialLabeling, F: Fn(usize, &G::Label) -> bool> SequentialLabeling
+    for FilteredGraph<G, F>
+{
+    type Label = G::Label;
+    type Lender<'b> = Iter<'b, G::Lender<'b>, F>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.0.num_nodes()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            iter: self.0.iter_from(from),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<G: SplitLabeling, F: Fn(usize, &G::Label) -> bool + Sync> SplitLabeling
+    for FilteredGraph<G, F>
+{
+    type SplitLender<'a> = Iter<'a, G::SplitLender<'a>, F>
+    where
+        Self: 'a;
+    type IntoIterator<'a> = SplitIter<'a, <G::IntoIterator<'a> as IntoIterator>::IntoIter, F>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        SplitIter {
+            iter: self.0.split_iter(how_many).into_iter(),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<G: SequentialGraph, F: Fn(usize, &usize) -> bool> SequentialGraph for FilteredGraph<G, F> {}
+
+impl<L, G: LabeledSequentialGraph<L>, F: Fn(usize, &(usize, L)) -> bool> LabeledSequentialGraph<L>
+    for FilteredGraph<G, F>
+{
+}
+
+impl<'c, G: SequentialLabeling, F: Fn(usize, &G::Label) -> bool> IntoLender
+    for &'c FilteredGraph<G, F>
+{
+    type Lender = <FilteredGraph<G, F> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator wrapping the split lenders of the underlying labeling.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct SplitIter<'a, I, F> {
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I: Iterator, F> Iterator for SplitIter<'a, I, F> {
+    type Item = Iter<'a, I::Item, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|iter| Iter {
+            iter,
+            filter: self.filter,
+        })
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Iter<'a, L, F> {
+    iter: L,
+    filter: &'a F,
+}
+
+impl<'a, L: Clone, F> Clone for Iter<'a, L, F> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            filter: self.filter,
+        }
+    }
+}
+
+impl<'a, 'succ, L, F> NodeLabelsLender<'succ> for Iter<'a, L, F>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next>,
+    F: for<'next> Fn(usize, &LenderLabel<'next, L>) -> bool,
+{
+    type Label = LenderLabel<'succ, L>;
+    type IntoIterator = Succ<'a, LenderIntoIter<'succ, L>, F>;
+}
+
+impl<'a, 'succ, L, F> Lending<'succ> for Iter<'a, L, F>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next>,
+    F: for<'next> Fn(usize, &LenderLabel<'next, L>) -> bool,
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<'a, L, F> Lender for Iter<'a, L, F>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next>,
+    F: for<'next> Fn(usize, &LenderLabel<'next, L>) -> bool,
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node, succ) = self.iter.next()?.into_pair();
+        Some((
+            node,
+            Succ {
+                iter: succ.into_iter(),
+                src: node,
+                filter: self.filter,
+            },
+        ))
+    }
+}
+
+unsafe impl<'a, L, F> SortedLender for Iter<'a, L, F>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next> + SortedLender,
+    F: for<'next> Fn(usize, &LenderLabel<'next, L>) -> bool,
+{
+}
+
+/// An iterator returning the labels of a node that satisfy a predicate.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Succ<'a, I, F> {
+    iter: I,
+    src: usize,
+    filter: &'a F,
+}
+
+impl<'a, I: Clone, F> Clone for Succ<'a, I, F> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            src: self.src,
+            filter: self.filter,
+        }
+    }
+}
+
+impl<'a, I: Iterator, F: Fn(usize, &I::Item) -> bool> Iterator for Succ<'a, I, F> {
+    type Item = I::Item;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let src = self.src;
+        self.iter.find(|label| (self.filter)(src, label))
+    }
+}
+
+unsafe impl<'a, I: SortedIterator, F: Fn(usize, &I::Item) -> bool> SortedIterator
+    for Succ<'a, I, F>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+
+    #[test]
+    fn test_filtered_graph() {
+        let g = Left(VecGraph::from_arc_list([
+            (0, 0),
+            (0, 1),
+            (0, 3),
+            (1, 2),
+            (2, 0),
+            (2, 2),
+            (3, 4),
+            (4, 4),
+        ]));
+        let filtered = FilteredGraph(g, |src, &dst| src != dst);
+        assert_eq!(filtered.num_nodes(), 5);
+        let expected = [vec![1, 3], vec![2], vec![0], vec![4], vec![]];
+        for from in 0..filtered.num_nodes() {
+            let mut iter = filtered.iter_from(from);
+            for (node, succ) in expected.iter().enumerate().skip(from) {
+                let Some((x, s)) = iter.next() else { panic!() };
+                assert_eq!(x, node);
+                assert_eq!(&s.collect::<Vec<_>>(), succ);
+            }
+            assert!(iter.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_filtered_labeled_graph() {
+        let g = VecGraph::from_labeled_arc_list([
+            (0, 1, 3_u64),
+            (0, 2, 10),
+            (1, 0, 12),
+            (1, 2, 1),
+            (2, 2, 15),
+        ]);
+        let filtered = FilteredGraph(g, |_, &(_, label)| label >= 10);
+        let mut arcs = vec![];
+        for_!((src, succ) in filtered.iter() {
+            arcs.extend(succ.map(|(dst, label)| (src, dst, label)));
+        });
+        assert_eq!(arcs, vec![(0, 2, 10), (1, 0, 12), (2, 2, 15)]);
+    }
+}
@@ -0,0 +1,462 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use lender::*;
+use std::collections::HashSet;
+use sux::traits::BitFieldSlice;
+
+#[derive(Debug)]
+/// A wrapper applying an arbitrary map to the nodes of an underlying graph.
+///
+/// Each node _x_ of the underlying graph is mapped to node `map[x]`, and each
+/// arc _x_ → _y_ becomes the arc `map[x]` → `map[y]`. Differently from a
+/// [`PermutedGraph`], the map needs not be injective: nodes with the same
+/// image are merged, and the successors of the resulting node are the union of
+/// the (mapped) successors of the merged nodes, without duplicates. In other
+/// words, this structure exhibits the quotient of the underlying graph by
+/// the map. Note that arcs between merged nodes become loops.
+///
+/// The map can be any [`BitFieldSlice`], such as a
+/// [`JavaPermutation`](crate::utils::JavaPermutation) or a vector loaded
+/// using ε-serde. A typical application is collapsing a graph of pages to
+/// the graph of the corresponding hosts.
+///
+/// The lender of the graph enumerates nodes in order, but successors are
+/// returned in the order of their first occurrence, unless the graph has been
+/// made [sorted](MappedGraph::sorted), in which case they are sorted. Since the
+/// preimage of a node must be known to compute its successors, the underlying
+/// graph must be [random-access](RandomAccessGraph). The inverse of the map is
+/// computed at construction time and uses two words per node.
+pub struct MappedGraph<
+    'a,
+    G: RandomAccessGraph,
+    M: BitFieldSlice<usize> + ?Sized,
+    const SORTED: bool = false,
+> {
+    graph: &'a G,
+    map: &'a M,
+    /// The preimage of node _x_ is `sources[offsets[x]..offsets[x + 1]]`.
+    offsets: Box<[usize]>,
+    sources: Box<[usize]>,
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool> Clone
+    for MappedGraph<'a, G, M, SORTED>
+{
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            map: self.map,
+            offsets: self.offsets.clone(),
+            sources: self.sources.clone(),
+        }
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized> MappedGraph<'a, G, M> {
+    /// Creates a new mapped graph with `num_nodes` nodes.
+    ///
+    /// An error is returned if the length of the map is not the number of
+    /// nodes of the graph, or if the map contains a node greater than or equal
+    /// to `num_nodes`.
+    pub fn new(graph: &'a G, map: &'a M, num_nodes: usize) -> Result<Self> {
+        ensure!(
+            map.len() == graph.num_nodes(),
+            "The given map has {} values and thus it's incompatible with a graph with {} nodes.",
+            map.len(),
+            graph.num_nodes(),
+        );
+        let mut offsets = vec![0; num_nodes + 1].into_boxed_slice();
+        for x in 0..map.len() {
+            let node = map.get(x);
+            ensure!(
+                node < num_nodes,
+                "Node {} is mapped to {}, but the mapped graph has {} nodes",
+                x,
+                node,
+                num_nodes
+            );
+            offsets[node + 1] += 1;
+        }
+        for node in 0..num_nodes {
+            offsets[node + 1] += offsets[node];
+        }
+        let mut pos = offsets[..num_nodes].to_vec();
+        let mut sources = vec![0; map.len()].into_boxed_slice();
+        for x in 0..map.len() {
+            let node = map.get(x);
+            sources[pos[node]] = x;
+            pos[node] += 1;
+        }
+        Ok(Self {
+            graph,
+            map,
+            offsets,
+            sources,
+        })
+    }
+
+    /// Returns a version of this graph whose successors are sorted.
+    pub fn sorted(self) -> MappedGraph<'a, G, M, true> {
+        MappedGraph {
+            graph: self.graph,
+            map: self.map,
+            offsets: self.offsets,
+            sources: self.sources,
+        }
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    MappedGraph<'a, G, M, SORTED>
+{
+    /// Returns the nodes of the underlying graph that are mapped to `node`,
+    /// in increasing order.
+    pub fn preimage(&self, node: usize) -> &[usize] {
+        &self.sources[self.offsets[node]..self.offsets[node + 1]]
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    SequentialLabeling for MappedGraph<'a, G, M, SORTED>
+{
+    type Label = usize;
+    type Lender<'b> = Iter<'b, G, M, SORTED>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            graph: self.graph,
+            map: self.map,
+            offsets: &self.offsets,
+            sources: &self.sources,
+            next: from,
+            succ: vec![],
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl<
+        'a,
+        G: RandomAccessGraph + Sync,
+        M: BitFieldSlice<usize> + Sync + ?Sized,
+        const SORTED: bool,
+    > SplitLabeling for MappedGraph<'a, G, M, SORTED>
+{
+    type SplitLender<'b> = split::ra::Lender<'b, MappedGraph<'a, G, M, SORTED>>
+    where
+        Self: 'b;
+    type IntoIterator<'b> = split::ra::IntoIterator<'b, MappedGraph<'a, G, M, SORTED>>
+    where
+        Self: 'b;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool> SequentialGraph
+    for MappedGraph<'a, G, M, SORTED>
+{
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    RandomAccessLabeling for MappedGraph<'a, G, M, SORTED>
+{
+    type Labels<'b> = Labels<SORTED>
+    where
+        Self: 'b;
+
+    /// Returns the number of arcs in the graph.
+    ///
+    /// This method enumerates all arcs, so it takes time linear in the
+    /// size of the underlying graph.
+    fn num_arcs(&self) -> u64 {
+        let mut num_arcs = 0;
+        let mut iter = self.iter();
+        while let Some((_, succ)) = iter.next() {
+            num_arcs += succ.into_iter().count() as u64;
+        }
+        num_arcs
+    }
+
+    /// Returns the successors of a node.
+    ///
+    /// This method enumerates the successors of the nodes in the preimage
+    /// of `node`, and allocates a vector to store the result.
+    fn labels(&self, node_id: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        let mut succ = vec![];
+        if SORTED {
+            for &x in self.preimage(node_id) {
+                succ.extend(
+                    self.graph
+                        .successors(x)
+                        .into_iter()
+                        .map(|y| self.map.get(y)),
+                );
+            }
+            succ.sort_unstable();
+            succ.dedup();
+        } else {
+            let mut seen = HashSet::new();
+            for &x in self.preimage(node_id) {
+                for y in self.graph.successors(x) {
+                    let y = self.map.get(y);
+                    if seen.insert(y) {
+                        succ.push(y);
+                    }
+                }
+            }
+        }
+        Labels(succ.into_iter())
+    }
+
+    /// Returns the outdegree of a node.
+    ///
+    /// This method enumerates the successors of the nodes in the preimage
+    /// of `node`.
+    fn outdegree(&self, node_id: usize) -> usize {
+        <Self as RandomAccessLabeling>::labels(self, node_id).len()
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    RandomAccessGraph for MappedGraph<'a, G, M, SORTED>
+{
+}
+
+impl<'a, 'b, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool> IntoLender
+    for &'b MappedGraph<'a, G, M, SORTED>
+{
+    type Lender = <MappedGraph<'a, G, M, SORTED> as SequentialLabeling>::Lender<'b>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+/// An iterator over the nodes of a mapped graph.
+///
+/// Successors are accumulated into a buffer; if successors must be sorted,
+/// duplicates are removed after sorting the buffer, whereas otherwise they are
+/// detected using a hash set, so the memory used is proportional to the
+/// number of successors of the current node.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Iter<'a, G, M: ?Sized, const SORTED: bool> {
+    graph: &'a G,
+    map: &'a M,
+    offsets: &'a [usize],
+    sources: &'a [usize],
+    next: usize,
+    succ: Vec<usize>,
+    seen: HashSet<usize>,
+}
+
+impl<'a, G, M: ?Sized, const SORTED: bool> Clone for Iter<'a, G, M, SORTED> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            map: self.map,
+            offsets: self.offsets,
+            sources: self.sources,
+            next: self.next,
+            succ: self.succ.clone(),
+            seen: self.seen.clone(),
+        }
+    }
+}
+
+impl<'a, 'succ, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    NodeLabelsLender<'succ> for Iter<'a, G, M, SORTED>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'succ, SORTED>;
+}
+
+impl<'a, 'succ, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    Lending<'succ> for Iter<'a, G, M, SORTED>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool> Lender
+    for Iter<'a, G, M, SORTED>
+{
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let node = self.next;
+        if node + 1 >= self.offsets.len() {
+            return None;
+        }
+        self.next += 1;
+        self.succ.clear();
+        let preimage = &self.sources[self.offsets[node]..self.offsets[node + 1]];
+        if SORTED {
+            for &x in preimage {
+                self.succ.extend(
+                    self.graph
+                        .successors(x)
+                        .into_iter()
+                        .map(|y| self.map.get(y)),
+                );
+            }
+            self.succ.sort_unstable();
+            self.succ.dedup();
+        } else {
+            self.seen.clear();
+            for &x in preimage {
+                for y in self.graph.successors(x) {
+                    let y = self.map.get(y);
+                    if self.seen.insert(y) {
+                        self.succ.push(y);
+                    }
+                }
+            }
+        }
+        Some((node, Succ(self.succ.iter().copied())))
+    }
+}
+
+impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool> ExactSizeLender
+    for Iter<'a, G, M, SORTED>
+{
+    fn len(&self) -> usize {
+        (self.offsets.len() - 1).saturating_sub(self.next)
+    }
+}
+
+unsafe impl<'a, G: RandomAccessGraph, M: BitFieldSlice<usize> + ?Sized, const SORTED: bool>
+    SortedLender for Iter<'a, G, M, SORTED>
+{
+}
+
+/// An iterator over the successors of a node of a mapped graph.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Succ<'a, const SORTED: bool>(core::iter::Copied<core::slice::Iter<'a, usize>>);
+
+impl<'a, const SORTED: bool> Iterator for Succ<'a, SORTED> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, const SORTED: bool> ExactSizeIterator for Succ<'a, SORTED> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+unsafe impl<'a> SortedIterator for Succ<'a, true> {}
+
+/// The successors of a node of a mapped graph returned by
+/// [`labels`](RandomAccessLabeling::labels).
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Labels<const SORTED: bool>(std::vec::IntoIter<usize>);
+
+impl<const SORTED: bool> Iterator for Labels<SORTED> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<const SORTED: bool> ExactSizeIterator for Labels<SORTED> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+unsafe impl SortedIterator for Labels<true> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+
+    #[test]
+    fn test_mapped_graph() -> anyhow::Result<()> {
+        let g = Left(VecGraph::from_arc_list([
+            (0, 4),
+            (0, 2),
+            (1, 0),
+            (1, 3),
+            (2, 3),
+            (3, 0),
+            (4, 1),
+            (4, 2),
+        ]));
+        // Merge 0 with 3, and 1 with 4; node 3 has an empty preimage
+        let map = [0, 1, 2, 0, 1];
+        let mapped = MappedGraph::new(&g, &map, 4)?;
+        assert_eq!(mapped.num_nodes(), 4);
+        assert_eq!(mapped.preimage(0), &[0, 3]);
+        assert_eq!(mapped.preimage(1), &[1, 4]);
+        assert!(mapped.preimage(3).is_empty());
+        let expected = [vec![2, 1, 0], vec![0, 1, 2], vec![0], vec![]];
+        let mut iter = mapped.iter();
+        for (node, succ) in expected.iter().enumerate() {
+            let (x, s) = iter.next().unwrap();
+            assert_eq!(x, node);
+            assert_eq!(&s.collect::<Vec<_>>(), succ);
+        }
+        assert!(iter.next().is_none());
+        for (node, succ) in expected.iter().enumerate() {
+            assert_eq!(&mapped.successors(node).collect::<Vec<_>>(), succ);
+        }
+        assert_eq!(mapped.num_arcs(), 7);
+
+        let sorted = mapped.sorted();
+        fn assert_sorted<I: SortedIterator>(iter: I) -> I {
+            iter
+        }
+        for_!((node, succ) in sorted.iter() {
+            let mut expected = expected[node].clone();
+            expected.sort();
+            assert_eq!(succ.collect::<Vec<_>>(), expected);
+            assert_eq!(
+                assert_sorted(sorted.successors(node)).collect::<Vec<_>>(),
+                expected
+            );
+        });
+
+        assert!(MappedGraph::new(&g, &[0, 1, 2, 0], 4).is_err());
+        assert!(MappedGraph::new(&g, &[0, 1, 2, 0, 4], 4).is_err());
+        Ok(())
+    }
+}
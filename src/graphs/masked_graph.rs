@@ -0,0 +1,273 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use core::ops::Index;
+use lender::*;
+
+#[derive(Debug, Clone)]
+/// A wrapper exhibiting the subgraph of a graph induced by a set of nodes,
+/// without renumbering.
+///
+/// The set of nodes is represented by a mask, that is, any structure
+/// indexable by nodes returning a boolean, such as a
+/// [`BitVec`](sux::bits::BitVec) or a slice of booleans. The number of nodes
+/// is the same of the underlying graph, so that node identifiers are
+/// preserved; nodes that are not in the mask have no successors, and
+/// successors that are not in the mask are skipped.
+///
+/// If the graph is [random-access](RandomAccessGraph), so is the masked
+/// graph; note, however, that the outdegree of a node is computed by
+/// enumerating its successors, and the number of arcs by enumerating all
+/// arcs. To renumber compactly the nodes of the subgraph, use
+/// [`subgraph`](crate::transform::subgraph).
+pub struct MaskedGraph<G: SequentialGraph, M: Index<usize, Output = bool>>(pub G, pub M);
+
+impl<G: SequentialGraph, M: Index<usize, Output = bool>> SequentialLabeling for MaskedGraph<G, M> {
+    type Label = usize;
+    type Lender<'b> = Iter<'b, G::Lender<'b>, M>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.0.num_nodes()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            iter: self.0.iter_from(from),
+            mask: &self.1,
+        }
+    }
+}
+
+impl<G: SequentialGraph, M: Index<usize, Output = bool> + Sync> SplitLabeling for MaskedGraph<G, M>
+where
+    for<'a> G::Lender<'a>: Clone + ExactSizeLender + Send + Sync,
+{
+    type SplitLender<'a> = split::seq::Lender<'a, MaskedGraph<G, M>>
+    where
+        Self: 'a;
+    type IntoIterator<'a> = split::seq::IntoIterator<'a, MaskedGraph<G, M>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), how_many)
+    }
+}
+
+impl<G: SequentialGraph, M: Index<usize, Output = bool>> SequentialGraph for MaskedGraph<G, M> {}
+
+impl<G: RandomAccessGraph, M: Index<usize, Output = bool>> RandomAccessLabeling
+    for MaskedGraph<G, M>
+{
+    type Labels<'a> = Succ<'a, <<G as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter, M>
+    where
+        Self: 'a;
+
+    /// Returns the number of arcs in the graph.
+    ///
+    /// This method enumerates all arcs, so it takes time linear in the
+    /// size of the graph.
+    fn num_arcs(&self) -> u64 {
+        let mut num_arcs = 0;
+        let mut iter = self.iter();
+        while let Some((_, succ)) = iter.next() {
+            num_arcs += succ.into_iter().count() as u64;
+        }
+        num_arcs
+    }
+
+    fn labels(&self, node_id: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        Succ {
+            iter: self.1[node_id].then(|| self.0.successors(node_id).into_iter()),
+            mask: &self.1,
+        }
+    }
+
+    /// Returns the outdegree of a node.
+    ///
+    /// This method enumerates the successors of the node.
+    fn outdegree(&self, node_id: usize) -> usize {
+        <Self as RandomAccessLabeling>::labels(self, node_id).count()
+    }
+}
+
+impl<G: RandomAccessGraph, M: Index<usize, Output = bool>> RandomAccessGraph for MaskedGraph<G, M> {
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        self.1[src_node_id] && self.1[dst_node_id] && self.0.has_arc(src_node_id, dst_node_id)
+    }
+}
+
+impl<'c, G: SequentialGraph, M: Index<usize, Output = bool>> IntoLender for &'c MaskedGraph<G, M> {
+    type Lender = <MaskedGraph<G, M> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Iter<'a, L, M> {
+    iter: L,
+    mask: &'a M,
+}
+
+impl<'a, L: Clone, M> Clone for Iter<'a, L, M> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            mask: self.mask,
+        }
+    }
+}
+
+impl<
+        'a,
+        'succ,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Index<usize, Output = bool>,
+    > NodeLabelsLender<'succ> for Iter<'a, L, M>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'a, LenderIntoIter<'succ, L>, M>;
+}
+
+impl<
+        'a,
+        'succ,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Index<usize, Output = bool>,
+    > Lending<'succ> for Iter<'a, L, M>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<
+        'a,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Index<usize, Output = bool>,
+    > Lender for Iter<'a, L, M>
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node, succ) = self.iter.next()?.into_pair();
+        Some((
+            node,
+            Succ {
+                iter: self.mask[node].then(|| succ.into_iter()),
+                mask: self.mask,
+            },
+        ))
+    }
+}
+
+impl<
+        'a,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + ExactSizeLender,
+        M: Index<usize, Output = bool>,
+    > ExactSizeLender for Iter<'a, L, M>
+{
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+unsafe impl<
+        'a,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + SortedLender,
+        M: Index<usize, Output = bool>,
+    > SortedLender for Iter<'a, L, M>
+{
+}
+
+/// An iterator returning the successors of a node that are in a mask.
+///
+/// If the iterator is missing (i.e., the node is not in the mask), the
+/// iterator is empty.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Succ<'a, I: Iterator<Item = usize>, M> {
+    iter: Option<I>,
+    mask: &'a M,
+}
+
+impl<'a, I: Iterator<Item = usize> + Clone, M> Clone for Succ<'a, I, M> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            mask: self.mask,
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = usize>, M: Index<usize, Output = bool>> Iterator for Succ<'a, I, M> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.as_mut()?.find(|&succ| self.mask[succ])
+    }
+}
+
+unsafe impl<'a, I: Iterator<Item = usize> + SortedIterator, M: Index<usize, Output = bool>>
+    SortedIterator for Succ<'a, I, M>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+
+    #[test]
+    fn test_masked_graph() -> anyhow::Result<()> {
+        let g = Left(VecGraph::from_arc_list([
+            (0, 1),
+            (0, 3),
+            (1, 2),
+            (2, 0),
+            (2, 2),
+            (2, 4),
+            (3, 4),
+            (4, 1),
+        ]));
+        let mask = [true, false, true, true, true];
+        let masked = MaskedGraph(g, mask);
+        let expected = [vec![3], vec![], vec![0, 2, 4], vec![4], vec![]];
+        assert_eq!(masked.num_nodes(), 5);
+        assert_eq!(masked.num_arcs(), 5);
+
+        for from in 0..masked.num_nodes() {
+            let mut iter = masked.iter_from(from);
+            for (node, succ) in expected.iter().enumerate().skip(from) {
+                let Some((x, s)) = iter.next() else { panic!() };
+                assert_eq!(x, node);
+                assert_eq!(&s.collect::<Vec<_>>(), succ);
+            }
+            assert!(iter.next().is_none());
+        }
+
+        for (node, succ) in expected.iter().enumerate() {
+            assert_eq!(&masked.successors(node).collect::<Vec<_>>(), succ);
+            assert_eq!(masked.outdegree(node), succ.len());
+            for dst in 0..masked.num_nodes() {
+                assert_eq!(masked.has_arc(node, dst), succ.contains(&dst));
+            }
+        }
+        Ok(())
+    }
+}
@@ -9,6 +9,19 @@ pub mod arc_list_graph;
 
 pub mod bvgraph;
 pub use bvgraph::*;
+
+mod delta_graph;
+pub use delta_graph::DeltaGraph;
+
+mod filtered_graph;
+pub use filtered_graph::FilteredGraph;
+
+mod mapped_graph;
+pub use mapped_graph::MappedGraph;
+
+mod masked_graph;
+pub use masked_graph::MaskedGraph;
+
 pub mod permuted_graph;
 
 mod union_graph;
@@ -16,6 +29,12 @@ pub use union_graph::UnionGraph;
 
 pub mod random;
 
+mod set_op_graph;
+pub use set_op_graph::{
+    Difference, DifferenceGraph, Intersection, IntersectionGraph, SetOp, SetOpGraph,
+    SymmetricDifference, SymmetricDifferenceGraph,
+};
+
 pub mod vec_graph;
 pub mod prelude {
     pub use super::bvgraph::*;
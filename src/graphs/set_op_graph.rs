@@ -0,0 +1,499 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use core::marker::PhantomData;
+use lender::*;
+
+/// A set operation on sorted sequences of successors.
+///
+/// The operation is described by which elements of the merge of two sorted
+/// sequences are returned: those appearing only in the first sequence, those
+/// appearing only in the second sequence, and those appearing in both.
+pub trait SetOp: Clone + Copy + Send + Sync {
+    /// Whether elements appearing only in the first sequence are returned.
+    const FIRST: bool;
+    /// Whether elements appearing only in the second sequence are returned.
+    const SECOND: bool;
+    /// Whether elements appearing in both sequences are returned.
+    const BOTH: bool;
+}
+
+/// The intersection of two graphs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Intersection;
+
+impl SetOp for Intersection {
+    const FIRST: bool = false;
+    const SECOND: bool = false;
+    const BOTH: bool = true;
+}
+
+/// The difference of two graphs, that is, the arcs of the first graph that
+/// are not arcs of the second graph.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Difference;
+
+impl SetOp for Difference {
+    const FIRST: bool = true;
+    const SECOND: bool = false;
+    const BOTH: bool = false;
+}
+
+/// The symmetric difference of two graphs, that is, the arcs that are in
+/// exactly one of the two graphs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SymmetricDifference;
+
+impl SetOp for SymmetricDifference {
+    const FIRST: bool = true;
+    const SECOND: bool = true;
+    const BOTH: bool = false;
+}
+
+/// A wrapper exhibiting the result of a [set operation](SetOp) on the arcs
+/// of two graphs with sorted successors, such as their
+/// [intersection](IntersectionGraph), their [difference](DifferenceGraph), or
+/// their [symmetric difference](SymmetricDifferenceGraph).
+///
+/// The successors of each node are computed by merging the successors of the
+/// node in the two graphs. As in the case of [`UnionGraph`](super::UnionGraph),
+/// the number of nodes is the maximum of the number of nodes of the two
+/// graphs, so that node identifiers are preserved, except for operations
+/// returning only arcs of the first graph and no arc in both graphs (i.e., the
+/// difference), in which case it is the number of nodes of the first graph.
+///
+/// If both graphs are [random-access](RandomAccessGraph), so is the result;
+/// note, however, that the outdegree of a node is computed by enumerating its
+/// successors, and the number of arcs by enumerating all arcs.
+#[derive(Debug, Clone)]
+pub struct SetOpGraph<G: SequentialGraph, H: SequentialGraph, O: SetOp>(pub G, pub H, pub O);
+
+/// A wrapper exhibiting the intersection of two graphs.
+pub type IntersectionGraph<G, H> = SetOpGraph<G, H, Intersection>;
+
+/// A wrapper exhibiting the difference of two graphs, that is, the arcs of
+/// the first graph that are not arcs of the second graph.
+pub type DifferenceGraph<G, H> = SetOpGraph<G, H, Difference>;
+
+/// A wrapper exhibiting the symmetric difference of two graphs, that is, the
+/// arcs that are in exactly one of the two graphs.
+pub type SymmetricDifferenceGraph<G, H> = SetOpGraph<G, H, SymmetricDifference>;
+
+/// Returns true if the nodes of an operation are those of the first graph.
+const fn first_only<O: SetOp>() -> bool {
+    O::FIRST && !O::SECOND && !O::BOTH
+}
+
+impl<G: SequentialGraph, H: SequentialGraph, O: SetOp> SequentialLabeling for SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+    type Label = usize;
+    type Lender<'b>
+        = Iter<G::Lender<'b>, H::Lender<'b>, O>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        if first_only::<O>() {
+            self.0.num_nodes()
+        } else {
+            self.0.num_nodes().max(self.1.num_nodes())
+        }
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    #[inline(always)]
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter(
+            self.0.iter_from(from.min(self.0.num_nodes())),
+            self.1.iter_from(from.min(self.1.num_nodes())),
+            PhantomData,
+        )
+    }
+}
+
+impl<G: SequentialGraph, H: SequentialGraph, O: SetOp> SplitLabeling for SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender + Clone + ExactSizeLender + Send + Sync,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender + Clone + ExactSizeLender + Send + Sync,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+    type SplitLender<'a>
+        = split::seq::Lender<'a, SetOpGraph<G, H, O>>
+    where
+        Self: 'a;
+    type IntoIterator<'a>
+        = split::seq::IntoIterator<'a, SetOpGraph<G, H, O>>
+    where
+        Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), how_many)
+    }
+}
+
+impl<G: SequentialGraph, H: SequentialGraph, O: SetOp> SequentialGraph for SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+}
+
+impl<G: RandomAccessGraph, H: RandomAccessGraph, O: SetOp> RandomAccessLabeling
+    for SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+    type Labels<'a>
+        = Succ<
+        <<G as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter,
+        <<H as RandomAccessLabeling>::Labels<'a> as IntoIterator>::IntoIter,
+        O,
+    >
+    where
+        Self: 'a;
+
+    /// Returns the number of arcs in the graph.
+    ///
+    /// This method enumerates all arcs, so it takes time linear in the
+    /// size of the two graphs.
+    fn num_arcs(&self) -> u64 {
+        let mut num_arcs = 0;
+        let mut iter = self.iter();
+        while let Some((_, succ)) = iter.next() {
+            num_arcs += succ.into_iter().count() as u64;
+        }
+        num_arcs
+    }
+
+    fn labels(&self, node_id: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        Succ::new(
+            (node_id < self.0.num_nodes()).then(|| self.0.successors(node_id).into_iter()),
+            (node_id < self.1.num_nodes()).then(|| self.1.successors(node_id).into_iter()),
+        )
+    }
+
+    /// Returns the outdegree of a node.
+    ///
+    /// This method enumerates the successors of the node.
+    fn outdegree(&self, node_id: usize) -> usize {
+        <Self as RandomAccessLabeling>::labels(self, node_id).count()
+    }
+}
+
+impl<G: RandomAccessGraph, H: RandomAccessGraph, O: SetOp> RandomAccessGraph for SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        let in0 = src_node_id < self.0.num_nodes() && self.0.has_arc(src_node_id, dst_node_id);
+        let in1 = src_node_id < self.1.num_nodes() && self.1.has_arc(src_node_id, dst_node_id);
+        match (in0, in1) {
+            (true, true) => O::BOTH,
+            (true, false) => O::FIRST,
+            (false, true) => O::SECOND,
+            (false, false) => false,
+        }
+    }
+}
+
+impl<'c, G: SequentialGraph, H: SequentialGraph, O: SetOp> IntoLender for &'c SetOpGraph<G, H, O>
+where
+    for<'a> G::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, G::Lender<'a>>: SortedIterator,
+    for<'a> H::Lender<'a>: SortedLender,
+    for<'a, 'b> LenderIntoIter<'b, H::Lender<'a>>: SortedIterator,
+{
+    type Lender = <SetOpGraph<G, H, O> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Iter<L, M, O>(L, M, PhantomData<O>);
+
+impl<
+        'succ,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        O: SetOp,
+    > NodeLabelsLender<'succ> for Iter<L, M, O>
+{
+    type Label = usize;
+    type IntoIterator = Succ<LenderIntoIter<'succ, L>, LenderIntoIter<'succ, M>, O>;
+}
+
+impl<
+        'succ,
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        O: SetOp,
+    > Lending<'succ> for Iter<L, M, O>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        M: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+        O: SetOp,
+    > Lender for Iter<L, M, O>
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let (node0, iter0) = self.0.next().unzip();
+        let (node1, iter1) = self.1.next().unzip();
+        let node = if first_only::<O>() {
+            node0?
+        } else {
+            node0.or(node1)?
+        };
+        Some((
+            node,
+            Succ::new(
+                iter0.map(IntoIterator::into_iter),
+                iter1.map(IntoIterator::into_iter),
+            ),
+        ))
+    }
+}
+
+impl<
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + ExactSizeLender,
+        M: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + ExactSizeLender,
+        O: SetOp,
+    > ExactSizeLender for Iter<L, M, O>
+{
+    fn len(&self) -> usize {
+        if first_only::<O>() {
+            self.0.len()
+        } else {
+            self.0.len().max(self.1.len())
+        }
+    }
+}
+
+unsafe impl<
+        L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + SortedLender,
+        M: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + SortedLender,
+        O: SetOp,
+    > SortedLender for Iter<L, M, O>
+{
+}
+
+/// An iterator merging two optional sorted iterators and returning the
+/// elements selected by a [set operation](SetOp).
+///
+/// A missing iterator behaves as an empty one.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Succ<I: Iterator<Item = usize>, J: Iterator<Item = usize>, O> {
+    iter0: Option<core::iter::Peekable<I>>,
+    iter1: Option<core::iter::Peekable<J>>,
+    _marker: PhantomData<O>,
+}
+
+impl<I: Iterator<Item = usize>, J: Iterator<Item = usize>, O: SetOp> Succ<I, J, O> {
+    pub fn new(iter0: Option<I>, iter1: Option<J>) -> Self {
+        Self {
+            iter0: iter0.map(Iterator::peekable),
+            iter1: iter1.map(Iterator::peekable),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<I: Iterator<Item = usize>, J: Iterator<Item = usize>, O: SetOp> Iterator for Succ<I, J, O> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next0 = self.iter0.as_mut().and_then(|iter| iter.peek().copied());
+            let next1 = self.iter1.as_mut().and_then(|iter| iter.peek().copied());
+            match (next0, next1) {
+                (None, None) => return None,
+                (Some(x), None) => {
+                    // Only elements of the first iterator are left
+                    if !O::FIRST {
+                        return None;
+                    }
+                    self.iter0.as_mut().and_then(Iterator::next);
+                    return Some(x);
+                }
+                (None, Some(y)) => {
+                    // Only elements of the second iterator are left
+                    if !O::SECOND {
+                        return None;
+                    }
+                    self.iter1.as_mut().and_then(Iterator::next);
+                    return Some(y);
+                }
+                (Some(x), Some(y)) => match x.cmp(&y) {
+                    std::cmp::Ordering::Less => {
+                        self.iter0.as_mut().and_then(Iterator::next);
+                        if O::FIRST {
+                            return Some(x);
+                        }
+                    }
+                    std::cmp::Ordering::Greater => {
+                        self.iter1.as_mut().and_then(Iterator::next);
+                        if O::SECOND {
+                            return Some(y);
+                        }
+                    }
+                    std::cmp::Ordering::Equal => {
+                        self.iter0.as_mut().and_then(Iterator::next);
+                        self.iter1.as_mut().and_then(Iterator::next);
+                        if O::BOTH {
+                            return Some(x);
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+unsafe impl<
+        I: Iterator<Item = usize> + SortedIterator,
+        J: Iterator<Item = usize> + SortedIterator,
+        O: SetOp,
+    > SortedIterator for Succ<I, J, O>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::vec_graph::VecGraph, prelude::proj::Left};
+
+    /// Checks sequential and random access to a graph against the expected
+    /// successors.
+    fn check<G: RandomAccessGraph>(graph: &G, expected: &[Vec<usize>]) {
+        assert_eq!(graph.num_nodes(), expected.len());
+        assert_eq!(
+            graph.num_arcs(),
+            expected.iter().map(Vec::len).sum::<usize>() as u64
+        );
+
+        for from in 0..graph.num_nodes() {
+            let mut iter = graph.iter_from(from);
+            for (node, succ) in expected.iter().enumerate().skip(from) {
+                let Some((x, s)) = iter.next() else { panic!() };
+                assert_eq!(x, node);
+                assert_eq!(&s.into_iter().collect::<Vec<_>>(), succ);
+            }
+            assert!(iter.next().is_none());
+        }
+
+        for (node, succ) in expected.iter().enumerate() {
+            assert_eq!(
+                &graph.successors(node).into_iter().collect::<Vec<_>>(),
+                succ
+            );
+            assert_eq!(graph.outdegree(node), succ.len());
+            for dst in 0..graph.num_nodes() {
+                assert_eq!(graph.has_arc(node, dst), succ.contains(&dst));
+            }
+        }
+    }
+
+    fn graphs() -> [Left<VecGraph>; 2] {
+        [
+            Left(VecGraph::from_arc_list([
+                (0, 1),
+                (0, 3),
+                (1, 2),
+                (2, 0),
+                (2, 2),
+                (2, 4),
+                (3, 4),
+                (3, 5),
+                (4, 1),
+            ])),
+            Left(VecGraph::from_arc_list([
+                (0, 3),
+                (1, 2),
+                (1, 3),
+                (2, 1),
+                (2, 2),
+                (2, 4),
+                (4, 0),
+                (5, 1),
+                (6, 6),
+            ])),
+        ]
+    }
+
+    #[test]
+    fn test_intersection_graph() {
+        let g = graphs();
+        let expected = [vec![3], vec![2], vec![2, 4], vec![], vec![], vec![], vec![]];
+        for i in 0..2 {
+            check(
+                &SetOpGraph(g[i].clone(), g[1 - i].clone(), Intersection),
+                &expected,
+            );
+        }
+    }
+
+    #[test]
+    fn test_difference_graph() {
+        let g = graphs();
+        check(
+            &SetOpGraph(g[0].clone(), g[1].clone(), Difference),
+            &[vec![1], vec![], vec![0], vec![4, 5], vec![1], vec![]],
+        );
+        check(
+            &SetOpGraph(g[1].clone(), g[0].clone(), Difference),
+            &[vec![], vec![3], vec![1], vec![], vec![0], vec![1], vec![6]],
+        );
+    }
+
+    #[test]
+    fn test_symmetric_difference_graph() {
+        let g = graphs();
+        let expected = [
+            vec![1],
+            vec![3],
+            vec![0, 1],
+            vec![4, 5],
+            vec![0, 1],
+            vec![1],
+            vec![6],
+        ];
+        for i in 0..2 {
+            check(
+                &SetOpGraph(g[i].clone(), g[1 - i].clone(), SymmetricDifference),
+                &expected,
+            );
+        }
+    }
+}
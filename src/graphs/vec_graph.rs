@@ -278,6 +278,15 @@ impl<L: Copy + 'static> RandomAccessLabeling for VecGraph<L> {
 
 impl<L: Copy + 'static> LabeledRandomAccessGraph<L> for VecGraph<L> {}
 
+impl<L: Copy + Send + Sync + 'static> SplitLabeling for VecGraph<L> {
+    type SplitLender<'a> = split::ra::Lender<'a, VecGraph<L>> where Self: 'a;
+    type IntoIterator<'a> = split::ra::IntoIterator<'a, VecGraph<L>> where Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
 #[doc(hidden)]
 #[repr(transparent)]
 pub struct Successors<'a, L: Copy + 'static>(std::collections::btree_set::Iter<'a, Successor<L>>);
@@ -0,0 +1,417 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+/*!
+
+Labelings stored as a bitstream of serialized labels.
+
+The labels of the arcs of each node are serialized one after the other, in
+the order of the successors, using a [`BitDeserializer`]-compatible
+[`BitSerializer`]. A list of bit offsets (one per node, plus a final one)
+makes it possible to locate the labels of each node.
+
+A [`BitStreamLabelingWriter`] writes in a single pass a `.labels` file
+containing the serialized labels and a `.labeloffsets` file containing the
+[γ-coded](dsi_bitstream::codes::gamma) differences between consecutive
+offsets (the first difference is the first offset, that is, zero). The
+resulting labeling can be loaded with
+[`load_labels`](crate::graphs::bvgraph::load_labels).
+
+*/
+
+use crate::prelude::*;
+use anyhow::Context;
+use dsi_bitstream::prelude::*;
+use epserde::prelude::*;
+use lender::{Lend, Lender, Lending};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::marker::PhantomData;
+use std::path::Path;
+use sux::traits::IndexedDict;
+
+/// The bit reader used by [`load_labels`](crate::graphs::bvgraph::load_labels)
+/// on memory-mapped label files.
+pub type MmapBitReader<E> = BufBitReader<E, MemWordReader<u32, ArcMmapHelper<u32>>>;
+
+/// The bit writer used by [`BitStreamLabelingWriter`].
+pub type FileBitWriter<E> = BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>;
+
+/// A labeling whose labels are stored in a bitstream.
+///
+/// The bit reader `BR` is cloned each time a new iterator is created, so it
+/// should be cheap to clone (e.g., a reader on [memory-mapped
+/// data](crate::utils::ArcMmapHelper)). The offsets `O` must contain
+/// `num_nodes + 1` bit positions.
+///
+/// Since the end of the labels of a node is detected using the offsets,
+/// labels must be serialized using at least one bit, or the number of labels
+/// of each node will appear to be zero. This is not a problem when the
+/// labeling is [zipped](Zip) with a graph, as in that case the number of
+/// labels is given by the outdegree.
+pub struct BitStreamLabeling<E: Endianness, BR, D, O> {
+    reader: BR,
+    deserializer: D,
+    offsets: MemCase<O>,
+    num_arcs: Option<u64>,
+    _marker: PhantomData<E>,
+}
+
+impl<E: Endianness, BR, D, O> BitStreamLabeling<E, BR, D, O> {
+    /// Creates a new labeling.
+    ///
+    /// # Arguments
+    /// - `reader`: a bit reader on the labels bitstream; it will be cloned
+    ///   for each iterator.
+    /// - `deserializer`: the deserializer for the labels.
+    /// - `offsets`: the bit offsets of the labels of each node, plus a final
+    ///   offset.
+    /// - `num_arcs`: the number of labels in the labeling, if known; if it is
+    ///   not known, [`num_arcs`](RandomAccessLabeling::num_arcs) will panic.
+    pub fn new(reader: BR, deserializer: D, offsets: MemCase<O>, num_arcs: Option<u64>) -> Self {
+        Self {
+            reader,
+            deserializer,
+            offsets,
+            num_arcs,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The lender returned by [`BitStreamLabeling`].
+pub struct Iter<'a, E, BR, D, O> {
+    reader: BR,
+    deserializer: &'a D,
+    offsets: &'a MemCase<O>,
+    next_node: usize,
+    num_nodes: usize,
+    _marker: PhantomData<E>,
+}
+
+impl<
+        'a,
+        'succ,
+        E: Endianness,
+        BR: BitRead<E> + BitSeek,
+        D: BitDeserializer<E, BR>,
+        O: IndexedDict<Input = usize, Output = usize>,
+    > NodeLabelsLender<'succ> for Iter<'a, E, BR, D, O>
+{
+    type Label = D::DeserType;
+    type IntoIterator = SeqLabels<'succ, E, BR, D>;
+}
+
+impl<
+        'a,
+        'succ,
+        E: Endianness,
+        BR: BitRead<E> + BitSeek,
+        D: BitDeserializer<E, BR>,
+        O: IndexedDict<Input = usize, Output = usize>,
+    > Lending<'succ> for Iter<'a, E, BR, D, O>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<
+        'a,
+        E: Endianness,
+        BR: BitRead<E> + BitSeek,
+        D: BitDeserializer<E, BR>,
+        O: IndexedDict<Input = usize, Output = usize>,
+    > Lender for Iter<'a, E, BR, D, O>
+{
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        if self.next_node >= self.num_nodes {
+            return None;
+        }
+        // We reposition the reader, as the previous iterator
+        // might not have been fully consumed
+        self.reader
+            .set_bit_pos(self.offsets.get(self.next_node) as u64)
+            .unwrap();
+        let res = (
+            self.next_node,
+            SeqLabels {
+                reader: &mut self.reader,
+                deserializer: self.deserializer,
+                end_pos: self.offsets.get(self.next_node + 1) as u64,
+                _marker: PhantomData,
+            },
+        );
+        self.next_node += 1;
+        Some(res)
+    }
+}
+
+/// The iterator on the labels of a node returned by [`Iter`].
+pub struct SeqLabels<'a, E, BR, D> {
+    reader: &'a mut BR,
+    deserializer: &'a D,
+    end_pos: u64,
+    _marker: PhantomData<E>,
+}
+
+impl<'a, E: Endianness, BR: BitRead<E> + BitSeek, D: BitDeserializer<E, BR>> Iterator
+    for SeqLabels<'a, E, BR, D>
+{
+    type Item = D::DeserType;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.bit_pos().unwrap() >= self.end_pos {
+            return None;
+        }
+        Some(self.deserializer.deserialize(self.reader).unwrap())
+    }
+}
+
+impl<
+        E: Endianness,
+        BR: BitRead<E> + BitSeek + Clone,
+        D: BitDeserializer<E, BR>,
+        O: IndexedDict<Input = usize, Output = usize>,
+    > SequentialLabeling for BitStreamLabeling<E, BR, D, O>
+{
+    type Label = D::DeserType;
+
+    type Lender<'node> = Iter<'node, E, BR, D, O>
+    where
+        Self: 'node;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        self.num_arcs
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        Iter {
+            reader: self.reader.clone(),
+            deserializer: &self.deserializer,
+            offsets: &self.offsets,
+            next_node: from,
+            num_nodes: self.num_nodes(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The iterator on the labels of a node returned by
+/// [`BitStreamLabeling::labels`](RandomAccessLabeling::labels).
+pub struct Labels<'a, E, BR, D> {
+    reader: BR,
+    deserializer: &'a D,
+    end_pos: u64,
+    _marker: PhantomData<E>,
+}
+
+impl<'a, E: Endianness, BR: BitRead<E> + BitSeek, D: BitDeserializer<E, BR>> Iterator
+    for Labels<'a, E, BR, D>
+{
+    type Item = D::DeserType;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.bit_pos().unwrap() >= self.end_pos {
+            return None;
+        }
+        Some(self.deserializer.deserialize(&mut self.reader).unwrap())
+    }
+}
+
+impl<
+        E: Endianness,
+        BR: BitRead<E> + BitSeek + Clone,
+        D: BitDeserializer<E, BR>,
+        O: IndexedDict<Input = usize, Output = usize>,
+    > RandomAccessLabeling for BitStreamLabeling<E, BR, D, O>
+{
+    type Labels<'succ> = Labels<'succ, E, BR, D> where Self: 'succ;
+
+    #[inline(always)]
+    fn num_arcs(&self) -> u64 {
+        self.num_arcs.expect("The number of arcs of this labeling is not known")
+    }
+
+    fn labels(&self, node_id: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        let mut reader = self.reader.clone();
+        reader
+            .set_bit_pos(self.offsets.get(node_id) as u64)
+            .unwrap();
+        Labels {
+            reader,
+            deserializer: &self.deserializer,
+            end_pos: self.offsets.get(node_id + 1) as u64,
+            _marker: PhantomData,
+        }
+    }
+
+    fn outdegree(&self, node_id: usize) -> usize {
+        self.labels(node_id).count()
+    }
+}
+
+/// The size of the files written by a [`BitStreamLabelingWriter`].
+///
+/// It is used to [append](BitStreamLabelingWriter::append) the files written
+/// by a writer to those of another writer, e.g., when labels are written in
+/// parallel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BitStreamLabelingStats {
+    /// The number of nodes.
+    pub num_nodes: usize,
+    /// The number of labels.
+    pub num_arcs: u64,
+    /// The number of bits written to the `.labels` file.
+    pub labels_bits: u64,
+    /// The number of bits written to the `.labeloffsets` file, excluding the
+    /// initial zero delta.
+    pub offsets_bits: u64,
+}
+
+/// A writer for the files of a [`BitStreamLabeling`].
+///
+/// The writer creates a `.labels` and a `.labeloffsets` file with the given
+/// basename. Labels must be [pushed](BitStreamLabelingWriter::push) one node
+/// at a time, in the same order of the successors, and the writer must be
+/// [flushed](BitStreamLabelingWriter::flush) at the end.
+///
+/// The files written by another writer can be
+/// [appended](BitStreamLabelingWriter::append), which makes it possible to
+/// write labels in parallel.
+pub struct BitStreamLabelingWriter<E: Endianness, S: BitSerializer<E, FileBitWriter<E>>>
+where
+    FileBitWriter<E>: BitWrite<E>,
+{
+    labels_writer: FileBitWriter<E>,
+    offsets_writer: FileBitWriter<E>,
+    serializer: S,
+    stats: BitStreamLabelingStats,
+}
+
+impl<E: Endianness, S: BitSerializer<E, FileBitWriter<E>>> BitStreamLabelingWriter<E, S>
+where
+    FileBitWriter<E>: BitWrite<E> + GammaWrite<E>,
+{
+    /// Creates a new writer for the labeling with given basename.
+    pub fn new(basename: impl AsRef<Path>, serializer: S) -> anyhow::Result<Self> {
+        let basename = basename.as_ref();
+        let create = |extension: &str| -> anyhow::Result<FileBitWriter<E>> {
+            let path = basename.with_extension(extension);
+            Ok(<BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(
+                BufWriter::with_capacity(
+                    1 << 20,
+                    File::create(&path)
+                        .with_context(|| format!("Could not create {}", path.display()))?,
+                ),
+            )))
+        };
+        let labels_writer = create(LABELS_EXTENSION)?;
+        let mut offsets_writer = create(LABELOFFSETS_EXTENSION)?;
+        offsets_writer
+            .write_gamma(0)
+            .context("Could not write initial delta")?;
+        Ok(Self {
+            labels_writer,
+            offsets_writer,
+            serializer,
+            stats: BitStreamLabelingStats::default(),
+        })
+    }
+
+    /// Writes the labels of the next node, returning the number of bits
+    /// written to the labels bitstream.
+    pub fn push(&mut self, labels: impl IntoIterator<Item = S::SerType>) -> anyhow::Result<u64> {
+        let mut bits = 0;
+        for label in labels {
+            bits += self
+                .serializer
+                .serialize(&label, &mut self.labels_writer)
+                .context("Could not serialize label")? as u64;
+            self.stats.num_arcs += 1;
+        }
+        self.stats.offsets_bits += self
+            .offsets_writer
+            .write_gamma(bits)
+            .context("Could not write delta")? as u64;
+        self.stats.labels_bits += bits;
+        self.stats.num_nodes += 1;
+        Ok(bits)
+    }
+
+    /// Returns the number of nodes written so far.
+    pub fn num_nodes(&self) -> usize {
+        self.stats.num_nodes
+    }
+
+    /// Returns the number of labels written so far.
+    pub fn num_arcs(&self) -> u64 {
+        self.stats.num_arcs
+    }
+
+    /// Returns the size of the files written so far.
+    pub fn stats(&self) -> BitStreamLabelingStats {
+        self.stats
+    }
+
+    /// Appends the labels of the nodes in the files with given basename,
+    /// written by a [flushed](BitStreamLabelingWriter::flush) writer with
+    /// the given [stats](BitStreamLabelingWriter::stats).
+    pub fn append(
+        &mut self,
+        basename: impl AsRef<Path>,
+        stats: BitStreamLabelingStats,
+    ) -> anyhow::Result<()>
+    where
+        BufBitReader<E, WordAdapter<u32, BufReader<File>>>: BitRead<E>,
+    {
+        let basename = basename.as_ref();
+        let open = |extension: &str| -> anyhow::Result<_> {
+            let path = basename.with_extension(extension);
+            Ok(<BufBitReader<E, _>>::new(<WordAdapter<u32, _>>::new(
+                BufReader::new(
+                    File::open(&path)
+                        .with_context(|| format!("Could not open {}", path.display()))?,
+                ),
+            )))
+        };
+        self.labels_writer
+            .copy_from(&mut open(LABELS_EXTENSION)?, stats.labels_bits)
+            .context("Could not copy labels")?;
+        let mut offsets_reader = open(LABELOFFSETS_EXTENSION)?;
+        // Skip the initial zero delta
+        offsets_reader
+            .skip_bits(1)
+            .context("Could not skip initial delta")?;
+        self.offsets_writer
+            .copy_from(&mut offsets_reader, stats.offsets_bits)
+            .context("Could not copy label offsets")?;
+        self.stats.num_nodes += stats.num_nodes;
+        self.stats.num_arcs += stats.num_arcs;
+        self.stats.labels_bits += stats.labels_bits;
+        self.stats.offsets_bits += stats.offsets_bits;
+        Ok(())
+    }
+
+    /// Flushes the underlying bitstreams.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        self.labels_writer
+            .flush()
+            .context("Could not flush labels")?;
+        self.offsets_writer
+            .flush()
+            .context("Could not flush label offsets")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+/*!
+
+Labels that are unsigned integers of fixed width.
+
+[`FixedWidth`] is both a [`BitSerializer`] and a [`BitDeserializer`] for
+labels of type `u64` that are written using a fixed number of bits, and it
+works with any endianness and bitstream. Thus, it can be used both to
+write and read [bitstream labelings](super::bitstream) and to
+sort labeled arcs with [`SortPairs`](crate::utils::sort_pairs::SortPairs).
+
+*/
+
+use crate::traits::{BitDeserializer, BitSerializer};
+use dsi_bitstream::prelude::*;
+
+/// A [`BitSerializer`] and [`BitDeserializer`] for `u64` labels of fixed
+/// width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedWidth {
+    width: usize,
+}
+
+impl FixedWidth {
+    /// Creates a new serializer/deserializer for labels of given width.
+    ///
+    /// # Panics
+    ///
+    /// If the width is zero or larger than 64.
+    pub fn new(width: usize) -> Self {
+        assert!(
+            (1..=64).contains(&width),
+            "The width of labels must be between 1 and 64, but it is {}",
+            width
+        );
+        Self { width }
+    }
+
+    /// Returns the width of the labels in bits.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the largest label representable with this width.
+    pub fn max_label(&self) -> u64 {
+        u64::MAX >> (64 - self.width)
+    }
+}
+
+impl<E: Endianness, BW: BitWrite<E>> BitSerializer<E, BW> for FixedWidth {
+    type SerType = u64;
+
+    #[inline(always)]
+    fn serialize(&self, value: &Self::SerType, bitstream: &mut BW) -> Result<usize, BW::Error> {
+        debug_assert!(
+            *value <= self.max_label(),
+            "Label {} does not fit in {} bits",
+            value,
+            self.width
+        );
+        bitstream.write_bits(*value, self.width)
+    }
+}
+
+impl<E: Endianness, BR: BitRead<E>> BitDeserializer<E, BR> for FixedWidth {
+    type DeserType = u64;
+
+    #[inline(always)]
+    fn deserialize(&self, bitstream: &mut BR) -> Result<Self::DeserType, BR::Error> {
+        bitstream.read_bits(self.width)
+    }
+}
@@ -7,7 +7,18 @@
 
 //! Utility structures for labelings.
 
+pub mod bitstream;
+pub use bitstream::{
+    BitStreamLabeling, BitStreamLabelingStats, BitStreamLabelingWriter, FileBitWriter,
+    MmapBitReader,
+};
+
+pub mod fixed_width;
+pub use fixed_width::FixedWidth;
+
 pub mod swh_labels;
+pub use swh_labels::SwhLabels;
+#[allow(deprecated)]
 pub use swh_labels::SeqLabels;
 
 pub mod zip;
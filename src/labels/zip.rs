@@ -9,9 +9,9 @@ use core::iter;
 use lender::{IntoLender, Lend, Lender, Lending};
 
 use crate::prelude::{
-    LabeledRandomAccessGraph, LabeledSequentialGraph, LenderIntoIter, LenderIntoIterator,
+    split, LabeledRandomAccessGraph, LabeledSequentialGraph, LenderIntoIter, LenderIntoIterator,
     LenderLabel, NodeLabelsLender, Pair, RandomAccessGraph, RandomAccessLabeling, SequentialGraph,
-    SequentialLabeling, SortedIterator, SortedLender,
+    SequentialLabeling, SortedIterator, SortedLender, SplitLabeling,
 };
 
 /**
@@ -156,6 +156,19 @@ impl<L: RandomAccessLabeling, R: RandomAccessLabeling> RandomAccessLabeling for
     }
 }
 
+impl<L: RandomAccessLabeling, R: RandomAccessLabeling> SplitLabeling for Zip<L, R>
+where
+    for<'a> <L as SequentialLabeling>::Lender<'a>: Send + Sync,
+    for<'a> <R as SequentialLabeling>::Lender<'a>: Send + Sync,
+{
+    type SplitLender<'a> = split::ra::Lender<'a, Zip<L, R>> where Self: 'a;
+    type IntoIterator<'a> = split::ra::IntoIterator<'a, Zip<L, R>> where Self: 'a;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::ra::Iter::new(self, how_many)
+    }
+}
+
 impl<G: SequentialGraph, L: SequentialLabeling> LabeledSequentialGraph<L::Label> for Zip<G, L> {}
 
 impl<G: RandomAccessGraph, L: RandomAccessLabeling> LabeledRandomAccessGraph<L::Label>
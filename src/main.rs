@@ -80,16 +80,25 @@ pub fn main() -> Result<()> {
         bfs,
         build,
         check_ef,
+        check_symmetric,
         convert,
         from_csv,
+        kcore,
         llp,
         merge_perms,
         optimize_codes,
         pad,
+        pagerank,
         rand_perm,
         recompress,
+        scc,
         simplify,
+        stats,
+        subgraph,
+        sumsweep,
         to_csv,
-        transpose
+        transpose,
+        triangles,
+        wcc
     )
 }
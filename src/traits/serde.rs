@@ -42,3 +42,13 @@ impl<E: Endianness, BR: BitRead<E>> BitDeserializer<E, BR> for () {
         Ok(())
     }
 }
+
+/// Delegating implementation of [`BitSerializer`] for references, so that a
+/// serializer can be shared, e.g., among threads.
+impl<E: Endianness, BW: BitWrite<E>, S: BitSerializer<E, BW>> BitSerializer<E, BW> for &S {
+    type SerType = S::SerType;
+    #[inline(always)]
+    fn serialize(&self, value: &Self::SerType, bitstream: &mut BW) -> Result<usize, BW::Error> {
+        (**self).serialize(value, bitstream)
+    }
+}
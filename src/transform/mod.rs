@@ -15,3 +15,6 @@ pub use transpose::*;
 
 mod perm;
 pub use perm::*;
+
+mod subgraph;
+pub use subgraph::{subgraph, subgraph_from_nodes, Subgraph};
@@ -5,45 +5,56 @@
  */
 
 use crate::graphs::arc_list_graph;
-use crate::prelude::sort_pairs::{BatchIterator, KMergeIters};
+use crate::prelude::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters};
 use crate::prelude::*;
+use crate::traits::graph::UnitLabelGraph;
 use anyhow::{ensure, Context, Result};
+use dsi_bitstream::traits::NE;
 use dsi_progress_logger::prelude::*;
 use lender::*;
 use sux::traits::BitFieldSlice;
 use tempfile::Builder;
 
-/// Returns a [sequential](crate::traits::SequentialGraph) permuted graph.
+/// Returns a permuted labeled graph as a [labeled sequential
+/// graph](crate::traits::LabeledSequentialGraph).
 ///
-/// This assumes that the permutation is bijective.
-/// For the meaning of the additional parameter, see
-/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+/// Each arc _x_ → _y_ with label _l_ becomes the arc
+/// `perm[x]` → `perm[y]` with the same label. This assumes that the
+/// permutation is bijective. For the meaning of the additional parameters,
+/// see [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
 #[allow(clippy::type_complexity)]
-pub fn permute(
-    graph: &impl SequentialGraph,
+pub fn permute_labeled<
+    S: BitSerializer<NE, BitWriter> + Clone,
+    D: BitDeserializer<NE, BitReader> + Clone + 'static,
+>(
+    graph: &impl LabeledSequentialGraph<S::SerType>,
     perm: &impl BitFieldSlice<usize>,
     batch_size: usize,
-) -> Result<Left<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<()>, ()>>>> {
+    serializer: S,
+    deserializer: D,
+) -> Result<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<D>, D::DeserType>>>
+where
+    S::SerType: Send + Sync + Copy,
+    D::DeserType: Clone + Copy,
+{
     ensure!(perm.len() == graph.num_nodes(),
-        "The given permutation has {} values and thus it's incompatible with a graph with {} nodes.", 
+        "The given permutation has {} values and thus it's incompatible with a graph with {} nodes.",
         perm.len(), graph.num_nodes(),
     );
     let dir = Builder::new().prefix("Permute").tempdir()?;
 
     // create a stream where to dump the sorted pairs
-    let mut sorted = SortPairs::new(batch_size, dir)?;
-
-    // get a premuted view
-    let pgraph = PermutedGraph { graph, perm };
+    let mut sorted = SortPairs::new_labeled(batch_size, dir.path(), serializer, deserializer)?;
 
     let mut pl = ProgressLogger::default();
     pl.item_name("node")
         .expected_updates(Some(graph.num_nodes()));
     pl.start("Creating batches...");
     // create batches of sorted edges
-    for_!( (src, succ) in pgraph.iter() {
-        for dst in succ {
-            sorted.push(dst, src)?;
+    for_!( (src, succ) in graph.iter() {
+        let src = perm.get(src);
+        for (dst, l) in succ {
+            sorted.push_labeled(src, perm.get(dst), l)?;
         }
         pl.light_update();
     });
@@ -53,7 +64,27 @@ pub fn permute(
     let sorted = arc_list_graph::ArcListGraph::new_labeled(graph.num_nodes(), edges);
     pl.done();
 
-    Ok(Left(sorted))
+    Ok(sorted)
+}
+
+/// Returns a [sequential](crate::traits::SequentialGraph) permuted graph.
+///
+/// This assumes that the permutation is bijective.
+/// For the meaning of the additional parameter, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn permute(
+    graph: &impl SequentialGraph,
+    perm: &impl BitFieldSlice<usize>,
+    batch_size: usize,
+) -> Result<Left<arc_list_graph::ArcListGraph<KMergeIters<BatchIterator<()>, ()>>>> {
+    Ok(Left(permute_labeled(
+        &UnitLabelGraph(graph),
+        perm,
+        batch_size,
+        (),
+        (),
+    )?))
 }
 
 /// Returns a [sequential](crate::traits::SequentialGraph) permuted graph.
@@ -6,9 +6,12 @@
 
 use crate::graphs::{arc_list_graph, UnionGraph};
 use crate::labels::Left;
-use crate::traits::{SequentialGraph, SplitLabeling};
-use crate::utils::sort_pairs::{BatchIterator, KMergeIters, SortPairs};
+use crate::traits::{
+    BitDeserializer, BitSerializer, LabeledSequentialGraph, SequentialGraph, SplitLabeling,
+};
+use crate::utils::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters, SortPairs};
 use anyhow::{Context, Result};
+use dsi_bitstream::traits::NE;
 use dsi_progress_logger::prelude::*;
 use itertools::{Dedup, Itertools};
 use lender::*;
@@ -88,6 +91,97 @@ pub fn simplify(
     Ok(Left(sorted))
 }
 
+/// An iterator on labeled arcs sorted by source and target that merges the
+/// labels of consecutive duplicate arcs.
+///
+/// This is the iterator underlying the graph returned by [`simplify_labeled`].
+pub struct MergeLabels<I: Iterator, F> {
+    iter: std::iter::Peekable<I>,
+    merge: F,
+}
+
+impl<I: Iterator, F: Clone> Clone for MergeLabels<I, F>
+where
+    I: Clone,
+    I::Item: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            merge: self.merge.clone(),
+        }
+    }
+}
+
+impl<L, I: Iterator<Item = (usize, usize, L)>, F: FnMut(L, L) -> L> Iterator for MergeLabels<I, F> {
+    type Item = (usize, usize, L);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (src, dst, mut label) = self.iter.next()?;
+        while let Some((_, _, next_label)) = self.iter.next_if(|&(s, d, _)| s == src && d == dst) {
+            label = (self.merge)(label, next_label);
+        }
+        Some((src, dst, label))
+    }
+}
+
+/// Returns a simplified (i.e., undirected and loopless) version of the provided
+/// labeled graph as a [labeled sequential
+/// graph](crate::traits::LabeledSequentialGraph).
+///
+/// Each arc _x_ → _y_ with label _l_, where _x_ ≠ _y_, yields the arcs
+/// _x_ → _y_ and _y_ → _x_ with label _l_. Duplicate arcs (e.g., an arc and
+/// its reverse in the original graph) are replaced by a single arc whose label
+/// is obtained by combining their labels using `merge`. Since duplicate arcs
+/// are returned in no specific order, `merge` should be associative and
+/// commutative (e.g., minimum, maximum, or sum).
+///
+/// For the meaning of the additional parameters, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs).
+#[allow(clippy::type_complexity)]
+pub fn simplify_labeled<S, D, F>(
+    graph: &impl LabeledSequentialGraph<S::SerType>,
+    batch_size: usize,
+    serializer: S,
+    deserializer: D,
+    merge: F,
+) -> Result<arc_list_graph::ArcListGraph<MergeLabels<KMergeIters<BatchIterator<D>, D::DeserType>, F>>>
+where
+    S: BitSerializer<NE, BitWriter> + Clone,
+    D: BitDeserializer<NE, BitReader> + Clone + 'static,
+    S::SerType: Send + Sync + Copy,
+    D::DeserType: Clone + Copy,
+    F: FnMut(D::DeserType, D::DeserType) -> D::DeserType + Clone,
+{
+    let dir = Builder::new().prefix("simplify-").tempdir()?;
+    let mut sorted = SortPairs::new_labeled(batch_size, dir.path(), serializer, deserializer)?;
+
+    let mut pl = ProgressLogger::default();
+    pl.item_name("node")
+        .expected_updates(Some(graph.num_nodes()));
+    pl.start("Creating batches...");
+    // create batches of sorted edges
+    let mut iter = graph.iter();
+    while let Some((src, succ)) = iter.next() {
+        for (dst, l) in succ {
+            if src != dst {
+                sorted.push_labeled(src, dst, l)?;
+                sorted.push_labeled(dst, src, l)?;
+            }
+        }
+        pl.light_update();
+    }
+    // merge the batches
+    let edges = MergeLabels {
+        iter: sorted.iter().context("Could not read arcs")?.peekable(),
+        merge,
+    };
+    let sorted = arc_list_graph::ArcListGraph::new_labeled(graph.num_nodes(), edges);
+    pl.done();
+
+    Ok(sorted)
+}
+
 /// Returns a simplified (i.e., undirected and loopless) version of the provided
 /// graph as a [sequential graph](crate::traits::SequentialGraph).
 ///
@@ -0,0 +1,283 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use crate::prelude::*;
+use anyhow::{ensure, Result};
+use core::ops::Index;
+use lender::*;
+
+/// The subgraph of a graph induced by a set of nodes, with nodes renumbered
+/// compactly.
+///
+/// Nodes of the subgraph are numbered from zero following the order of the
+/// nodes of the original graph, so node _x_ of the subgraph is node
+/// `nodes()[x]` of the original graph; as a consequence, if successors are
+/// sorted in the original graph, they are sorted in the subgraph, too.
+///
+/// Instances are built by [`subgraph`] or [`subgraph_from_nodes`]. The
+/// subgraph is computed lazily by scanning the original graph, skipping
+/// nodes that are not in the subgraph. If the original graph is
+/// [random-access](RandomAccessGraph), so is the subgraph; note, however,
+/// that the outdegree of a node is computed by enumerating its successors,
+/// and the number of arcs by enumerating all arcs. To keep the original
+/// node identifiers, use a [`MaskedGraph`](crate::graphs::MaskedGraph)
+/// instead.
+#[derive(Debug, Clone)]
+pub struct Subgraph<'a, G: SequentialGraph> {
+    graph: &'a G,
+    /// The node of the original graph corresponding to each node.
+    nodes: Box<[usize]>,
+    /// The node of the subgraph corresponding to each node of the original
+    /// graph, or `usize::MAX` if the node is not in the subgraph.
+    map: Box<[usize]>,
+}
+
+impl<'a, G: SequentialGraph> Subgraph<'a, G> {
+    /// Returns the mapping from the nodes of the subgraph to the nodes of
+    /// the original graph.
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+
+    /// Consumes the subgraph and returns the mapping from the nodes of the
+    /// subgraph to the nodes of the original graph.
+    pub fn into_nodes(self) -> Box<[usize]> {
+        self.nodes
+    }
+}
+
+/// Returns the subgraph of a graph induced by the nodes in a mask, with nodes
+/// renumbered compactly.
+///
+/// The mask can be any structure indexable by the nodes of the graph
+/// returning a boolean, such as a [`BitVec`](sux::bits::BitVec) or a slice of
+/// booleans. The mapping from the nodes of the subgraph to the nodes of the
+/// graph is available via [`Subgraph::nodes`].
+pub fn subgraph<'a, G: SequentialGraph, M: Index<usize, Output = bool> + ?Sized>(
+    graph: &'a G,
+    mask: &M,
+) -> Subgraph<'a, G> {
+    let mut nodes = vec![];
+    let mut map = vec![usize::MAX; graph.num_nodes()].into_boxed_slice();
+    for (node, new_node) in map.iter_mut().enumerate() {
+        if mask[node] {
+            *new_node = nodes.len();
+            nodes.push(node);
+        }
+    }
+    Subgraph {
+        graph,
+        nodes: nodes.into_boxed_slice(),
+        map,
+    }
+}
+
+/// Returns the subgraph of a graph induced by a strictly increasing sequence
+/// of nodes, with nodes renumbered compactly.
+///
+/// Node _x_ of the subgraph is the _x_-th node of the sequence. An error
+/// is returned if the sequence is not strictly increasing or if it contains
+/// a node that is not in the graph.
+pub fn subgraph_from_nodes<G: SequentialGraph>(
+    graph: &G,
+    nodes: impl IntoIterator<Item = usize>,
+) -> Result<Subgraph<'_, G>> {
+    let num_nodes = graph.num_nodes();
+    let mut map = vec![usize::MAX; num_nodes].into_boxed_slice();
+    let nodes = nodes.into_iter().collect::<Box<[usize]>>();
+    for (new_node, &node) in nodes.iter().enumerate() {
+        ensure!(
+            node < num_nodes,
+            "Node {} is out of range for a graph with {} nodes",
+            node,
+            num_nodes
+        );
+        ensure!(
+            new_node == 0 || nodes[new_node - 1] < node,
+            "The nodes are not strictly increasing ({} follows {})",
+            node,
+            nodes[new_node - 1]
+        );
+        map[node] = new_node;
+    }
+    Ok(Subgraph { graph, nodes, map })
+}
+
+impl<'a, G: SequentialGraph> SequentialLabeling for Subgraph<'a, G> {
+    type Label = usize;
+    type Lender<'b> = Iter<'b, G::Lender<'b>>
+    where
+        Self: 'b;
+
+    #[inline(always)]
+    fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline(always)]
+    fn num_arcs_hint(&self) -> Option<u64> {
+        None
+    }
+
+    fn iter_from(&self, from: usize) -> Self::Lender<'_> {
+        let (iter, next, pos) = match self.nodes.get(from) {
+            Some(&node) => (self.graph.iter_from(node), from, node),
+            None => (self.graph.iter(), self.nodes.len(), 0),
+        };
+        Iter {
+            iter,
+            nodes: &self.nodes,
+            map: &self.map,
+            next,
+            pos,
+        }
+    }
+}
+
+impl<'a, G: SequentialGraph + Sync> SplitLabeling for Subgraph<'a, G>
+where
+    for<'b> G::Lender<'b>: Clone + ExactSizeLender + Send + Sync,
+{
+    type SplitLender<'b> = split::seq::Lender<'b, Subgraph<'a, G>>
+    where
+        Self: 'b;
+    type IntoIterator<'b> = split::seq::IntoIterator<'b, Subgraph<'a, G>>
+    where
+        Self: 'b;
+
+    fn split_iter(&self, how_many: usize) -> Self::IntoIterator<'_> {
+        split::seq::Iter::new(self.iter(), how_many)
+    }
+}
+
+impl<'a, G: SequentialGraph> SequentialGraph for Subgraph<'a, G> {}
+
+impl<'a, G: RandomAccessGraph> RandomAccessLabeling for Subgraph<'a, G> {
+    type Labels<'b> = Succ<'b, <<G as RandomAccessLabeling>::Labels<'b> as IntoIterator>::IntoIter>
+    where
+        Self: 'b;
+
+    /// Returns the number of arcs in the graph.
+    ///
+    /// This method enumerates all arcs, so it takes time linear in the
+    /// size of the original graph.
+    fn num_arcs(&self) -> u64 {
+        let mut num_arcs = 0;
+        let mut iter = self.iter();
+        while let Some((_, succ)) = iter.next() {
+            num_arcs += succ.into_iter().count() as u64;
+        }
+        num_arcs
+    }
+
+    fn labels(&self, node_id: usize) -> <Self as RandomAccessLabeling>::Labels<'_> {
+        Succ {
+            iter: self.graph.successors(self.nodes[node_id]).into_iter(),
+            map: &self.map,
+        }
+    }
+
+    /// Returns the outdegree of a node.
+    ///
+    /// This method enumerates the successors of the node.
+    fn outdegree(&self, node_id: usize) -> usize {
+        <Self as RandomAccessLabeling>::labels(self, node_id).count()
+    }
+}
+
+impl<'a, G: RandomAccessGraph> RandomAccessGraph for Subgraph<'a, G> {
+    fn has_arc(&self, src_node_id: usize, dst_node_id: usize) -> bool {
+        self.graph
+            .has_arc(self.nodes[src_node_id], self.nodes[dst_node_id])
+    }
+}
+
+impl<'a, 'c, G: SequentialGraph> IntoLender for &'c Subgraph<'a, G> {
+    type Lender = <Subgraph<'a, G> as SequentialLabeling>::Lender<'c>;
+
+    #[inline(always)]
+    fn into_lender(self) -> Self::Lender {
+        self.iter()
+    }
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Iter<'a, L> {
+    iter: L,
+    nodes: &'a [usize],
+    map: &'a [usize],
+    /// The next node of the subgraph to return.
+    next: usize,
+    /// The next node of the original graph that `iter` will return.
+    pos: usize,
+}
+
+impl<'a, 'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>>
+    NodeLabelsLender<'succ> for Iter<'a, L>
+{
+    type Label = usize;
+    type IntoIterator = Succ<'a, LenderIntoIter<'succ, L>>;
+}
+
+impl<'a, 'succ, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lending<'succ>
+    for Iter<'a, L>
+{
+    type Lend = (usize, <Self as NodeLabelsLender<'succ>>::IntoIterator);
+}
+
+impl<'a, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> Lender for Iter<'a, L> {
+    #[inline(always)]
+    fn next(&mut self) -> Option<Lend<'_, Self>> {
+        let &node = self.nodes.get(self.next)?;
+        let succ = self.iter.nth(node - self.pos)?.into_pair().1;
+        self.pos = node + 1;
+        self.next += 1;
+        Some((
+            self.next - 1,
+            Succ {
+                iter: succ.into_iter(),
+                map: self.map,
+            },
+        ))
+    }
+}
+
+impl<'a, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>> ExactSizeLender
+    for Iter<'a, L>
+{
+    fn len(&self) -> usize {
+        self.nodes.len() - self.next
+    }
+}
+
+unsafe impl<'a, L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + SortedLender>
+    SortedLender for Iter<'a, L>
+{
+}
+
+/// An iterator returning the successors of a node that are in the
+/// subgraph, renumbered.
+#[doc(hidden)]
+#[derive(Debug, Clone)]
+pub struct Succ<'a, I: Iterator<Item = usize>> {
+    iter: I,
+    map: &'a [usize],
+}
+
+impl<'a, I: Iterator<Item = usize>> Iterator for Succ<'a, I> {
+    type Item = usize;
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(|succ| match self.map[succ] {
+            usize::MAX => None,
+            succ => Some(succ),
+        })
+    }
+}
+
+unsafe impl<'a, I: Iterator<Item = usize> + SortedIterator> SortedIterator for Succ<'a, I> {}
@@ -5,14 +5,17 @@
  */
 
 use crate::graphs::arc_list_graph;
-use crate::prelude::proj::Left;
+use crate::prelude::proj::{Left, LeftIterator};
 use crate::prelude::sort_pairs::{BatchIterator, BitReader, BitWriter, KMergeIters, SortPairs};
 use crate::prelude::{BitDeserializer, BitSerializer, LabeledSequentialGraph, SequentialGraph};
 use crate::traits::graph::UnitLabelGraph;
-use anyhow::Result;
+use crate::traits::NodeLabelsLender;
+use crate::traits::SplitLabeling;
+use anyhow::{Context, Result};
 use dsi_bitstream::traits::NE;
 use dsi_progress_logger::prelude::*;
 use lender::prelude::*;
+use std::sync::Mutex;
 use tempfile::Builder;
 
 /// Returns the transpose of the provided labeled graph as a [sequential
@@ -75,6 +78,117 @@ pub fn transpose(
     )?))
 }
 
+/// Returns the transpose of the provided graph as a sequence of lenders on
+/// adjacent, increasing ranges of nodes, suitable for parallel compression
+/// with [`BVComp::parallel_iter`](crate::graphs::bvgraph::BVComp::parallel_iter).
+///
+/// The nodes are divided in as many ranges as the threads of the pool, and
+/// there is a [`SortPairs`] for each range, shared by all threads. The graph
+/// is scanned in parallel using [`SplitLabeling`], and each thread pushes the
+/// transposed arcs into the [`SortPairs`] of the range of their source. The
+/// batches of each range are then merged, so that the returned lenders can
+/// be consumed independently.
+///
+/// For the meaning of the additional parameter, see
+/// [`SortPairs`](crate::prelude::sort_pairs::SortPairs); the batch size is
+/// divided among the [`SortPairs`] instances.
+#[allow(clippy::type_complexity)]
+pub fn transpose_split<S>(
+    graph: &S,
+    batch_size: usize,
+    mut threads: impl AsMut<rayon::ThreadPool>,
+) -> Result<Vec<LeftIterator<arc_list_graph::Iter<(), KMergeIters<BatchIterator<()>, ()>>>>>
+where
+    S: SequentialGraph + SplitLabeling,
+{
+    let pool = threads.as_mut();
+    let num_threads = pool.current_num_threads();
+    let num_nodes = graph.num_nodes();
+    let range_size = num_nodes.div_ceil(num_threads).max(1);
+    let num_ranges = num_nodes.div_ceil(range_size);
+    let batch_size = (batch_size / num_ranges.max(1)).max(1);
+
+    let mut dirs = vec![];
+    let mut sorted = vec![];
+    for range in 0..num_ranges {
+        let dir = Builder::new()
+            .prefix(&format!("TransposeSplit{}", range))
+            .tempdir()
+            .context("Could not create a temporary directory")?;
+        sorted.push(Mutex::new(SortPairs::new(batch_size, dir.path())?));
+        dirs.push(dir);
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    pool.in_place_scope(|scope| {
+        for iter in graph.split_iter(num_threads) {
+            let tx = tx.clone();
+            let sorted = &sorted;
+            scope.spawn(move |_| {
+                tx.send(push_transposed(iter, sorted, range_size))
+                    .expect("Could not send the result");
+            });
+        }
+    });
+    drop(tx);
+    for result in rx {
+        result?;
+    }
+
+    let lenders = sorted
+        .into_iter()
+        .enumerate()
+        .map(|(range, sorted)| {
+            let start = range * range_size;
+            let end = num_nodes.min(start + range_size);
+            let edges = sorted
+                .into_inner()
+                .unwrap()
+                .iter()
+                .context("Could not read arcs")?;
+            Ok(LeftIterator(arc_list_graph::Iter::new_from(
+                end, start, edges,
+            )))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    drop(dirs);
+    Ok(lenders)
+}
+
+/// Pushes the transposed arcs returned by a lender into the [`SortPairs`]
+/// of the range of their source, buffering them to reduce contention.
+fn push_transposed<L>(iter: L, sorted: &[Mutex<SortPairs>], range_size: usize) -> Result<()>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize>,
+{
+    // The number of arcs buffered for each range before pushing them
+    const BUFFER_SIZE: usize = 1024;
+
+    let push = |range: usize, buffer: &mut Vec<(usize, usize)>| -> Result<()> {
+        let mut sorted = sorted[range].lock().unwrap();
+        for (src, dst) in buffer.drain(..) {
+            sorted.push(src, dst)?;
+        }
+        Ok(())
+    };
+
+    let mut buffers = vec![Vec::with_capacity(BUFFER_SIZE); sorted.len()];
+    for_!( (src, succ) in iter {
+        for dst in succ {
+            let range = dst / range_size;
+            buffers[range].push((dst, src));
+            if buffers[range].len() == BUFFER_SIZE {
+                push(range, &mut buffers[range])?;
+            }
+        }
+    });
+    for (range, buffer) in buffers.iter_mut().enumerate() {
+        push(range, buffer)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
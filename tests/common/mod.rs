@@ -0,0 +1,111 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+//! Helpers shared by integration tests.
+
+// Each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use sux::prelude::*;
+use webgraph::{
+    graphs::{bvgraph::sequential::BVGraphSeq, random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compress in parallel the lenders returned by `iter` into a temporary
+/// directory, reload the result sequentially, check its number of nodes, and
+/// return it as a [`VecGraph`].
+pub fn compress_par<L>(
+    iter: impl Iterator<Item = L>,
+    num_nodes: usize,
+    num_threads: usize,
+) -> Result<VecGraph>
+where
+    L: Lender + for<'next> NodeLabelsLender<'next, Label = usize> + Send,
+{
+    let dir = tempfile::tempdir()?;
+    let basename = dir.path().join("graph");
+    BVComp::parallel_iter::<BE, _>(
+        &basename,
+        iter,
+        num_nodes,
+        CompFlags::default(),
+        Threads::Num(num_threads),
+        tempfile::tempdir()?,
+    )?;
+    let graph = BVGraphSeq::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    assert_eq!(graph.num_nodes(), num_nodes);
+    Ok(VecGraph::from_lender(graph.iter()))
+}
+
+/// Return the transpose of a graph as a [`VecGraph`].
+pub fn transpose(graph: &impl SequentialGraph) -> VecGraph<()> {
+    let mut transpose = VecGraph::empty(graph.num_nodes());
+    for_!( (node, succ) in graph.iter() {
+        for s in succ {
+            transpose.add_arc(s, node);
+        }
+    });
+    transpose
+}
+
+/// Build the degree cumulative function of a graph.
+pub fn build_dcf(graph: &impl RandomAccessGraph) -> DCF {
+    let num_nodes = graph.num_nodes();
+    let mut efb = EliasFanoBuilder::new(num_nodes + 1, graph.num_arcs() as usize + 1);
+    let mut cumul_deg = 0;
+    efb.push(0).unwrap();
+    for node in 0..num_nodes {
+        cumul_deg += graph.outdegree(node);
+        efb.push(cumul_deg).unwrap();
+    }
+    efb.build().convert_to().unwrap()
+}
+
+/// Return a symmetric graph with the arcs of a random graph and their
+/// reverses.
+pub fn symmetric_random(n: usize, p: f64, seed: u64) -> Left<VecGraph> {
+    let random = VecGraph::from_lender(ErdosRenyi::new(n, p, seed).iter());
+    let mut graph = VecGraph::empty(n);
+    for_!((node, succ) in random.iter() {
+        for (s, _) in succ {
+            graph.add_arc(node, s);
+            graph.add_arc(s, node);
+        }
+    });
+    Left(graph)
+}
+
+/// Return a labeled graph with 100 nodes, some loops and some reciprocal arcs.
+pub fn labeled_graph<L: Copy + From<u8> + 'static>() -> VecGraph<L> {
+    let mut arcs = vec![];
+    for x in 0..100 {
+        for y in [x / 2, x + 1, 2 * x, 3 * x + 7] {
+            if y < 100 {
+                arcs.push((x, y, L::from((x * y % 17) as u8)));
+            }
+        }
+    }
+    VecGraph::from_labeled_arc_list(arcs)
+}
+
+/// Return the labeled arcs of a graph in the order of its lender.
+pub fn labeled_arcs<L>(graph: &impl LabeledSequentialGraph<L>) -> Vec<(usize, usize, L)> {
+    let mut arcs = vec![];
+    for_!((x, succ) in graph.iter() {
+        for (y, l) in succ {
+            arcs.push((x, y, l));
+        }
+    });
+    arcs
+}
@@ -0,0 +1,96 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::prelude::*;
+
+#[test]
+fn test_append() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let tmp_dir = tempfile::tempdir()?;
+    let full_basename = tmp_dir.path().join("full");
+    let basename = tmp_dir.path().join("appended");
+
+    let full_bits =
+        BVComp::single_thread::<BE, _>(&full_basename, &graph, CompFlags::default(), false, None)?;
+
+    // Compress a prefix, without offsets, and append the rest in two steps
+    let (first, second) = (num_nodes / 3, 2 * num_nodes / 3);
+    let mut bits = BVComp::single_thread::<BE, _>(
+        &basename,
+        graph.iter().take(first),
+        CompFlags::default(),
+        false,
+        None,
+    )?;
+    bits += BVComp::append::<BE, _>(
+        &basename,
+        graph.iter_from(first).take(second - first),
+        RefSelection::Greedy,
+    )?;
+    bits += BVComp::append::<BE, _>(&basename, graph.iter_from(second), RefSelection::Greedy)?;
+
+    // With greedy reference selection the result is the same
+    assert_eq!(bits, full_bits);
+    let full = std::fs::read(full_basename.with_extension(GRAPH_EXTENSION))?;
+    let appended = std::fs::read(basename.with_extension(GRAPH_EXTENSION))?;
+    assert_eq!(full, appended);
+    check(&graph, &basename)?;
+
+    // Appending with optimal references
+    let basename = tmp_dir.path().join("optimal");
+    BVComp::single_thread::<BE, _>(
+        &basename,
+        graph.iter().take(first),
+        CompFlags::default(),
+        true,
+        None,
+    )?;
+    BVComp::append::<BE, _>(
+        &basename,
+        graph.iter_from(first),
+        RefSelection::Optimal { chunk_size: 1000 },
+    )?;
+    check(&graph, &basename)?;
+
+    // Nodes must be contiguous
+    assert!(BVComp::append::<BE, _>(&basename, graph.iter_from(1), RefSelection::Greedy).is_err());
+    check(&graph, &basename)?;
+    Ok(())
+}
+
+fn check(graph: &impl RandomAccessGraph, basename: &std::path::Path) -> Result<()> {
+    let appended = BVGraph::with_basename(basename).endianness::<BE>().load()?;
+    assert_eq!(appended.num_nodes(), graph.num_nodes());
+    assert_eq!(appended.num_arcs(), graph.num_arcs());
+    for node in 0..graph.num_nodes() {
+        assert_eq!(
+            appended.successors(node).collect::<Vec<_>>(),
+            graph.successors(node).into_iter().collect::<Vec<_>>()
+        );
+    }
+
+    // The offsets must be consistent with the Elias-Fano representation
+    let seq = BVGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let mut reader =
+        <BufBitReader<BE, _>>::new(<WordAdapter<u32, _>>::new(std::io::BufReader::new(
+            std::fs::File::open(basename.with_extension(OFFSETS_EXTENSION))?,
+        )));
+    let mut offset = reader.read_gamma()?;
+    for (expected, _) in seq.offset_deg_iter() {
+        assert_eq!(offset, expected);
+        offset += reader.read_gamma()?;
+    }
+    Ok(())
+}
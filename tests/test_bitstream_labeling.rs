@@ -0,0 +1,165 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::labels::swh_labels::SwhDeserializer;
+use webgraph::prelude::*;
+
+#[derive(Clone, Debug)]
+struct DeltaSerDe;
+
+impl BitSerializer<LE, FileBitWriter<LE>> for DeltaSerDe {
+    type SerType = u64;
+    fn serialize(
+        &self,
+        value: &Self::SerType,
+        bitstream: &mut FileBitWriter<LE>,
+    ) -> Result<usize, <FileBitWriter<LE> as BitWrite<LE>>::Error> {
+        bitstream.write_delta(*value)
+    }
+}
+
+impl BitDeserializer<LE, MmapBitReader<LE>> for DeltaSerDe {
+    type DeserType = u64;
+    fn deserialize(
+        &self,
+        bitstream: &mut MmapBitReader<LE>,
+    ) -> Result<Self::DeserType, <MmapBitReader<LE> as BitRead<LE>>::Error> {
+        bitstream.read_delta()
+    }
+}
+
+/// Serializes SWH-style labels of width 7.
+struct SwhSerializer;
+
+impl BitSerializer<BE, FileBitWriter<BE>> for SwhSerializer {
+    type SerType = Vec<u64>;
+    fn serialize(
+        &self,
+        value: &Self::SerType,
+        bitstream: &mut FileBitWriter<BE>,
+    ) -> Result<usize, <FileBitWriter<BE> as BitWrite<BE>>::Error> {
+        let mut bits = bitstream.write_gamma(value.len() as u64)?;
+        for &x in value {
+            bits += bitstream.write_bits(x, 7)?;
+        }
+        Ok(bits)
+    }
+}
+
+#[test]
+fn test_bitstream_labeling() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("delta");
+    let num_nodes = 50;
+    let labels = |x: usize| (0..x % 7).map(move |i| (x * i) as u64);
+
+    let mut writer = BitStreamLabelingWriter::<LE, _>::new(&basename, DeltaSerDe)?;
+    for x in 0..num_nodes {
+        writer.push(labels(x))?;
+    }
+    writer.flush()?;
+    assert_eq!(writer.num_nodes(), num_nodes);
+    let num_arcs = writer.num_arcs();
+
+    let labeling = load_labels::<LE, _>(
+        &basename,
+        num_nodes,
+        Some(num_arcs),
+        DeltaSerDe,
+        MemoryFlags::empty(),
+    )?;
+    assert_eq!(labeling.num_nodes(), num_nodes);
+    assert_eq!(labeling.num_arcs(), num_arcs);
+    for x in (0..num_nodes).rev() {
+        assert_eq!(
+            labeling.labels(x).collect::<Vec<_>>(),
+            labels(x).collect::<Vec<_>>()
+        );
+        assert_eq!(labeling.outdegree(x), x % 7);
+    }
+    let mut iter = labeling.iter_from(3);
+    while let Some((x, l)) = iter.next() {
+        assert_eq!(l.collect::<Vec<_>>(), labels(x).collect::<Vec<_>>());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_bitstream_labeling_append() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let num_nodes = 50;
+    let labels = |x: usize| (0..x % 7).map(move |i| (x * i) as u64);
+
+    // Write two parts separately and append them to a third writer
+    let mut stats = vec![];
+    for (part, range) in [0..20, 20..num_nodes].into_iter().enumerate() {
+        let mut writer = BitStreamLabelingWriter::<LE, _>::new(
+            tmp_dir.path().join(part.to_string()),
+            DeltaSerDe,
+        )?;
+        for x in range {
+            writer.push(labels(x))?;
+        }
+        writer.flush()?;
+        stats.push(writer.stats());
+    }
+    let basename = tmp_dir.path().join("delta");
+    let mut writer = BitStreamLabelingWriter::<LE, _>::new(&basename, DeltaSerDe)?;
+    for (part, stats) in stats.into_iter().enumerate() {
+        writer.append(tmp_dir.path().join(part.to_string()), stats)?;
+    }
+    writer.flush()?;
+    assert_eq!(writer.num_nodes(), num_nodes);
+
+    let labeling = load_labels::<LE, _>(
+        &basename,
+        num_nodes,
+        Some(writer.num_arcs()),
+        DeltaSerDe,
+        MemoryFlags::empty(),
+    )?;
+    for x in 0..num_nodes {
+        assert_eq!(
+            labeling.labels(x).collect::<Vec<_>>(),
+            labels(x).collect::<Vec<_>>()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_swh_labels() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("swh");
+    let num_nodes = 20;
+    let labels =
+        |x: usize| (0..x % 3).map(move |i| (0..i).map(|j| ((x + j) % 128) as u64).collect());
+
+    let mut writer = BitStreamLabelingWriter::<BE, _>::new(&basename, SwhSerializer)?;
+    for x in 0..num_nodes {
+        writer.push(labels(x))?;
+    }
+    writer.flush()?;
+
+    let labeling = load_labels::<BE, _>(
+        &basename,
+        num_nodes,
+        None,
+        SwhDeserializer::new(7),
+        MemoryFlags::empty(),
+    )?;
+    for x in 0..num_nodes {
+        assert_eq!(
+            labeling.labels(x).collect::<Vec<Vec<u64>>>(),
+            labels(x).collect::<Vec<Vec<u64>>>()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::path::Path;
+use webgraph::graphs::bvgraph::BuildEf;
+use webgraph::prelude::*;
+
+fn check(basename: &Path, graph: &impl RandomAccessGraph) -> Result<()> {
+    let built = BVGraph::with_basename(basename)
+        .endianness::<BE>()
+        .offsets_mode::<BuildEf>()
+        .load()?;
+    assert_eq!(built.num_nodes(), graph.num_nodes());
+    for node in (0..graph.num_nodes()).step_by(97) {
+        assert_eq!(
+            built.successors(node).collect::<Vec<_>>(),
+            graph.successors(node).into_iter().collect::<Vec<_>>()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_build_ef() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    for extension in [GRAPH_EXTENSION, PROPERTIES_EXTENSION, OFFSETS_EXTENSION] {
+        std::fs::copy(
+            Path::new("tests/data/cnr-2000").with_extension(extension),
+            basename.with_extension(extension),
+        )?;
+    }
+
+    // From the .offsets file
+    check(&basename, &graph)?;
+    assert!(!basename.with_extension(EF_EXTENSION).exists());
+
+    // Scanning the graph
+    std::fs::remove_file(basename.with_extension(OFFSETS_EXTENSION))?;
+    check(&basename, &graph)?;
+    assert!(!basename.with_extension(EF_EXTENSION).exists());
+
+    // Persisting the result
+    BVGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .offsets_mode::<BuildEf<true>>()
+        .load()?;
+    assert!(basename.with_extension(EF_EXTENSION).exists());
+    check(&basename, &graph)?;
+    let stored = BVGraph::with_basename(&basename)
+        .endianness::<BE>()
+        .load()?;
+    for node in (0..graph.num_nodes()).step_by(97) {
+        assert_eq!(
+            stored.successors(node).collect::<Vec<_>>(),
+            graph.successors(node).collect::<Vec<_>>()
+        );
+    }
+    Ok(())
+}
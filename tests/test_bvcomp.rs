@@ -15,8 +15,9 @@ use anyhow::Result;
 use dsi_bitstream::prelude::*;
 use dsi_progress_logger::prelude::*;
 use std::path::Path;
+use webgraph::graphs::bvgraph::BuildEf;
 use webgraph::{graphs::random::ErdosRenyi, prelude::*};
-use Code::{Delta, Gamma, Unary, Zeta};
+use Code::{Delta, ExpGolomb, Gamma, Golomb, Pi, Rice, Unary, Zeta};
 
 #[cfg_attr(feature = "slow_tests", test)]
 #[cfg_attr(not(feature = "slow_tests"), allow(dead_code))]
@@ -58,6 +59,7 @@ where
                                         min_interval_length,
                                         compression_window,
                                         max_ref_count,
+                                        ..CompFlags::default()
                                     };
 
                                     _test_body::<E, _>(tmp_path, &seq_graph, compression_flags)?;
@@ -73,6 +75,217 @@ where
     Ok(())
 }
 
+#[test]
+fn test_bvcomp_codes() -> Result<()> {
+    _test_bvcomp_codes::<LE>().and(_test_bvcomp_codes::<BE>())
+}
+
+fn _test_bvcomp_codes<E: Endianness>() -> Result<()>
+where
+    BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
+    BufBitReader<E, MemWordReader<u32, MmapHelper<u32>>>: CodeRead<E>,
+{
+    let tmp_file = NamedTempFile::new()?;
+    let tmp_path = tmp_file.path();
+    let seq_graph = ErdosRenyi::new(100, 0.1, 0);
+    for code in [
+        Pi { k: 1 },
+        Pi { k: 2 },
+        Pi { k: 4 },
+        Golomb { b: 2 },
+        Golomb { b: 5 },
+        ExpGolomb { k: 1 },
+        ExpGolomb { k: 4 },
+        Rice { log2_b: 1 },
+        Rice { log2_b: 3 },
+    ] {
+        let compression_flags = CompFlags {
+            outdegrees: code,
+            references: code,
+            blocks: code,
+            intervals: code,
+            residuals: code,
+            ..CompFlags::default()
+        };
+        _test_body::<E, _>(tmp_path, &seq_graph, compression_flags)?;
+
+        // Check that the codes survive a round trip through the properties
+        let properties = compression_flags.to_properties::<E>(100, 0)?;
+        let map = properties
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let parsed = CompFlags::from_properties::<E>(&map)?;
+        assert_eq!(parsed.outdegrees, code);
+        assert_eq!(parsed.references, code);
+        assert_eq!(parsed.blocks, code);
+        assert_eq!(parsed.intervals, code);
+        assert_eq!(parsed.residuals, code);
+    }
+    std::fs::remove_file(tmp_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_bvcomp_split_codes() -> Result<()> {
+    let tmp_file = NamedTempFile::new()?;
+    let tmp_path = tmp_file.path();
+    let seq_graph = ErdosRenyi::new(100, 0.1, 0);
+    let compression_flags = CompFlags {
+        block_counts: Some(Unary),
+        interval_counts: Some(Delta),
+        interval_starts: Some(Pi { k: 2 }),
+        interval_lens: Some(Zeta { k: 2 }),
+        first_residuals: Some(ExpGolomb { k: 3 }),
+        min_interval_length: 1,
+        ..CompFlags::default()
+    };
+    _test_body::<BE, _>(tmp_path, &seq_graph, compression_flags)?;
+
+    let properties = compression_flags.to_properties::<BE>(100, 0)?;
+    let map = properties
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let parsed = CompFlags::from_properties::<BE>(&map)?;
+    assert_eq!(parsed.block_count_code(), Unary);
+    assert_eq!(parsed.blocks, Gamma);
+    assert_eq!(parsed.interval_count_code(), Delta);
+    assert_eq!(parsed.interval_start_code(), Pi { k: 2 });
+    assert_eq!(parsed.interval_len_code(), Zeta { k: 2 });
+    assert_eq!(parsed.intervals, Gamma);
+    assert_eq!(parsed.first_residual_code(), ExpGolomb { k: 3 });
+    assert_eq!(parsed.residuals, Zeta { k: 3 });
+
+    // Default flags must not use any split stream
+    let properties = CompFlags::default().to_properties::<BE>(100, 0)?;
+    assert!(properties.contains("compressionflags=\n"));
+    assert!(properties.contains("zetak=3\n"));
+    std::fs::remove_file(tmp_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_const_codes_param_mismatch() -> Result<()> {
+    use webgraph::graphs::bvgraph::const_codes::*;
+    let data = [0_u32; 4];
+    let reader = || <BufBitReader<BE, _>>::new(MemWordReader::new(&data[..]));
+    type Decoder<'a, const O: usize, const R: usize> = ConstCodesDecoder<
+        BE,
+        BufBitReader<BE, MemWordReader<u32, &'a [u32]>>,
+        O,
+        UNARY,
+        GAMMA,
+        GAMMA,
+        R,
+        3,
+    >;
+
+    let flags = |outdegrees, residuals| CompFlags {
+        outdegrees,
+        residuals,
+        ..CompFlags::default()
+    };
+
+    assert!(Decoder::<GAMMA, ZETA>::new(reader(), &flags(Gamma, Zeta { k: 3 })).is_ok());
+    assert!(Decoder::<GAMMA, ZETA>::new(reader(), &flags(Gamma, Zeta { k: 2 })).is_err());
+    assert!(Decoder::<GAMMA, PI>::new(reader(), &flags(Gamma, Pi { k: 3 })).is_ok());
+    assert!(Decoder::<GAMMA, PI>::new(reader(), &flags(Gamma, Pi { k: 2 })).is_err());
+    assert!(
+        Decoder::<GOLOMB, ZETA>::new(reader(), &flags(Golomb { b: 5 }, Zeta { k: 3 })).is_err()
+    );
+    // Different components needing different parameters
+    assert!(
+        Decoder::<GOLOMB, ZETA>::new(reader(), &flags(Golomb { b: 3 }, Zeta { k: 2 })).is_err()
+    );
+    assert!(Decoder::<GOLOMB, ZETA>::new(reader(), &flags(Golomb { b: 3 }, Zeta { k: 3 })).is_ok());
+    // Parameters of split streams are checked, too
+    let split = CompFlags {
+        first_residuals: Some(Zeta { k: 4 }),
+        ..CompFlags::default()
+    };
+    assert!(Decoder::<GAMMA, ZETA>::new(reader(), &split).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_bvcomp_optimal_refs() -> Result<()> {
+    let tmp_file = NamedTempFile::new()?;
+    let tmp_path = tmp_file.path();
+    let seq_graph = ErdosRenyi::new(100, 0.1, 0);
+    for compression_window in [0, 1, 3, 16] {
+        for max_ref_count in [0, 1, 3, usize::MAX] {
+            for chunk_size in [1, 7, 1000] {
+                let compression_flags = CompFlags {
+                    compression_window,
+                    max_ref_count,
+                    ref_selection: RefSelection::Optimal { chunk_size },
+                    ..CompFlags::default()
+                };
+                _test_body::<BE, _>(tmp_path, &seq_graph, compression_flags)?;
+            }
+        }
+    }
+    std::fs::remove_file(tmp_path)?;
+    Ok(())
+}
+
+#[test]
+fn test_bvcomp_optimal_refs_cnr() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let tmp_dir = tempfile::tempdir()?;
+    let greedy_basename = tmp_dir.path().join("greedy");
+    let optimal_basename = tmp_dir.path().join("optimal");
+
+    for max_ref_count in [1, 3] {
+        let compression_flags = CompFlags {
+            max_ref_count,
+            ..CompFlags::default()
+        };
+        let greedy_bits = BVComp::single_thread::<BE, _>(
+            &greedy_basename,
+            &graph,
+            compression_flags,
+            false,
+            None,
+        )?;
+        let optimal_bits = BVComp::single_thread::<BE, _>(
+            &optimal_basename,
+            &graph,
+            CompFlags {
+                ref_selection: RefSelection::Optimal { chunk_size: 1000 },
+                ..compression_flags
+            },
+            true,
+            None,
+        )?;
+        assert!(
+            optimal_bits <= greedy_bits,
+            "{} > {}",
+            optimal_bits,
+            greedy_bits
+        );
+
+        // Random access uses the offsets written during compression
+        let optimal = BVGraph::with_basename(&optimal_basename)
+            .endianness::<BE>()
+            .offsets_mode::<BuildEf>()
+            .load()?;
+        assert_eq!(optimal.num_arcs(), graph.num_arcs());
+        for node in 0..graph.num_nodes() {
+            assert_eq!(
+                optimal.successors(node).collect::<Vec<_>>(),
+                graph.successors(node).collect::<Vec<_>>()
+            );
+        }
+    }
+    Ok(())
+}
+
 fn _test_body<E: Endianness, P: AsRef<Path>>(
     tmp_path: P,
     seq_graph: &impl SequentialGraph,
@@ -82,19 +295,23 @@ where
     BufBitWriter<E, WordAdapter<usize, BufWriter<File>>>: CodeWrite<E>,
     BufBitReader<E, MemWordReader<u32, MmapHelper<u32>>>: CodeRead<E>,
 {
-    let writer = EncoderValidator::new(<DynCodesEncoder<E, _>>::new(
-        <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(File::create(
-            tmp_path.as_ref(),
-        )?))),
-        &compression_flags,
-    ));
+    let writer = EncoderValidator::new(
+        <DynCodesEncoder<E, _>>::new(
+            <BufBitWriter<E, _>>::new(<WordAdapter<usize, _>>::new(BufWriter::new(File::create(
+                tmp_path.as_ref(),
+            )?))),
+            &compression_flags,
+        ),
+        compression_flags.max_ref_count,
+    );
     let mut bvcomp = BVComp::new(
         writer,
         compression_flags.compression_window,
         compression_flags.max_ref_count,
         compression_flags.min_interval_length,
         0,
-    );
+    )
+    .with_ref_selection(compression_flags.ref_selection);
 
     let mut pl = ProgressLogger::default();
     pl.display_memory(true)
@@ -150,16 +367,21 @@ pub struct EncoderValidator<E: Encode> {
     flush: bool,
     // encoder has to be flushed, while estimator does not
     is_estimator: bool,
+    max_ref_count: usize,
+    // the length of the reference chain of each written node
+    ref_counts: Vec<usize>,
 }
 
 impl<E: Encode> EncoderValidator<E> {
-    pub fn new(encoder: E) -> Self {
+    pub fn new(encoder: E, max_ref_count: usize) -> Self {
         Self {
             encoder,
             start_nodes: 0,
             end_nodes: 0,
             flush: false,
             is_estimator: false,
+            max_ref_count,
+            ref_counts: vec![],
         }
     }
     pub fn new_estimator(encoder: E) -> Self {
@@ -169,6 +391,8 @@ impl<E: Encode> EncoderValidator<E> {
             end_nodes: 0,
             flush: false,
             is_estimator: true,
+            max_ref_count: usize::MAX,
+            ref_counts: vec![],
         }
     }
 }
@@ -187,6 +411,10 @@ impl<E: Encode> Encode for EncoderValidator<E> {
     fn start_node(&mut self, node: usize) -> std::prelude::v1::Result<usize, Self::Error> {
         assert_eq!(self.start_nodes, self.end_nodes);
         self.start_nodes += 1;
+        if !self.is_estimator {
+            assert_eq!(node, self.ref_counts.len());
+            self.ref_counts.push(0);
+        }
         self.encoder.start_node(node)
     }
     fn end_node(&mut self, node: usize) -> std::prelude::v1::Result<usize, Self::Error> {
@@ -206,6 +434,12 @@ impl<E: Encode> Encode for EncoderValidator<E> {
         &mut self,
         value: u64,
     ) -> std::prelude::v1::Result<usize, Self::Error> {
+        if !self.is_estimator && value != 0 {
+            let node = self.ref_counts.len() - 1;
+            let ref_count = self.ref_counts[node - value as usize] + 1;
+            assert!(ref_count <= self.max_ref_count, "reference chain too long");
+            self.ref_counts[node] = ref_count;
+        }
         self.encoder.write_reference_offset(value)
     }
     fn write_block_count(&mut self, value: u64) -> std::prelude::v1::Result<usize, Self::Error> {
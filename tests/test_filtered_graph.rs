@@ -0,0 +1,59 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::{
+    graphs::{bvgraph::sequential::BVGraphSeq, vec_graph::VecGraph, FilteredGraph},
+    prelude::*,
+};
+
+/// Remove loops and arcs between distant nodes.
+fn filter(src: usize, &dst: &usize) -> bool {
+    src != dst && src.abs_diff(dst) < 1000
+}
+
+#[test]
+fn test_filtered_parallel_compression() -> Result<()> {
+    let graph = BVGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut expected = VecGraph::new();
+    for node in 0..graph.num_nodes() {
+        expected.add_node(node);
+    }
+    for_!((src, succ) in graph.iter() {
+        for dst in succ {
+            if filter(src, &dst) {
+                expected.add_arc(src, dst);
+            }
+        }
+    });
+
+    // Sequential split
+    let filtered = FilteredGraph(graph, filter);
+    for num_threads in [1, 3] {
+        let split = filtered.split_iter(num_threads);
+        let result = common::compress_par(split.into_iter(), filtered.num_nodes(), num_threads)?;
+        assert_eq!(result, expected);
+    }
+
+    // Random-access split
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let filtered = FilteredGraph(graph, filter);
+    for num_threads in [1, 3] {
+        let split = filtered.split_iter(num_threads);
+        let result = common::compress_par(split.into_iter(), filtered.num_nodes(), num_threads)?;
+        assert_eq!(result, expected);
+    }
+    Ok(())
+}
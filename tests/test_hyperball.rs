@@ -0,0 +1,166 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::{build_dcf, transpose};
+use std::collections::VecDeque;
+use webgraph::{
+    algo::{HyperBallBuilder, HyperLogLogCounterArray},
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compute with a BFS from each node the exact neighborhood function, and
+/// the sum of distances and of inverse distances of each node.
+fn exact(graph: &impl RandomAccessGraph) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let n = graph.num_nodes();
+    let mut nf = vec![0.0; n];
+    let mut sum_of_distances = vec![0.0; n];
+    let mut harmonic = vec![0.0; n];
+    let mut max_dist = 0;
+    let mut dist = vec![usize::MAX; n];
+    for start in 0..n {
+        dist.fill(usize::MAX);
+        dist[start] = 0;
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let d = dist[node];
+            nf[d] += 1.0;
+            max_dist = max_dist.max(d);
+            if d != 0 {
+                sum_of_distances[start] += d as f64;
+                harmonic[start] += 1.0 / d as f64;
+            }
+            for succ in graph.successors(node) {
+                if dist[succ] == usize::MAX {
+                    dist[succ] = d + 1;
+                    queue.push_back(succ);
+                }
+            }
+        }
+    }
+    nf.truncate(max_dist + 1);
+    for t in 1..nf.len() {
+        nf[t] += nf[t - 1];
+    }
+    (nf, sum_of_distances, harmonic)
+}
+
+fn assert_close(a: &[f64], b: &[f64]) {
+    assert_eq!(a.len(), b.len());
+    for (&x, &y) in a.iter().zip(b) {
+        assert!((x - y).abs() <= 1E-9 * y.abs(), "{} != {}", x, y);
+    }
+}
+
+#[test]
+fn test_hyperloglog() {
+    let mut counters = HyperLogLogCounterArray::new(2, 10, 0);
+    assert_eq!(counters.estimate(0), 0.0);
+    for x in 0..10_000 {
+        counters.add(0, x);
+        counters.add(1, x % 100);
+    }
+    let rsd = HyperLogLogCounterArray::rsd(10);
+    assert!((counters.estimate(0) / 10_000.0 - 1.0).abs() < 3.0 * rsd);
+    assert!((counters.estimate(1) / 100.0 - 1.0).abs() < 3.0 * rsd);
+    assert_eq!(HyperLogLogCounterArray::log2m_for_rsd(rsd), 10);
+}
+
+#[test]
+fn test_hyperloglog_small_seed() {
+    // Elements smaller than the seed, and the seed itself, must be hashed
+    // like any other element
+    let seed = 42;
+    let mut counters = HyperLogLogCounterArray::new(1000, 10, seed);
+    for x in 0..1000 {
+        counters.add(x as usize, x);
+        // A register of value v is set with probability 2^-v
+        assert!(counters.counter(x as usize).iter().all(|&r| r < 32));
+    }
+    let mut counters = HyperLogLogCounterArray::new(1, 10, seed);
+    for x in 0..100 {
+        counters.add(0, x);
+    }
+    let rsd = HyperLogLogCounterArray::rsd(10);
+    assert!((counters.estimate(0) / 100.0 - 1.0).abs() < 3.0 * rsd);
+}
+
+#[test]
+fn test_hyperball() -> Result<()> {
+    let graph = Left(VecGraph::from_lender(ErdosRenyi::new(500, 0.004, 0).iter()));
+    let transpose = Left(transpose(&graph));
+    let deg_cumul = build_dcf(&graph);
+    let thread_pool = rayon::ThreadPoolBuilder::new().num_threads(4).build()?;
+    let (nf, sum_of_distances, harmonic) = exact(&graph);
+
+    let mut hyperball = HyperBallBuilder::new(&graph, &deg_cumul)
+        .log2m(10)
+        .seed(42)
+        .granularity(16)
+        .centralities(true)
+        .build();
+    hyperball.run_until_done(&thread_pool, None)?;
+
+    let approx_nf = hyperball.neighborhood_function();
+    // The last iteration detects that nothing changed
+    assert_eq!(approx_nf.len(), nf.len() + 1);
+    for (&a, &e) in approx_nf.iter().zip(nf.iter()) {
+        assert!((a / e - 1.0).abs() < 0.05, "{} != {}", a, e);
+    }
+
+    // Compare aggregated centralities (as all counters use the same hash
+    // function, errors are correlated)
+    let approx_sod = hyperball.sum_of_distances().unwrap();
+    let approx_harmonic = hyperball.harmonic_centrality().unwrap();
+    let total = |v: &[f64]| v.iter().sum::<f64>();
+    assert!((total(&approx_sod) / total(&sum_of_distances) - 1.0).abs() < 0.1);
+    assert!((total(&approx_harmonic) / total(&harmonic) - 1.0).abs() < 0.1);
+
+    let closeness = hyperball.closeness_centrality().unwrap();
+    let lin = hyperball.lin_centrality().unwrap();
+    let reachable = hyperball.reachable_nodes(&thread_pool);
+    for node in 0..graph.num_nodes() {
+        if graph.outdegree(node) == 0 {
+            assert_eq!(approx_sod[node], 0.0);
+            assert_eq!(closeness[node], 0.0);
+            assert_eq!(lin[node], 1.0);
+            assert!((reachable[node] - 1.0).abs() < 0.01);
+        }
+    }
+
+    // Systolic iterations must give exactly the same results
+    let mut systolic = HyperBallBuilder::new(&graph, &deg_cumul)
+        .transpose(&transpose)
+        .log2m(10)
+        .seed(42)
+        .granularity(16)
+        .centralities(true)
+        .build();
+    systolic.run_until_done(&thread_pool, None)?;
+    // (the neighborhood function is accumulated in parallel, so it might
+    // differ in the last bits)
+    assert_close(systolic.neighborhood_function(), approx_nf);
+    assert_eq!(systolic.sum_of_distances().unwrap(), approx_sod);
+    assert_eq!(systolic.harmonic_centrality().unwrap(), approx_harmonic);
+
+    // Stopping after a given number of iterations, or on a threshold
+    let mut bounded = HyperBallBuilder::new(&graph, &deg_cumul)
+        .log2m(10)
+        .seed(42)
+        .build();
+    bounded.run(2, None, &thread_pool, None)?;
+    assert_close(bounded.neighborhood_function(), &approx_nf[..3]);
+    assert!(bounded.harmonic_centrality().is_none());
+    bounded.run(usize::MAX, Some(0.5), &thread_pool, None)?;
+    assert!(bounded.neighborhood_function().len() < approx_nf.len());
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::symmetric_random;
+use dsi_progress_logger::prelude::*;
+use webgraph::{algo, graphs::vec_graph::VecGraph, labels::proj::Left, prelude::*};
+
+/// Compute core numbers by removing repeatedly a node of minimum degree.
+fn naive_cores(graph: &impl RandomAccessGraph) -> Vec<usize> {
+    let n = graph.num_nodes();
+    let mut degrees = (0..n)
+        .map(|node| {
+            graph
+                .successors(node)
+                .into_iter()
+                .filter(|&s| s != node)
+                .count()
+        })
+        .collect::<Vec<_>>();
+    let mut removed = vec![false; n];
+    let mut cores = vec![0; n];
+    let mut k = 0;
+    for _ in 0..n {
+        let node = (0..n)
+            .filter(|&node| !removed[node])
+            .min_by_key(|&node| degrees[node])
+            .unwrap();
+        k = k.max(degrees[node]);
+        cores[node] = k;
+        removed[node] = true;
+        for succ in graph.successors(node) {
+            if !removed[succ] {
+                degrees[succ] -= 1;
+            }
+        }
+    }
+    cores
+}
+
+/// Check that the order of a decomposition is a degeneracy ordering.
+fn check_order(graph: &impl RandomAccessGraph, kcores: &algo::KCores) {
+    let n = graph.num_nodes();
+    let perm = kcores.perm();
+    let mut seen = vec![false; n];
+    for (pos, &node) in kcores.order().iter().enumerate() {
+        assert!(!seen[node]);
+        seen[node] = true;
+        assert_eq!(perm[node], pos);
+        // Each node has at most as many later neighbors as its core number
+        let later = graph
+            .successors(node)
+            .into_iter()
+            .filter(|&s| perm[s] > pos)
+            .count();
+        assert!(later <= kcores.cores()[node]);
+    }
+    assert!(kcores
+        .order()
+        .windows(2)
+        .all(|w| kcores.cores()[w[0]] <= kcores.cores()[w[1]]));
+}
+
+#[test]
+fn test_random() -> Result<()> {
+    for (n, p) in [(1, 0.0), (10, 0.3), (100, 0.1), (1000, 0.01)] {
+        let graph = symmetric_random(n, p, 0);
+        let expected = naive_cores(&graph);
+        for num_threads in [1, 4] {
+            let mut pl = ProgressLogger::default();
+            let kcores = algo::kcore(&graph, Threads::Num(num_threads), Some(&mut pl));
+            assert_eq!(kcores.cores(), expected.as_slice());
+            assert_eq!(kcores.degeneracy(), *expected.iter().max().unwrap());
+            check_order(&graph, &kcores);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_clique() -> Result<()> {
+    // A complete graph on nodes 0-4, with a loop on node 0, a path 4, 5, 6,
+    // and an isolated node 7
+    let mut arcs = vec![(0, 0), (4, 5), (5, 4), (5, 6), (6, 5)];
+    for u in 0..5 {
+        for v in 0..5 {
+            if u != v {
+                arcs.push((u, v));
+            }
+        }
+    }
+    let mut graph = VecGraph::from_arc_list(arcs);
+    graph.add_node(7);
+    let graph = Left(graph);
+    let kcores = algo::kcore(&graph, Threads::Num(2), None);
+    assert_eq!(kcores.cores(), &[4, 4, 4, 4, 4, 1, 1, 0]);
+    assert_eq!(kcores.degeneracy(), 4);
+    assert_eq!(kcores.order()[0], 7);
+    check_order(&graph, &kcores);
+    Ok(())
+}
@@ -0,0 +1,171 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::labeled_graph;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use webgraph::graphs::bvgraph::BuildEf;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::labels::FixedWidth;
+use webgraph::prelude::*;
+
+type Writer = FileBitWriter<BE>;
+
+#[derive(Clone, Debug)]
+struct GammaSerDe;
+
+impl BitSerializer<BE, Writer> for GammaSerDe {
+    type SerType = usize;
+    fn serialize(
+        &self,
+        value: &Self::SerType,
+        bitstream: &mut Writer,
+    ) -> Result<usize, <Writer as BitWrite<BE>>::Error> {
+        bitstream.write_gamma(*value as u64)
+    }
+}
+
+impl BitDeserializer<BE, MmapBitReader<BE>> for GammaSerDe {
+    type DeserType = usize;
+    fn deserialize(
+        &self,
+        bitstream: &mut MmapBitReader<BE>,
+    ) -> Result<Self::DeserType, <MmapBitReader<BE> as BitRead<BE>>::Error> {
+        bitstream.read_gamma().map(|x| x as usize)
+    }
+}
+
+fn check(graph: &VecGraph<usize>, basename: &std::path::Path) -> Result<()> {
+    let labels = load_labels::<BE, _>(
+        basename,
+        graph.num_nodes(),
+        Some(graph.num_arcs()),
+        GammaSerDe,
+        MemoryFlags::empty(),
+    )?;
+    assert_eq!(labels.num_nodes(), graph.num_nodes());
+    assert_eq!(labels.num_arcs(), graph.num_arcs());
+
+    // random access
+    for node in 0..graph.num_nodes() {
+        assert_eq!(
+            labels.labels(node).collect::<Vec<_>>(),
+            RandomAccessLabeling::labels(graph, node)
+                .map(|(_, l)| l)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // random access zipped with the graph
+    let labeled = BVGraph::with_basename(basename)
+        .endianness::<BE>()
+        .offsets_mode::<BuildEf>()
+        .load_labeled(GammaSerDe)?;
+    for node in 0..graph.num_nodes() {
+        assert_eq!(
+            RandomAccessLabeling::labels(&labeled, node).collect::<Vec<_>>(),
+            RandomAccessLabeling::labels(graph, node).collect::<Vec<_>>()
+        );
+    }
+
+    // sequential access zipped with the graph
+    let seq_graph = BVGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let zipped = Zip(seq_graph, labels);
+    let mut iter = zipped.iter();
+    while let Some((node, succ)) = iter.next() {
+        assert_eq!(
+            succ.collect::<Vec<_>>(),
+            RandomAccessLabeling::labels(graph, node).collect::<Vec<_>>()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_labeled_bvcomp() -> Result<()> {
+    let graph = labeled_graph::<usize>();
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("labeled");
+
+    BVComp::single_thread_labeled::<BE, _, _>(
+        &basename,
+        &graph,
+        CompFlags::default(),
+        GammaSerDe,
+        true,
+        Some(graph.num_nodes()),
+    )?;
+    check(&graph, &basename)?;
+
+    // The parallel compressor does not write offsets, so we must not use
+    // the stale ones
+    std::fs::remove_file(basename.with_extension(OFFSETS_EXTENSION))?;
+    for threads in 1..5 {
+        BVComp::parallel_labeled_iter::<BE, _, _>(
+            &basename,
+            webgraph::traits::split::ra::Iter::new(&graph, threads),
+            graph.num_nodes(),
+            CompFlags::default(),
+            GammaSerDe,
+            Threads::Num(threads),
+            temp_dir(tmp_dir.path())?,
+        )?;
+        check(&graph, &basename)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_labeled_bvcomp_le() -> Result<()> {
+    let graph = labeled_graph::<u64>();
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("labeled");
+    let fixed_width = FixedWidth::new(5);
+
+    let check = |basename: &std::path::Path| -> Result<()> {
+        // The properties must be written with the endianness of the graph
+        assert_eq!(get_endianness(basename)?, LE::NAME);
+        let labeled = BVGraph::with_basename(basename)
+            .endianness::<LE>()
+            .offsets_mode::<BuildEf>()
+            .load_labeled(fixed_width)?;
+        for node in 0..graph.num_nodes() {
+            assert_eq!(
+                RandomAccessLabeling::labels(&labeled, node).collect::<Vec<_>>(),
+                RandomAccessLabeling::labels(&graph, node).collect::<Vec<_>>()
+            );
+        }
+        Ok(())
+    };
+
+    BVComp::single_thread_labeled::<LE, _, _>(
+        &basename,
+        &graph,
+        CompFlags::default(),
+        fixed_width,
+        false,
+        Some(graph.num_nodes()),
+    )?;
+    check(&basename)?;
+
+    BVComp::parallel_labeled_iter::<LE, _, _>(
+        &basename,
+        webgraph::traits::split::ra::Iter::new(&graph, 3),
+        graph.num_nodes(),
+        CompFlags::default(),
+        fixed_width,
+        Threads::Num(3),
+        temp_dir(tmp_dir.path())?,
+    )?;
+    check(&basename)?;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use anyhow::Result;
+use clap::Command;
+use common::{labeled_arcs, labeled_graph};
+use dsi_bitstream::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+use webgraph::cli::{recompress, simplify, transpose};
+use webgraph::graphs::bvgraph::BuildEf;
+use webgraph::labels::FixedWidth;
+use webgraph::prelude::*;
+
+const WIDTH: usize = 5;
+
+/// Return the labeled arcs of a compressed graph with fixed-width labels.
+fn load_arcs(basename: &Path) -> Result<Vec<(usize, usize, u64)>> {
+    let graph = BVGraph::with_basename(basename)
+        .endianness::<BE>()
+        .offsets_mode::<BuildEf>()
+        .load_labeled(FixedWidth::new(WIDTH))?;
+    Ok(labeled_arcs(&graph))
+}
+
+/// Run a command with the given arguments.
+fn run(
+    cli: fn(Command) -> Command,
+    main: fn(&clap::ArgMatches) -> Result<()>,
+    name: &str,
+    args: &[&str],
+) -> Result<()> {
+    let matches = cli(Command::new("webgraph"))
+        .get_matches_from(["webgraph", name].iter().chain(args.iter()));
+    main(matches.subcommand_matches(name).unwrap())
+}
+
+#[test]
+fn test_labeled_cli() -> Result<()> {
+    let graph = labeled_graph();
+    let n = graph.num_nodes();
+    let arcs = labeled_arcs(&graph);
+    let tmp_dir = tempfile::tempdir()?;
+    let path = |name: &str| tmp_dir.path().join(name);
+    let str = |name: &str| path(name).to_str().unwrap().to_owned();
+    let width = WIDTH.to_string();
+
+    BVComp::single_thread_labeled::<BE, _, _>(
+        path("graph"),
+        &graph,
+        CompFlags::default(),
+        FixedWidth::new(WIDTH),
+        true,
+        Some(n),
+    )?;
+    assert_eq!(load_arcs(&path("graph"))?, arcs);
+
+    // Transpose
+    run(
+        transpose::cli,
+        transpose::main,
+        transpose::COMMAND_NAME,
+        &[&str("graph"), &str("t"), "--label-width", &width],
+    )?;
+    let mut expected = arcs.iter().map(|&(x, y, l)| (y, x, l)).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(load_arcs(&path("t"))?, expected);
+
+    // Simplify, summing the labels of duplicate arcs
+    run(
+        simplify::cli,
+        simplify::main,
+        simplify::COMMAND_NAME,
+        &[
+            &str("graph"),
+            &str("s"),
+            "--label-width",
+            &width,
+            "--label-merge",
+            "sum",
+        ],
+    )?;
+    let max_label = FixedWidth::new(WIDTH).max_label();
+    let mut expected = BTreeMap::new();
+    for &(x, y, l) in &arcs {
+        if x != y {
+            for arc in [(x, y), (y, x)] {
+                expected
+                    .entry(arc)
+                    .and_modify(|e: &mut u64| *e = (*e + l).min(max_label))
+                    .or_insert(l);
+            }
+        }
+    }
+    let expected = expected
+        .into_iter()
+        .map(|((x, y), l)| (x, y, l))
+        .collect::<Vec<_>>();
+    assert_eq!(load_arcs(&path("s"))?, expected);
+
+    // Recompress, without and with a permutation
+    run(
+        recompress::cli,
+        recompress::main,
+        recompress::COMMAND_NAME,
+        &[&str("graph"), &str("r"), "--label-width", &width],
+    )?;
+    assert_eq!(load_arcs(&path("r"))?, arcs);
+
+    let perm = (0..n).map(|x| (x * 37 + 11) % n).collect::<Vec<_>>();
+    std::fs::write(
+        path("perm"),
+        perm.iter()
+            .flat_map(|&x| (x as u64).to_be_bytes())
+            .collect::<Vec<_>>(),
+    )?;
+    run(
+        recompress::cli,
+        recompress::main,
+        recompress::COMMAND_NAME,
+        &[
+            &str("graph"),
+            &str("p"),
+            "--label-width",
+            &width,
+            "--permutation",
+            &str("perm"),
+        ],
+    )?;
+    let mut expected = arcs
+        .iter()
+        .map(|&(x, y, l)| (perm[x], perm[y], l))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(load_arcs(&path("p"))?, expected);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::{labeled_arcs, labeled_graph};
+use lender::*;
+use std::collections::BTreeMap;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::labels::FixedWidth;
+use webgraph::prelude::*;
+use webgraph::transform;
+
+#[test]
+fn test_permute_labeled() -> Result<()> {
+    let graph = labeled_graph();
+    let n = graph.num_nodes();
+    let perm = (0..n).map(|x| (x * 37 + 11) % n).collect::<Vec<_>>();
+    let fixed_width = FixedWidth::new(5);
+    for batch_size in [10, 1000] {
+        let permuted =
+            transform::permute_labeled(&graph, &perm, batch_size, fixed_width, fixed_width)?;
+        assert_eq!(permuted.num_nodes(), n);
+        let mut expected = labeled_arcs(&graph)
+            .into_iter()
+            .map(|(x, y, l)| (perm[x], perm[y], l))
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(labeled_arcs(&permuted), expected);
+    }
+
+    // The unlabeled version must agree with the labeled one
+    let unlabeled = Left(VecGraph::from_lender(Left(graph.clone()).iter()));
+    let permuted = transform::permute(&unlabeled, &perm, 10)?;
+    let mut expected = labeled_arcs(&graph)
+        .into_iter()
+        .map(|(x, y, _)| (perm[x], perm[y]))
+        .collect::<Vec<_>>();
+    expected.sort();
+    let mut arcs = vec![];
+    for_!((x, succ) in permuted.iter() {
+        for y in succ {
+            arcs.push((x, y));
+        }
+    });
+    assert_eq!(arcs, expected);
+    Ok(())
+}
+
+#[test]
+fn test_simplify_labeled() -> Result<()> {
+    let graph = labeled_graph();
+    let n = graph.num_nodes();
+    let fixed_width = FixedWidth::new(8);
+    for merge in [u64::min as fn(u64, u64) -> u64, u64::max, |a, b| a + b] {
+        let simplified = transform::simplify_labeled(&graph, 10, fixed_width, fixed_width, merge)?;
+        assert_eq!(simplified.num_nodes(), n);
+
+        let mut expected = BTreeMap::new();
+        for (x, y, l) in labeled_arcs(&graph) {
+            if x != y {
+                for arc in [(x, y), (y, x)] {
+                    expected
+                        .entry(arc)
+                        .and_modify(|e| *e = merge(*e, l))
+                        .or_insert(l);
+                }
+            }
+        }
+        let expected = expected
+            .into_iter()
+            .map(|((x, y), l)| (x, y, l))
+            .collect::<Vec<_>>();
+        assert_eq!(labeled_arcs(&simplified), expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_transpose_fixed_width() -> Result<()> {
+    let graph = labeled_graph();
+    let fixed_width = FixedWidth::new(5);
+    let transposed = transform::transpose_labeled(&graph, 10, fixed_width, fixed_width)?;
+    let mut expected = labeled_arcs(&graph)
+        .into_iter()
+        .map(|(x, y, l)| (y, x, l))
+        .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(labeled_arcs(&transposed), expected);
+    Ok(())
+}
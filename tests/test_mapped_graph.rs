@@ -0,0 +1,95 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use epserde::prelude::*;
+use lender::*;
+use mmap_rs::MmapFlags;
+use std::collections::BTreeSet;
+use webgraph::{
+    graphs::{vec_graph::VecGraph, MappedGraph},
+    prelude::*,
+    utils::JavaPermutation,
+};
+
+/// Collapse cnr-2000 using a map loaded in ε-serde and Java format, compress
+/// the result in parallel and check it against a direct computation of the
+/// quotient.
+#[test]
+fn test_mapped_graph() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    // Groups of consecutive nodes, a rough proxy for hosts
+    let num_hosts = graph.num_nodes().div_ceil(100);
+    let map = (0..graph.num_nodes()).map(|x| x / 100).collect::<Vec<_>>();
+
+    let mut expected = vec![BTreeSet::new(); num_hosts];
+    for_!((x, succ) in graph.iter() {
+        expected[map[x]].extend(succ.map(|y| map[y]));
+    });
+    let mut expected_graph = VecGraph::new();
+    for host in 0..num_hosts {
+        expected_graph.add_node(host);
+    }
+    for (host, succ) in expected.iter().enumerate() {
+        for &s in succ {
+            expected_graph.add_arc(host, s);
+        }
+    }
+
+    let tmp_dir = tempfile::tempdir()?;
+    let epserde_path = tmp_dir.path().join("map.epserde");
+    map.store(&epserde_path)?;
+    let java_path = tmp_dir.path().join("map.java");
+    // Java permutations are sequences of big-endian 64-bit values
+    std::fs::write(
+        &java_path,
+        map.iter()
+            .flat_map(|&host| (host as u64).to_be_bytes())
+            .collect::<Vec<_>>(),
+    )?;
+
+    let epserde_map = <Vec<usize>>::mmap(&epserde_path, Flags::RANDOM_ACCESS)?;
+    let java_map = JavaPermutation::mmap(&java_path, MmapFlags::RANDOM_ACCESS)?;
+
+    let epserde_mapped = MappedGraph::new(&graph, &*epserde_map, num_hosts)?.sorted();
+    let java_mapped = MappedGraph::new(&graph, &java_map, num_hosts)?.sorted();
+    assert_eq!(VecGraph::from_lender(epserde_mapped.iter()), expected_graph);
+
+    for num_threads in [1, 3] {
+        let split = java_mapped.split_iter(num_threads);
+        let hosts = common::compress_par(split.into_iter(), num_hosts, num_threads)?;
+        assert_eq!(hosts, expected_graph);
+    }
+    Ok(())
+}
+
+/// Mapped graphs only hold references, so they can be cloned even if the
+/// underlying graph cannot (here, because its offsets are in a [`MemCase`]).
+#[test]
+fn test_mapped_graph_clone() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let num_hosts = graph.num_nodes().div_ceil(100);
+    let map = (0..graph.num_nodes()).map(|x| x / 100).collect::<Vec<_>>();
+    let mapped = MappedGraph::new(&graph, &map, num_hosts)?.sorted();
+    let expected = VecGraph::from_lender(mapped.iter());
+
+    assert_eq!(VecGraph::from_lender(mapped.clone().iter()), expected);
+    let mut iter = mapped.iter();
+    iter.next();
+    assert_eq!(
+        VecGraph::from_lender(iter.clone()),
+        VecGraph::from_lender(iter)
+    );
+    Ok(())
+}
@@ -0,0 +1,252 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::{build_dcf, transpose};
+use dsi_bitstream::prelude::BE;
+use lender::*;
+use predicates::prelude::*;
+use webgraph::{
+    algo::{
+        pagerank::{preds::L1Norm, Dangling, PageRank},
+        preds::MaxIters,
+    },
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compute PageRank with a straightforward sequential power method.
+fn naive_pagerank(
+    graph: &impl SequentialGraph,
+    alpha: f64,
+    preference: Option<&[f64]>,
+    dangling: Option<&[f64]>,
+) -> Vec<f64> {
+    let n = graph.num_nodes();
+    let uniform = vec![1.0 / n as f64; n];
+    let preference = preference.unwrap_or(&uniform);
+    let dangling = dangling.unwrap_or(&uniform);
+    let mut adj = vec![vec![]; n];
+    for_!( (node, succ) in graph.iter() {
+        adj[node].extend(succ);
+    });
+    let mut rank = uniform.clone();
+    loop {
+        let mut next = vec![0.0; n];
+        let mut dangling_rank = 0.0;
+        for (node, succ) in adj.iter().enumerate() {
+            if succ.is_empty() {
+                dangling_rank += rank[node];
+            }
+            for &s in succ {
+                next[s] += rank[node] / succ.len() as f64;
+            }
+        }
+        let mut delta = 0.0;
+        for node in 0..n {
+            next[node] = alpha * (next[node] + dangling_rank * dangling[node])
+                + (1.0 - alpha) * preference[node];
+            delta += (next[node] - rank[node]).abs();
+        }
+        rank = next;
+        if delta < 1E-14 {
+            return rank;
+        }
+    }
+}
+
+fn assert_close(a: &[f64], b: &[f64], eps: f64) {
+    assert_eq!(a.len(), b.len());
+    for (&x, &y) in a.iter().zip(b) {
+        assert!((x - y).abs() <= eps, "{} != {}", x, y);
+    }
+}
+
+#[test]
+fn test_pagerank_random() -> Result<()> {
+    for (n, p) in [(1, 0.0), (10, 0.1), (100, 0.01), (1000, 0.002)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        let transpose = Left(transpose(&graph));
+        let deg_cumul = build_dcf(&transpose);
+
+        // A preference vector concentrated on even nodes
+        let mut preference = (0..n)
+            .map(|node| ((node % 2 == 0) as usize) as f64)
+            .collect::<Vec<_>>();
+        let sum = preference.iter().sum::<f64>();
+        preference.iter_mut().for_each(|x| *x /= sum);
+
+        for alpha in [0.0, 0.5, 0.85] {
+            for (pref, dangling) in [
+                (None, Dangling::Uniform),
+                (Some(preference.as_slice()), Dangling::Uniform),
+                (Some(preference.as_slice()), Dangling::Preference),
+            ] {
+                let expected = naive_pagerank(
+                    &graph,
+                    alpha,
+                    pref,
+                    match dangling {
+                        Dangling::Preference => pref,
+                        _ => None,
+                    },
+                );
+                let mut pagerank = PageRank::new().alpha(alpha).dangling(dangling);
+                if let Some(pref) = pref {
+                    pagerank = pagerank.preference(pref);
+                }
+
+                for num_threads in [1, 4] {
+                    let rank = pagerank.run_seq(
+                        &graph,
+                        L1Norm::try_from(1E-14)?,
+                        Threads::Num(num_threads),
+                        None,
+                    )?;
+                    assert_close(&rank, &expected, 1E-12);
+
+                    let rank = pagerank.clone().granularity(16).run_transpose(
+                        &transpose,
+                        &deg_cumul,
+                        L1Norm::try_from(1E-14)?,
+                        Threads::Num(num_threads),
+                        None,
+                    )?;
+                    assert_close(&rank, &expected, 1E-12);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_pagerank_preds() -> Result<()> {
+    // 0 -> 1 -> 2, with 2 dangling
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2)]));
+    let rank = PageRank::new().alpha(0.5).run_seq(
+        &graph,
+        MaxIters::from(1).or(L1Norm::default()),
+        Threads::Num(1),
+        None,
+    )?;
+    // One iteration starting from the uniform vector
+    let third = 1.0 / 3.0;
+    assert_close(
+        &rank,
+        &[
+            0.5 * third * third + 0.5 * third,
+            0.5 * (third + third * third) + 0.5 * third,
+            0.5 * (third + third * third) + 0.5 * third,
+        ],
+        1E-15,
+    );
+
+    // Invalid parameters
+    assert!(PageRank::new()
+        .alpha(1.0)
+        .run_seq(&graph, L1Norm::default(), Threads::Num(1), None)
+        .is_err());
+    assert!(PageRank::new()
+        .preference(&[0.5, 0.5])
+        .run_seq(&graph, L1Norm::default(), Threads::Num(1), None)
+        .is_err());
+    assert!(PageRank::new()
+        .dangling(Dangling::Distribution(&[0.5, 0.5, 0.5]))
+        .run_seq(&graph, L1Norm::default(), Threads::Num(1), None)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_pagerank_cnr_2000() -> Result<()> {
+    let graph = BVGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let transpose = Left(transpose(&graph));
+    let deg_cumul = build_dcf(&transpose);
+
+    // A fixed number of iterations, so that the two methods are comparable
+    let seq = PageRank::new().run_seq(&graph, MaxIters::from(20), Threads::Num(4), None)?;
+    let pull = PageRank::new().run_transpose(
+        &transpose,
+        &deg_cumul,
+        MaxIters::from(20),
+        Threads::Num(4),
+        None,
+    )?;
+    assert!((seq.iter().sum::<f64>() - 1.0).abs() < 1E-9);
+    assert_close(&seq, &pull, 1E-12);
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_pagerank_cli_preference() -> Result<()> {
+    use clap::Command;
+    use webgraph::cli::pagerank;
+    use webgraph::cli::utils::{load_vec, store_vec};
+
+    let basename = "tests/data/cnr-2000";
+    let graph = BVGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+    let mut preference = (0..num_nodes)
+        .map(|node| (node % 10 + 1) as f64)
+        .collect::<Vec<_>>();
+    let sum = preference.iter().sum::<f64>();
+    preference.iter_mut().for_each(|p| *p /= sum);
+
+    let tmp_dir = tempfile::tempdir()?;
+    let preference_path = tmp_dir.path().join("preference");
+    let ranks_path = tmp_dir.path().join("ranks");
+    store_vec(preference.clone(), &preference_path, false)?;
+
+    let command = || pagerank::cli(Command::new("webgraph"));
+    // --strongly is meaningless without a preference vector
+    assert!(command()
+        .try_get_matches_from([
+            "webgraph",
+            pagerank::COMMAND_NAME,
+            basename,
+            ranks_path.to_str().unwrap(),
+            "--strongly",
+        ])
+        .is_err());
+
+    let matches = command().get_matches_from([
+        "webgraph",
+        pagerank::COMMAND_NAME,
+        basename,
+        ranks_path.to_str().unwrap(),
+        "--preference",
+        preference_path.to_str().unwrap(),
+        "--strongly",
+        "-i",
+        "20",
+        "--threshold",
+        "0",
+    ]);
+    pagerank::main(matches.subcommand_matches(pagerank::COMMAND_NAME).unwrap())?;
+    let ranks = load_vec::<f64>(&ranks_path, false)?;
+
+    let expected = PageRank::new()
+        .preference(&preference)
+        .dangling(Dangling::Preference)
+        .run_seq(&graph, MaxIters::from(20), Threads::Num(4), None)?;
+    assert_close(&ranks, &expected, 1E-12);
+    assert!(PageRank::new()
+        .run_seq(&graph, MaxIters::from(20), Threads::Num(4), None)?
+        .iter()
+        .zip(&expected)
+        .any(|(a, b)| (a - b).abs() > 1E-9));
+    Ok(())
+}
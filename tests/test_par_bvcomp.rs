@@ -96,3 +96,60 @@ fn _test_par_bvcomp(basename: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_par_bvcomp_optimize_codes() -> Result<()> {
+    let basename = "tests/data/cnr-2000";
+    let tmp_dir = tempfile::tempdir()?;
+    let tmp_basename = tmp_dir.path().join("cnr-2000-opt");
+
+    let graph = webgraph::graphs::bvgraph::sequential::BVGraphSeq::with_basename(basename)
+        .endianness::<BE>()
+        .load()?;
+
+    for sample_size in [Some(1000), None] {
+        let comp_flags =
+            BVComp::optimize_codes(&graph, CompFlags::default(), sample_size, Threads::Num(4))?;
+        BVComp::parallel_graph::<BE>(
+            &tmp_basename,
+            &graph,
+            comp_flags,
+            Threads::Num(4),
+            temp_dir(tmp_dir.path())?,
+        )?;
+
+        // The optimized flags must be recovered from the .properties file
+        let comp_graph = BVGraphSeq::with_basename(&tmp_basename)
+            .endianness::<BE>()
+            .load()?;
+        let mut iter = comp_graph.iter();
+        let mut iter_nodes = graph.iter();
+        while let Some((node, succ_iter)) = iter_nodes.next() {
+            let (new_node, new_succ_iter) = iter.next().unwrap();
+            assert_eq!(node, new_node);
+            assert_eq!(
+                succ_iter.collect::<Vec<_>>(),
+                new_succ_iter.collect::<Vec<_>>(),
+                "Node {} differs",
+                node
+            );
+        }
+    }
+
+    // Optimizing on the whole graph cannot be worse than the default codes
+    let expected_size = PathBuf::from(basename)
+        .with_extension(GRAPH_EXTENSION)
+        .metadata()?
+        .len();
+    let found_size = tmp_basename
+        .with_extension(GRAPH_EXTENSION)
+        .metadata()?
+        .len();
+    assert!(
+        found_size <= expected_size,
+        "{} > {}",
+        found_size,
+        expected_size
+    );
+    Ok(())
+}
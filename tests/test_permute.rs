@@ -0,0 +1,53 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use lender::*;
+use webgraph::graphs::vec_graph::VecGraph;
+use webgraph::prelude::*;
+use webgraph::transform;
+
+/// Return the arcs of a graph in the order of its lender.
+fn arcs(graph: &impl SequentialGraph) -> Vec<(usize, usize)> {
+    let mut arcs = vec![];
+    for_!((x, succ) in graph.iter() {
+        for y in succ {
+            arcs.push((x, y));
+        }
+    });
+    arcs
+}
+
+#[test]
+fn test_permute() -> Result<()> {
+    // A graph with no reciprocal arcs, so that a transpose cannot pass
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (0, 3),
+        (1, 2),
+        (2, 0),
+        (3, 4),
+        (4, 2),
+    ]));
+    let perm = [2, 0, 4, 1, 3];
+    for batch_size in [1, 10] {
+        let permuted = transform::permute(&graph, &perm, batch_size)?;
+        assert_eq!(permuted.num_nodes(), graph.num_nodes());
+        assert_eq!(
+            arcs(&permuted),
+            vec![(0, 4), (1, 3), (2, 0), (2, 1), (3, 4), (4, 2)]
+        );
+        // The result must agree with the permuted view
+        let mut expected = arcs(&PermutedGraph {
+            graph: &graph,
+            perm: &perm,
+        });
+        expected.sort();
+        assert_eq!(arcs(&permuted), expected);
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::*;
+use std::collections::HashMap;
+use webgraph::prelude::*;
+
+/// Parse the content of a `.properties` file.
+fn parse(properties: &str) -> HashMap<String, String> {
+    properties
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_residuals_round_trip() -> Result<()> {
+    for residuals in [Code::Unary, Code::Gamma, Code::Delta, Code::Zeta { k: 3 }] {
+        let flags = CompFlags {
+            residuals,
+            ..CompFlags::default()
+        };
+        let parsed = CompFlags::from_properties::<BE>(&parse(&flags.to_properties::<BE>(10, 0)?))?;
+        assert_eq!(parsed.residuals, residuals);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_zetak() -> Result<()> {
+    // As written by the Java version
+    let parsed = CompFlags::from_properties::<BE>(&parse(
+        "compressionflags=OUTDEGREES_ZETA|BLOCKS_ZETA\nzetak=2\n",
+    ))?;
+    assert_eq!(parsed.outdegrees, Code::Zeta { k: 2 });
+    assert_eq!(parsed.blocks, Code::Zeta { k: 2 });
+    assert_eq!(parsed.residuals, Code::Zeta { k: 2 });
+    assert_eq!(parsed.intervals, Code::Gamma);
+
+    // With an explicit parameter
+    let parsed = CompFlags::from_properties::<BE>(&parse(
+        "compressionflags=OUTDEGREES_ZETA5|RESIDUALS_ZETA\nzetak=2\n",
+    ))?;
+    assert_eq!(parsed.outdegrees, Code::Zeta { k: 5 });
+    assert_eq!(parsed.residuals, Code::Zeta { k: 2 });
+    Ok(())
+}
+
+#[test]
+fn test_malformed_flags() {
+    for flags in [
+        "OUTDEGREES",
+        "OUTDEGREES_FOO",
+        "FOO_GAMMA",
+        "OUTDEGREES_ZETAX",
+        // Parameters not supported by the decoders
+        "OUTDEGREES_ZETA9",
+        "OUTDEGREES_PI0",
+        "OUTDEGREES_PI5",
+        "BLOCKS_GOLOMB1",
+        "BLOCKS_EXPGOLOMB0",
+        "RESIDUALS_RICE8",
+    ] {
+        let map = parse(&format!("compressionflags={}\n", flags));
+        assert!(CompFlags::from_properties::<BE>(&map).is_err(), "{}", flags);
+    }
+}
+
+#[test]
+fn test_max_ref_count() -> Result<()> {
+    let parsed = CompFlags::from_properties::<BE>(&parse("maxrefcount=5\n"))?;
+    assert_eq!(parsed.max_ref_count, 5);
+    // The Java version uses -1 for unbounded chains
+    let parsed = CompFlags::from_properties::<BE>(&parse("maxrefcount=-1\n"))?;
+    assert_eq!(parsed.max_ref_count, usize::MAX);
+
+    // Unbounded chains are written as -1, too
+    let flags = CompFlags {
+        max_ref_count: usize::MAX,
+        ..CompFlags::default()
+    };
+    let properties = parse(&flags.to_properties::<BE>(10, 0)?);
+    assert_eq!(properties["maxrefcount"], "-1");
+    let parsed = CompFlags::from_properties::<BE>(&properties)?;
+    assert_eq!(parsed.max_ref_count, usize::MAX);
+    Ok(())
+}
@@ -0,0 +1,110 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_progress_logger::prelude::*;
+use lender::*;
+use webgraph::{
+    algo,
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+#[test]
+fn test_random() -> Result<()> {
+    for (n, p) in [(10, 0.3), (100, 0.05), (1000, 0.005)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        let mut num_arcs = 0;
+        let mut expected = vec![];
+        for_!((node, succ) in graph.iter() {
+            for s in succ {
+                num_arcs += 1;
+                if !graph.has_arc(s, node) {
+                    expected.push((node, s));
+                }
+            }
+        });
+
+        let mut pl = ProgressLogger::default();
+        let reciprocity = algo::reciprocity(&graph, 100, usize::MAX, Some(&mut pl))?;
+        assert_eq!(reciprocity.num_arcs(), num_arcs);
+        assert_eq!(
+            reciprocity.num_reciprocal(),
+            num_arcs - expected.len() as u64
+        );
+        assert_eq!(reciprocity.non_reciprocal_arcs(), expected.as_slice());
+        assert_eq!(reciprocity.is_symmetric(), expected.is_empty());
+
+        let reciprocity = algo::reciprocity(&graph, 100, 3, None)?;
+        assert_eq!(reciprocity.non_reciprocal_arcs(), &expected[..3]);
+
+        // Symmetrize
+        let mut sym = VecGraph::empty(n);
+        for_!((node, succ) in graph.iter() {
+            for s in succ {
+                sym.add_arc(node, s);
+                sym.add_arc(s, node);
+            }
+        });
+        let reciprocity = algo::reciprocity(&Left(sym), 100, 10, None)?;
+        assert!(reciprocity.is_symmetric());
+        assert_eq!(reciprocity.reciprocity(), 1.0);
+        assert!(reciprocity.non_reciprocal_arcs().is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_small() -> Result<()> {
+    // Loops are reciprocal
+    let graph = Left(VecGraph::from_arc_list([(0, 0), (0, 1), (1, 0), (1, 2)]));
+    let reciprocity = algo::reciprocity(&graph, 10, 10, None)?;
+    assert!(!reciprocity.is_symmetric());
+    assert_eq!(reciprocity.num_arcs(), 4);
+    assert_eq!(reciprocity.num_reciprocal(), 3);
+    assert_eq!(reciprocity.reciprocity(), 0.75);
+    assert_eq!(reciprocity.non_reciprocal_arcs(), &[(1, 2)]);
+
+    let graph = Left(VecGraph::<()>::empty(3));
+    let reciprocity = algo::reciprocity(&graph, 10, 10, None)?;
+    assert!(reciprocity.is_symmetric());
+    assert_eq!(reciprocity.reciprocity(), 1.0);
+    Ok(())
+}
+
+#[test]
+fn test_check_symmetry() -> Result<()> {
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 0), (1, 2)]));
+    let err = algo::llp::check_symmetry(&graph, None).unwrap_err();
+    assert!(err.to_string().contains("1 -> 2"));
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 0), (1, 2), (2, 1)]));
+    algo::llp::check_symmetry(&graph, None)?;
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_llp_check_symmetry() -> Result<()> {
+    use clap::Command;
+    use webgraph::cli::llp;
+
+    // cnr-2000 is not symmetric
+    let tmp_dir = tempfile::tempdir()?;
+    let perm = tmp_dir.path().join("perm");
+    let matches = llp::cli(Command::new("webgraph")).get_matches_from([
+        "webgraph",
+        llp::COMMAND_NAME,
+        "tests/data/cnr-2000",
+        perm.to_str().unwrap(),
+        "--check-symmetry",
+    ]);
+    let err = llp::main(matches.subcommand_matches(llp::COMMAND_NAME).unwrap()).unwrap_err();
+    assert!(err.to_string().contains("not symmetric"));
+    assert!(!perm.exists());
+    Ok(())
+}
@@ -9,8 +9,6 @@
 use anyhow::Result;
 #[cfg(feature = "fuzz")]
 use webgraph::fuzz::bvcomp_and_read::*;
-#[cfg(feature = "fuzz")]
-use webgraph::prelude::*;
 
 #[test]
 #[cfg(feature = "fuzz")]
@@ -22,9 +20,15 @@ fn test_regression_le() -> Result<()> {
             blocks: CodeFuzz::Unary,
             intervals: CodeFuzz::Unary,
             residuals: CodeFuzz::Unary,
+            block_counts: None,
+            interval_counts: None,
+            interval_starts: None,
+            interval_lens: None,
+            first_residuals: None,
             min_interval_length: 248,
             compression_window: 255,
             max_ref_count: 255,
+            optimal_refs_chunk_size: None,
         },
         edges: vec![(2, 187)],
     };
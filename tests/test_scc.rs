@@ -0,0 +1,136 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::BE;
+use webgraph::{
+    algo::{scc, Sccs},
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Check that the components are in reverse topological order and that
+/// sizes are consistent.
+fn check_order(graph: &impl RandomAccessGraph, sccs: &Sccs) {
+    let components = sccs.components();
+    assert_eq!(components.len(), graph.num_nodes());
+    for node in 0..graph.num_nodes() {
+        assert!(components[node] < sccs.num_components());
+        for succ in graph.successors(node) {
+            assert!(components[node] >= components[succ]);
+        }
+    }
+    let sizes = sccs.compute_sizes();
+    assert_eq!(sizes.len(), sccs.num_components());
+    assert!(sizes.iter().all(|&s| s > 0));
+    assert_eq!(sizes.iter().sum::<usize>(), graph.num_nodes());
+}
+
+/// Return the reachability matrix of a graph.
+fn reachability(graph: &impl RandomAccessGraph) -> Vec<Vec<bool>> {
+    let n = graph.num_nodes();
+    let mut reach = vec![vec![false; n]; n];
+    for (start, reach) in reach.iter_mut().enumerate() {
+        let mut stack = vec![start];
+        reach[start] = true;
+        while let Some(node) = stack.pop() {
+            for succ in graph.successors(node) {
+                if !reach[succ] {
+                    reach[succ] = true;
+                    stack.push(succ);
+                }
+            }
+        }
+    }
+    reach
+}
+
+#[test]
+fn test_scc_small() {
+    // 0 -> 1 -> 2 -> 0, 2 -> 3, 3 -> 4 -> 3, 5
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (1, 2),
+        (2, 0),
+        (2, 3),
+        (3, 4),
+        (4, 3),
+        (5, 5),
+    ]));
+    let mut sccs = scc(&graph, None);
+    assert_eq!(sccs.num_components(), 3);
+    assert_eq!(sccs.components(), &[1, 1, 1, 0, 0, 2]);
+    check_order(&graph, &sccs);
+
+    assert_eq!(sccs.sort_by_size().as_ref(), &[3, 2, 1]);
+    assert_eq!(sccs.components(), &[0, 0, 0, 1, 1, 2]);
+}
+
+#[test]
+fn test_scc_random() {
+    for (n, p) in [(1, 0.0), (10, 0.1), (100, 0.01), (100, 0.02), (200, 0.05)] {
+        for seed in 0..5 {
+            let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, seed).iter()));
+            let sccs = scc(&graph, None);
+            check_order(&graph, &sccs);
+            let reach = reachability(&graph);
+            let components = sccs.components();
+            for x in 0..n {
+                for y in 0..n {
+                    assert_eq!(
+                        components[x] == components[y],
+                        reach[x][y] && reach[y][x],
+                        "n = {}, p = {}, seed = {}, x = {}, y = {}",
+                        n,
+                        p,
+                        seed,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_scc_deep() {
+    // A long cycle and a long path, which would overflow the stack of a
+    // recursive implementation
+    let n = 1_000_000;
+    let graph = Left(VecGraph::from_arc_list((0..n).map(|x| (x, (x + 1) % n))));
+    let sccs = scc(&graph, None);
+    assert_eq!(sccs.num_components(), 1);
+
+    let graph = Left(VecGraph::from_arc_list((0..n - 1).map(|x| (x, x + 1))));
+    let sccs = scc(&graph, None);
+    assert_eq!(sccs.num_components(), n);
+    check_order(&graph, &sccs);
+}
+
+#[test]
+fn test_scc_cnr_2000() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mut sccs = scc(&graph, None);
+    check_order(&graph, &sccs);
+
+    // The transpose has the same components
+    let mut transpose = VecGraph::empty(graph.num_nodes());
+    for node in 0..graph.num_nodes() {
+        for succ in graph.successors(node) {
+            transpose.add_arc(succ, node);
+        }
+    }
+    let transpose = Left(transpose);
+    let mut t_sccs = scc(&transpose, None);
+    assert_eq!(sccs.num_components(), t_sccs.num_components());
+    assert_eq!(sccs.sort_by_size(), t_sccs.sort_by_size());
+    Ok(())
+}
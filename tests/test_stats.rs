@@ -0,0 +1,134 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+#![cfg(feature = "cli")]
+
+use anyhow::Result;
+use clap::Command;
+use dsi_bitstream::prelude::*;
+use lender::*;
+use std::collections::HashMap;
+use std::io::BufReader;
+use webgraph::cli::stats;
+use webgraph::prelude::*;
+
+#[test]
+fn test_stats() -> Result<()> {
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let num_nodes = graph.num_nodes();
+
+    let tmp_dir = tempfile::tempdir()?;
+    let output = tmp_dir.path().join("cnr-2000");
+    let matches = stats::cli(Command::new("webgraph")).get_matches_from([
+        "webgraph",
+        stats::COMMAND_NAME,
+        "tests/data/cnr-2000",
+        "-o",
+        output.to_str().unwrap(),
+    ]);
+    stats::main(matches.subcommand_matches(stats::COMMAND_NAME).unwrap())?;
+
+    let map: HashMap<String, String> = java_properties::read(BufReader::new(std::fs::File::open(
+        output.with_extension(stats::STATS_EXTENSION),
+    )?))?;
+    let get = |key: &str| map.get(key).unwrap().parse::<usize>().unwrap();
+
+    let mut indegrees = vec![0; num_nodes];
+    let mut loops = 0;
+    let mut dangling = 0;
+    let mut max_outdegree = 0;
+    for_!((node, succ) in graph.iter() {
+        let mut outdegree = 0;
+        for s in succ {
+            indegrees[s] += 1;
+            outdegree += 1;
+            if s == node {
+                loops += 1;
+            }
+        }
+        if outdegree == 0 {
+            dangling += 1;
+        }
+        max_outdegree = max_outdegree.max(outdegree);
+    });
+
+    assert_eq!(get("nodes"), num_nodes);
+    assert_eq!(get("arcs"), graph.num_arcs() as usize);
+    assert_eq!(get("loops"), loops);
+    assert_eq!(get("dangling"), dangling);
+    assert_eq!(get("maxoutdegree"), max_outdegree);
+    assert_eq!(get("maxindegree"), *indegrees.iter().max().unwrap());
+    assert_eq!(get("minindegree"), *indegrees.iter().min().unwrap());
+    assert_eq!(
+        graph.outdegree(get("maxoutdegreenode")),
+        get("maxoutdegree")
+    );
+    assert_eq!(indegrees[get("maxindegreenode")], get("maxindegree"));
+
+    // The graph file contains padding, so bits per link are approximate
+    let graph_bits = std::fs::metadata("tests/data/cnr-2000.graph")?.len() * 8;
+    let bits_per_link = map.get("bitsperlink").unwrap().parse::<f64>()?;
+    assert!(bits_per_link <= graph_bits as f64 / graph.num_arcs() as f64);
+    assert!(bits_per_link >= (graph_bits - 64) as f64 / graph.num_arcs() as f64);
+    assert!(map.get("loggapcost").unwrap().parse::<f64>()? > 0.0);
+
+    // Histograms
+    let outdegree = std::fs::read_to_string(output.with_extension(stats::OUTDEGREE_EXTENSION))?
+        .lines()
+        .map(|line| line.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(outdegree.len(), max_outdegree + 1);
+    assert_eq!(outdegree.iter().sum::<usize>(), num_nodes);
+    assert_eq!(outdegree[0], dangling);
+    let indegree = std::fs::read_to_string(output.with_extension(stats::INDEGREE_EXTENSION))?
+        .lines()
+        .map(|line| line.parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        indegree
+            .iter()
+            .enumerate()
+            .map(|(d, &c)| d * c)
+            .sum::<usize>(),
+        graph.num_arcs() as usize
+    );
+    Ok(())
+}
+
+#[test]
+fn test_stats_without_ef() -> Result<()> {
+    // A freshly compressed graph has no .ef file
+    let tmp_dir = tempfile::tempdir()?;
+    let basename = tmp_dir.path().join("cnr-2000");
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    BVComp::single_thread::<BE, _>(&basename, &graph, CompFlags::default(), true, None)?;
+    assert!(!basename.with_extension(EF_EXTENSION).exists());
+
+    let output = tmp_dir.path().join("stats");
+    let matches = stats::cli(Command::new("webgraph")).get_matches_from([
+        "webgraph",
+        stats::COMMAND_NAME,
+        basename.to_str().unwrap(),
+        "-o",
+        output.to_str().unwrap(),
+    ]);
+    stats::main(matches.subcommand_matches(stats::COMMAND_NAME).unwrap())?;
+
+    let map: HashMap<String, String> = java_properties::read(BufReader::new(std::fs::File::open(
+        output.with_extension(stats::STATS_EXTENSION),
+    )?))?;
+    assert_eq!(
+        map.get("nodes").unwrap().parse::<usize>()?,
+        graph.num_nodes()
+    );
+    assert_eq!(map.get("arcs").unwrap().parse::<u64>()?, graph.num_arcs());
+    Ok(())
+}
@@ -0,0 +1,178 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use lender::*;
+use webgraph::{
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph, MaskedGraph},
+    labels::proj::Left,
+    prelude::*,
+    transform,
+};
+
+/// Return the subgraph induced by `mask` computed naively, together with the
+/// map from its nodes to the nodes of the graph.
+fn naive_subgraph(graph: &Left<VecGraph>, mask: &[bool]) -> (Left<VecGraph>, Vec<usize>) {
+    let nodes = (0..graph.num_nodes())
+        .filter(|&x| mask[x])
+        .collect::<Vec<_>>();
+    let mut map = vec![usize::MAX; graph.num_nodes()];
+    for (i, &x) in nodes.iter().enumerate() {
+        map[x] = i;
+    }
+    let mut subgraph = VecGraph::new();
+    for i in 0..nodes.len() {
+        subgraph.add_node(i);
+    }
+    for (i, &x) in nodes.iter().enumerate() {
+        for y in graph.successors(x) {
+            if mask[y] {
+                subgraph.add_arc(i, map[y]);
+            }
+        }
+    }
+    (Left(subgraph), nodes)
+}
+
+fn check_subgraph(graph: &Left<VecGraph>, mask: &[bool]) -> Result<()> {
+    let (expected, nodes) = naive_subgraph(graph, mask);
+    let subgraph = transform::subgraph(graph, mask);
+    assert_eq!(subgraph.nodes(), nodes.as_slice());
+    assert_eq!(subgraph.num_nodes(), nodes.len());
+    assert_eq!(VecGraph::from_lender(subgraph.iter()), expected.0);
+    assert_eq!(subgraph.num_arcs(), expected.num_arcs());
+
+    // Starting from any node
+    for from in 0..subgraph.num_nodes() {
+        let mut iter = subgraph.iter_from(from);
+        for x in from..subgraph.num_nodes() {
+            let (node, succ) = iter.next().unwrap();
+            assert_eq!(node, x);
+            assert!(succ.eq(expected.successors(x)));
+        }
+        assert!(iter.next().is_none());
+    }
+
+    // Random access
+    for x in 0..subgraph.num_nodes() {
+        assert!(subgraph.successors(x).eq(expected.successors(x)));
+        assert_eq!(subgraph.outdegree(x), expected.outdegree(x));
+    }
+
+    // From a list of nodes
+    let from_nodes = transform::subgraph_from_nodes(graph, nodes.iter().copied())?;
+    assert_eq!(VecGraph::from_lender(from_nodes.iter()), expected.0);
+    assert_eq!(from_nodes.into_nodes().as_ref(), nodes.as_slice());
+
+    // Without renumbering
+    let masked = MaskedGraph(graph.clone(), mask.to_vec());
+    assert_eq!(masked.num_nodes(), graph.num_nodes());
+    for_!((x, succ) in masked.iter() {
+        let succ = succ.collect::<Vec<_>>();
+        if mask[x] {
+            let i = nodes.binary_search(&x).unwrap();
+            assert!(succ.iter().copied().eq(expected.successors(i).into_iter().map(|y| nodes[y])));
+        } else {
+            assert!(succ.is_empty());
+        }
+    });
+    Ok(())
+}
+
+#[test]
+fn test_subgraph() -> Result<()> {
+    for (n, p) in [(10, 0.5), (100, 0.1), (1000, 0.01)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        check_subgraph(&graph, &vec![true; n])?;
+        check_subgraph(&graph, &vec![false; n])?;
+        check_subgraph(&graph, &(0..n).map(|x| x % 3 != 0).collect::<Vec<_>>())?;
+        check_subgraph(&graph, &(0..n).map(|x| x < n / 2).collect::<Vec<_>>())?;
+        check_subgraph(&graph, &(0..n).map(|x| x >= n / 2).collect::<Vec<_>>())?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_subgraph_from_nodes_errors() {
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0)]));
+    assert!(transform::subgraph_from_nodes(&graph, [0, 3]).is_err());
+    assert!(transform::subgraph_from_nodes(&graph, [1, 0]).is_err());
+    assert!(transform::subgraph_from_nodes(&graph, [1, 1]).is_err());
+    assert!(transform::subgraph_from_nodes(&graph, [0, 2]).is_ok());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_subgraph_cli() -> Result<()> {
+    use clap::Command;
+    use dsi_bitstream::prelude::*;
+    use std::io::Write;
+    use webgraph::cli::subgraph;
+    use webgraph::graphs::bvgraph::sequential::BVGraphSeq;
+
+    let graph = BVGraph::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let mask = (0..graph.num_nodes())
+        .map(|x| x % 7 == 0 || x % 5 == 1)
+        .collect::<Vec<_>>();
+
+    let tmp_dir = tempfile::tempdir()?;
+    let nodes_path = tmp_dir.path().join("nodes.txt");
+    let mut nodes_file = std::fs::File::create(&nodes_path)?;
+    // In reverse order, and with duplicates
+    for x in (0..graph.num_nodes()).rev().filter(|&x| mask[x]) {
+        writeln!(nodes_file, "{}", x)?;
+        if x % 2 == 0 {
+            writeln!(nodes_file, "{}", x)?;
+        }
+    }
+    drop(nodes_file);
+
+    let expected = transform::subgraph(&graph, mask.as_slice());
+    let dst = tmp_dir.path().join("sub");
+    let map = tmp_dir.path().join("sub.map");
+    let matches = subgraph::cli(Command::new("webgraph")).get_matches_from([
+        "webgraph",
+        subgraph::COMMAND_NAME,
+        "tests/data/cnr-2000",
+        nodes_path.to_str().unwrap(),
+        dst.to_str().unwrap(),
+        "-m",
+        map.to_str().unwrap(),
+    ]);
+    subgraph::main(matches.subcommand_matches(subgraph::COMMAND_NAME).unwrap())?;
+    let sub = BVGraphSeq::with_basename(&dst).endianness::<BE>().load()?;
+    assert_eq!(sub.num_nodes(), expected.num_nodes());
+    assert_eq!(
+        VecGraph::from_lender(sub.iter()),
+        VecGraph::from_lender(expected.iter())
+    );
+    let map = std::fs::read(map)?
+        .chunks_exact(8)
+        .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()) as usize)
+        .collect::<Vec<_>>();
+    assert_eq!(map, expected.nodes());
+
+    let dst = tmp_dir.path().join("masked");
+    let matches = subgraph::cli(Command::new("webgraph")).get_matches_from([
+        "webgraph",
+        subgraph::COMMAND_NAME,
+        "tests/data/cnr-2000",
+        nodes_path.to_str().unwrap(),
+        dst.to_str().unwrap(),
+        "--no-renumber",
+    ]);
+    subgraph::main(matches.subcommand_matches(subgraph::COMMAND_NAME).unwrap())?;
+    let masked = BVGraphSeq::with_basename(&dst).endianness::<BE>().load()?;
+    assert_eq!(masked.num_nodes(), graph.num_nodes());
+    assert_eq!(
+        VecGraph::from_lender(masked.iter()),
+        VecGraph::from_lender(MaskedGraph(graph, mask).iter())
+    );
+    Ok(())
+}
@@ -0,0 +1,156 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::transpose;
+use std::collections::VecDeque;
+use webgraph::{
+    algo::sumsweep::{Level, SumSweep},
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compute the eccentricity of each node with a BFS.
+fn eccentricities(graph: &impl RandomAccessGraph) -> Vec<usize> {
+    let n = graph.num_nodes();
+    let mut dist = vec![usize::MAX; n];
+    (0..n)
+        .map(|start| {
+            dist.fill(usize::MAX);
+            dist[start] = 0;
+            let mut ecc = 0;
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                ecc = dist[node];
+                for succ in graph.successors(node) {
+                    if dist[succ] == usize::MAX {
+                        dist[succ] = dist[node] + 1;
+                        queue.push_back(succ);
+                    }
+                }
+            }
+            ecc
+        })
+        .collect()
+}
+
+/// A random strongly connected graph: a cycle plus random arcs.
+fn random_scc(n: usize, p: f64, seed: u64) -> VecGraph<()> {
+    let mut graph = VecGraph::from_lender(ErdosRenyi::new(n, p, seed).iter());
+    for node in 0..n {
+        graph.add_arc(node, (node + 1) % n);
+    }
+    graph
+}
+
+#[test]
+fn test_sumsweep_random() -> Result<()> {
+    for (n, p) in [(1, 0.0), (2, 0.0), (10, 0.1), (100, 0.01), (500, 0.005)] {
+        for seed in 0..3 {
+            let graph = Left(random_scc(n, p, seed));
+            let transpose = Left(transpose(&graph));
+            let forward = eccentricities(&graph);
+            let backward = eccentricities(&transpose);
+            let radius = *forward.iter().min().unwrap();
+            let diameter = *forward.iter().max().unwrap();
+
+            for level in [
+                Level::Radius,
+                Level::Diameter,
+                Level::RadiusDiameter,
+                Level::AllForward,
+                Level::All,
+            ] {
+                let mut sumsweep = SumSweep::new(&graph, &transpose, level);
+                sumsweep.run(None)?;
+                assert!(sumsweep.is_done());
+                assert!(sumsweep.num_visits() <= 2 * n);
+
+                if level != Level::Diameter {
+                    assert_eq!(sumsweep.radius(), Some(radius));
+                    assert_eq!(forward[sumsweep.radial_vertex()], radius);
+                }
+                if level != Level::Radius {
+                    assert_eq!(sumsweep.diameter(), Some(diameter));
+                    let v = sumsweep.diametral_vertex();
+                    assert!(forward[v] == diameter || backward[v] == diameter);
+                }
+                if matches!(level, Level::AllForward | Level::All) {
+                    for (node, &ecc) in forward.iter().enumerate() {
+                        assert_eq!(sumsweep.forward_eccentricity(node), Some(ecc));
+                    }
+                }
+                if level == Level::All {
+                    for (node, &ecc) in backward.iter().enumerate() {
+                        assert_eq!(sumsweep.backward_eccentricity(node), Some(ecc));
+                    }
+                }
+
+                // Bounds are always consistent
+                for node in 0..n {
+                    let (low, high) = sumsweep.forward_eccentricity_bounds(node);
+                    assert!(low <= forward[node] && forward[node] <= high);
+                    let (low, high) = sumsweep.backward_eccentricity_bounds(node);
+                    assert!(low <= backward[node] && backward[node] <= high);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_sumsweep_bounds() -> Result<()> {
+    let graph = Left(random_scc(1000, 0.002, 0));
+    let transpose = Left(transpose(&graph));
+    let forward = eccentricities(&graph);
+    let radius = *forward.iter().min().unwrap();
+    let diameter = *forward.iter().max().unwrap();
+
+    let mut sumsweep = SumSweep::new(&graph, &transpose, Level::RadiusDiameter);
+    while !sumsweep.step()? {
+        assert!(sumsweep.radius_lower_bound() <= radius);
+        assert!(radius <= sumsweep.radius_upper_bound());
+        assert!(sumsweep.diameter_lower_bound() <= diameter);
+        assert!(diameter <= sumsweep.diameter_upper_bound());
+    }
+    // Further steps do nothing
+    let num_visits = sumsweep.num_visits();
+    assert!(sumsweep.step()?);
+    assert_eq!(sumsweep.num_visits(), num_visits);
+    assert_eq!(sumsweep.radius(), Some(radius));
+    assert_eq!(sumsweep.diameter(), Some(diameter));
+    Ok(())
+}
+
+#[test]
+fn test_sumsweep_symmetric() -> Result<()> {
+    // A path of length 10 is symmetric, with radius 5 and diameter 10
+    let mut arcs = vec![];
+    for node in 0..10 {
+        arcs.push((node, node + 1));
+        arcs.push((node + 1, node));
+    }
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let mut sumsweep = SumSweep::new(&graph, &graph, Level::RadiusDiameter);
+    sumsweep.run(None)?;
+    assert_eq!(sumsweep.radius(), Some(5));
+    assert_eq!(sumsweep.radial_vertex(), 5);
+    assert_eq!(sumsweep.diameter(), Some(10));
+    Ok(())
+}
+
+#[test]
+fn test_sumsweep_not_strongly_connected() {
+    let graph = Left(VecGraph::from_arc_list([(0, 1), (1, 2)]));
+    let transpose = Left(transpose(&graph));
+    let mut sumsweep = SumSweep::new(&graph, &transpose, Level::Diameter);
+    assert!(sumsweep.run(None).is_err());
+}
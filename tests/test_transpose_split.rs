@@ -0,0 +1,46 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use webgraph::{
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+    transform,
+};
+
+/// Transpose a graph with `transpose_split`, compress it in parallel, and
+/// check it against the result of `transpose`.
+fn check_transpose_split(graph: &Left<VecGraph>, batch_size: usize) -> Result<()> {
+    let expected = VecGraph::from_lender(&transform::transpose(graph, batch_size)?);
+    for num_threads in [1, 2, 4, 7] {
+        let split = transform::transpose_split(graph, batch_size, Threads::Num(num_threads))?;
+        let transposed = common::compress_par(split.into_iter(), graph.num_nodes(), num_threads)?;
+        assert_eq!(transposed, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_random() -> Result<()> {
+    for (n, p) in [(10, 0.5), (100, 0.1), (1000, 0.02)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        check_transpose_split(&graph, 100)?;
+        check_transpose_split(&graph, 10_000)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_few_nodes() -> Result<()> {
+    // Fewer nodes than threads, with a dangling node at the end
+    let mut graph = VecGraph::from_arc_list([(0, 1), (1, 0), (1, 2), (2, 2)]);
+    graph.add_node(3);
+    check_transpose_split(&Left(graph), 10)
+}
@@ -0,0 +1,123 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+mod common;
+
+use anyhow::Result;
+use common::{build_dcf, symmetric_random};
+use dsi_progress_logger::prelude::*;
+use webgraph::{algo, graphs::vec_graph::VecGraph, labels::proj::Left, prelude::*};
+
+/// Count the triangles of each node by brute force.
+fn naive_triangles(graph: &impl RandomAccessGraph) -> Vec<usize> {
+    let n = graph.num_nodes();
+    let mut triangles = vec![0; n];
+    for u in 0..n {
+        for v in graph.successors(u).into_iter().filter(|&v| v > u) {
+            for w in graph.successors(v).into_iter().filter(|&w| w > v) {
+                if graph.has_arc(u, w) {
+                    triangles[u] += 1;
+                    triangles[v] += 1;
+                    triangles[w] += 1;
+                }
+            }
+        }
+    }
+    triangles
+}
+
+#[test]
+fn test_random() -> Result<()> {
+    for (n, p) in [(10, 0.5), (100, 0.1), (1000, 0.02)] {
+        let graph = symmetric_random(n, p, 0);
+        let dcf = build_dcf(&graph);
+        let expected = naive_triangles(&graph);
+        for num_threads in [1, 4] {
+            let mut pl = ProgressLogger::default();
+            let triangles = algo::triangles(&graph, &dcf, Threads::Num(num_threads), Some(&mut pl));
+            assert_eq!(triangles.triangles(), expected.as_slice());
+            assert_eq!(
+                triangles.num_triangles(),
+                expected.iter().sum::<usize>() as u64 / 3
+            );
+            for node in 0..n {
+                assert_eq!(triangles.degrees()[node], graph.outdegree(node));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_hub() -> Result<()> {
+    // Node 0 has the smallest identifier but the largest degree, so edges
+    // are not oriented as in the identifier order
+    let n = 500;
+    let Left(mut graph) = symmetric_random(n, 0.05, 1);
+    for v in 1..n {
+        graph.add_arc(0, v);
+        graph.add_arc(v, 0);
+    }
+    let graph = Left(graph);
+    let expected = naive_triangles(&graph);
+    let triangles = algo::triangles(&graph, &build_dcf(&graph), Threads::Num(4), None);
+    assert_eq!(triangles.triangles(), expected.as_slice());
+    assert_eq!(triangles.degrees()[0], n - 1);
+    Ok(())
+}
+
+#[test]
+fn test_clustering() -> Result<()> {
+    // A complete graph on five nodes, with a loop on node 0
+    let mut arcs = vec![(0, 0)];
+    for u in 0..5 {
+        for v in 0..5 {
+            if u != v {
+                arcs.push((u, v));
+            }
+        }
+    }
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let triangles = algo::triangles(&graph, &build_dcf(&graph), Threads::Num(2), None);
+    assert_eq!(triangles.triangles(), &[6; 5]);
+    assert_eq!(triangles.degrees(), &[4; 5]);
+    assert_eq!(triangles.num_triangles(), 10);
+    assert_eq!(triangles.num_wedges(), 30);
+    assert_eq!(triangles.global_clustering_coefficient(), 1.0);
+    assert_eq!(triangles.average_clustering_coefficient(), 1.0);
+
+    // A triangle 0, 1, 2 with a pendant node 3 attached to 0
+    let mut arcs = vec![];
+    for (u, v) in [(0, 1), (1, 2), (0, 2), (0, 3)] {
+        arcs.push((u, v));
+        arcs.push((v, u));
+    }
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let triangles = algo::triangles(&graph, &build_dcf(&graph), Threads::Num(2), None);
+    assert_eq!(triangles.triangles(), &[1, 1, 1, 0]);
+    assert_eq!(triangles.num_triangles(), 1);
+    // Wedges: 3 centered in 0, 1 in 1, 1 in 2
+    assert_eq!(triangles.num_wedges(), 5);
+    assert_eq!(triangles.global_clustering_coefficient(), 3.0 / 5.0);
+    assert_eq!(
+        &*triangles.local_clustering_coefficients(),
+        &[1.0 / 3.0, 1.0, 1.0, 0.0]
+    );
+    assert!((triangles.average_clustering_coefficient() - (1.0 / 3.0 + 2.0) / 4.0).abs() < 1E-12);
+
+    // A star has no triangles
+    let mut arcs = vec![];
+    for v in 1..10 {
+        arcs.push((0, v));
+        arcs.push((v, 0));
+    }
+    let graph = Left(VecGraph::from_arc_list(arcs));
+    let triangles = algo::triangles(&graph, &build_dcf(&graph), Threads::Num(2), None);
+    assert_eq!(triangles.num_triangles(), 0);
+    assert_eq!(triangles.global_clustering_coefficient(), 0.0);
+    Ok(())
+}
@@ -0,0 +1,290 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::ops::ControlFlow::{self, Break, Continue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use webgraph::{
+    algo::visits::{breadth_first, depth_first},
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compute the distances from a node with a queue-based BFS.
+fn naive_distances(graph: &impl RandomAccessGraph, root: usize) -> Vec<usize> {
+    let mut dist = vec![usize::MAX; graph.num_nodes()];
+    dist[root] = 0;
+    let mut queue = VecDeque::from([root]);
+    while let Some(node) = queue.pop_front() {
+        for succ in graph.successors(node) {
+            if dist[succ] == usize::MAX {
+                dist[succ] = dist[node] + 1;
+                queue.push_back(succ);
+            }
+        }
+    }
+    dist
+}
+
+/// Compute the preorder and the postorder of a recursive DFS from all nodes.
+fn naive_dfs(graph: &impl RandomAccessGraph) -> (Vec<usize>, Vec<usize>) {
+    fn visit(
+        graph: &impl RandomAccessGraph,
+        node: usize,
+        seen: &mut [bool],
+        pre: &mut Vec<usize>,
+        post: &mut Vec<usize>,
+    ) {
+        seen[node] = true;
+        pre.push(node);
+        for succ in graph.successors(node) {
+            if !seen[succ] {
+                visit(graph, succ, seen, pre, post);
+            }
+        }
+        post.push(node);
+    }
+    let mut seen = vec![false; graph.num_nodes()];
+    let (mut pre, mut post) = (vec![], vec![]);
+    for node in 0..graph.num_nodes() {
+        if !seen[node] {
+            visit(graph, node, &mut seen, &mut pre, &mut post);
+        }
+    }
+    (pre, post)
+}
+
+#[test]
+fn test_bfs() {
+    for (n, p) in [(1, 0.0), (10, 0.2), (100, 0.02), (1000, 0.002)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        let expected = naive_distances(&graph, 0);
+        let reachable_arcs = (0..n)
+            .filter(|&node| expected[node] != usize::MAX)
+            .map(|node| graph.outdegree(node))
+            .sum::<usize>();
+
+        // Sequential
+        let mut dist = vec![usize::MAX; n];
+        let mut order = vec![];
+        let mut arcs = 0;
+        let mut frontier_sizes = 0;
+        let mut visit = breadth_first::Seq::new(&graph);
+        let _ = visit.visit(0, |event| {
+            match event {
+                breadth_first::Event::Unknown {
+                    node,
+                    pred,
+                    distance,
+                    ..
+                } => {
+                    if node != pred {
+                        assert_eq!(dist[pred] + 1, distance);
+                        arcs += 1;
+                    }
+                    dist[node] = distance;
+                    order.push(node);
+                }
+                breadth_first::Event::Known { .. } => arcs += 1,
+                breadth_first::Event::FrontierSize { size, .. } => frontier_sizes += size,
+                _ => {}
+            }
+            ControlFlow::<Infallible>::Continue(())
+        });
+        assert_eq!(dist, expected);
+        assert_eq!(arcs, reachable_arcs);
+        assert_eq!(frontier_sizes, order.len());
+        // Nodes are discovered in order of distance
+        assert!(order.windows(2).all(|w| dist[w[0]] <= dist[w[1]]));
+
+        // Parallel
+        for num_threads in [1, 4] {
+            let thread_pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            let dist = (0..n)
+                .map(|_| AtomicUsize::new(usize::MAX))
+                .collect::<Vec<_>>();
+            let arcs = AtomicUsize::new(0);
+            let mut visit = breadth_first::Par::new(&graph, 4);
+            let _ = visit.visit(
+                0,
+                |event| {
+                    match event {
+                        breadth_first::Event::Unknown { node, distance, .. } => {
+                            dist[node].store(distance, Ordering::Relaxed);
+                            if node != 0 {
+                                arcs.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                        breadth_first::Event::Known { .. } => {
+                            arcs.fetch_add(1, Ordering::Relaxed);
+                        }
+                        _ => {}
+                    }
+                    ControlFlow::<Infallible>::Continue(())
+                },
+                &thread_pool,
+            );
+            let dist = dist.into_iter().map(|d| d.into_inner()).collect::<Vec<_>>();
+            assert_eq!(dist, expected);
+            assert_eq!(arcs.into_inner(), reachable_arcs);
+        }
+    }
+}
+
+#[test]
+fn test_bfs_visit_all() {
+    let graph = Left(VecGraph::from_lender(ErdosRenyi::new(200, 0.005, 0).iter()));
+    let mut count = 0;
+    let mut roots = vec![];
+    let mut visit = breadth_first::Seq::new(&graph);
+    let _ = visit.visit_all(|event| {
+        match event {
+            breadth_first::Event::Unknown { .. } => count += 1,
+            breadth_first::Event::Init { root } => roots.push(root),
+            _ => {}
+        }
+        ControlFlow::<Infallible>::Continue(())
+    });
+    assert_eq!(count, 200);
+    assert_eq!(roots[0], 0);
+    assert!((0..200).all(|node| visit.is_visited(node)));
+
+    // Already visited nodes generate no events
+    let result = visit.visit(0, |_| Break(()));
+    assert_eq!(result, Continue(()));
+    visit.reset();
+    assert!(!visit.is_visited(0));
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let count = AtomicUsize::new(0);
+    let mut visit = breadth_first::Par::new(&graph, 1);
+    let _ = visit.visit_all(
+        |event| {
+            if let breadth_first::Event::Unknown { .. } = event {
+                count.fetch_add(1, Ordering::Relaxed);
+            }
+            ControlFlow::<Infallible>::Continue(())
+        },
+        &thread_pool,
+    );
+    assert_eq!(count.into_inner(), 200);
+}
+
+#[test]
+fn test_bfs_interruption() {
+    // A path 0 -> 1 -> ... -> 99
+    let graph = Left(VecGraph::from_arc_list((0..99).map(|i| (i, i + 1))));
+    let mut visit = breadth_first::Seq::new(&graph);
+    let result = visit.visit(0, |event| match event {
+        breadth_first::Event::Unknown {
+            node: 50, distance, ..
+        } => Break(distance),
+        _ => Continue(()),
+    });
+    assert_eq!(result, Break(50));
+    assert!(!visit.is_visited(51));
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    let mut visit = breadth_first::Par::new(&graph, 1);
+    let result = visit.visit(
+        0,
+        |event| match event {
+            breadth_first::Event::Unknown {
+                node: 50, distance, ..
+            } => Break(distance),
+            _ => Continue(()),
+        },
+        &thread_pool,
+    );
+    assert_eq!(result, Break(50));
+    assert!(!visit.is_visited(52));
+}
+
+#[test]
+fn test_dfs() {
+    for (n, p) in [(1, 0.0), (10, 0.2), (100, 0.02), (1000, 0.002)] {
+        let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, 0).iter()));
+        let (expected_pre, expected_post) = naive_dfs(&graph);
+
+        let mut pre = vec![];
+        let mut post = vec![];
+        let mut depth = vec![usize::MAX; n];
+        let mut visit = depth_first::Seq::new(&graph);
+        let _ = visit.visit_all(|event| {
+            match event {
+                depth_first::Event::Previsit {
+                    node,
+                    parent,
+                    depth: d,
+                    ..
+                } => {
+                    if node != parent {
+                        assert_eq!(depth[parent] + 1, d);
+                    } else {
+                        assert_eq!(d, 0);
+                    }
+                    depth[node] = d;
+                    pre.push(node);
+                }
+                depth_first::Event::Postvisit { node, depth: d, .. } => {
+                    assert_eq!(depth[node], d);
+                    post.push(node);
+                }
+                _ => {}
+            }
+            ControlFlow::<Infallible>::Continue(())
+        });
+        assert_eq!(pre, expected_pre);
+        assert_eq!(post, expected_post);
+    }
+}
+
+#[test]
+fn test_dfs_cycles() {
+    // Acyclic: 0 -> 1 -> 2, 0 -> 2
+    let dag = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (0, 2)]));
+    // Cyclic: 0 -> 1 -> 2 -> 0
+    let cycle = Left(VecGraph::from_arc_list([(0, 1), (1, 2), (2, 0)]));
+
+    let has_cycle = |graph: &Left<VecGraph<()>>| {
+        depth_first::Seq::new(graph)
+            .visit_all(|event| match event {
+                depth_first::Event::Revisit { on_stack: true, .. } => Break(()),
+                _ => Continue(()),
+            })
+            .is_break()
+    };
+    assert!(!has_cycle(&dag));
+    assert!(has_cycle(&cycle));
+}
+
+#[test]
+fn test_dfs_deep() {
+    // A path with a million nodes would overflow a recursive visit
+    let n = 1_000_000;
+    let graph = Left(VecGraph::from_arc_list((0..n - 1).map(|i| (i, i + 1))));
+    let mut max_depth = 0;
+    let mut visit = depth_first::Seq::new(&graph);
+    let _ = visit.visit(0, |event| {
+        if let depth_first::Event::Previsit { depth, .. } = event {
+            max_depth = max_depth.max(depth);
+        }
+        ControlFlow::<Infallible>::Continue(())
+    });
+    assert_eq!(max_depth, n - 1);
+}
@@ -0,0 +1,102 @@
+/*
+ * SPDX-FileCopyrightText: 2024 Inria
+ * SPDX-FileCopyrightText: 2024 Sebastiano Vigna
+ *
+ * SPDX-License-Identifier: Apache-2.0 OR LGPL-2.1-or-later
+ */
+
+use anyhow::Result;
+use dsi_bitstream::prelude::BE;
+use lender::*;
+use webgraph::{
+    algo::wcc,
+    graphs::{random::ErdosRenyi, vec_graph::VecGraph},
+    labels::proj::Left,
+    prelude::*,
+};
+
+/// Compute the weakly connected components with a sequential visit
+/// of the symmetrized graph, numbering them in order of their smallest node.
+fn naive_wcc(graph: &impl SequentialGraph) -> Vec<usize> {
+    let n = graph.num_nodes();
+    let mut adj = vec![vec![]; n];
+    for_!( (node, succ) in graph.iter() {
+        for s in succ {
+            adj[node].push(s);
+            adj[s].push(node);
+        }
+    });
+    let mut components = vec![usize::MAX; n];
+    let mut num_components = 0;
+    for start in 0..n {
+        if components[start] != usize::MAX {
+            continue;
+        }
+        components[start] = num_components;
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for &s in &adj[node] {
+                if components[s] == usize::MAX {
+                    components[s] = num_components;
+                    stack.push(s);
+                }
+            }
+        }
+        num_components += 1;
+    }
+    components
+}
+
+#[test]
+fn test_wcc_small() {
+    // 0 -> 1, 2 -> 1, 3 <-> 4, 5, 6 -> 6
+    let graph = Left(VecGraph::from_arc_list([
+        (0, 1),
+        (2, 1),
+        (3, 4),
+        (4, 3),
+        (6, 6),
+    ]));
+    let mut wccs = wcc(&graph, Threads::Num(2), None);
+    assert_eq!(wccs.num_components(), 4);
+    assert_eq!(wccs.components(), &[0, 0, 0, 1, 1, 2, 3]);
+    assert_eq!(wccs.sort_by_size().as_ref(), &[3, 2, 1, 1]);
+    assert_eq!(wccs.components(), &[0, 0, 0, 1, 1, 2, 3]);
+}
+
+#[test]
+fn test_wcc_random() {
+    for (n, p) in [
+        (1, 0.0),
+        (10, 0.1),
+        (100, 0.005),
+        (100, 0.01),
+        (1000, 0.001),
+    ] {
+        for seed in 0..5 {
+            let graph = Left(VecGraph::from_lender(ErdosRenyi::new(n, p, seed).iter()));
+            let expected = naive_wcc(&graph);
+            for num_threads in [1, 2, 4] {
+                let wccs = wcc(&graph, Threads::Num(num_threads), None);
+                assert_eq!(wccs.components(), expected.as_slice());
+                assert_eq!(
+                    wccs.num_components(),
+                    expected.iter().max().map_or(0, |&c| c + 1)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_wcc_cnr_2000() -> Result<()> {
+    let graph = BVGraphSeq::with_basename("tests/data/cnr-2000")
+        .endianness::<BE>()
+        .load()?;
+    let expected = naive_wcc(&graph);
+    let wccs = wcc(&graph, Threads::Num(4), None);
+    assert_eq!(wccs.components(), expected.as_slice());
+    let sizes = wccs.compute_sizes();
+    assert_eq!(sizes.iter().sum::<usize>(), graph.num_nodes());
+    Ok(())
+}